@@ -106,6 +106,20 @@ export default class CupApp extends LitElement {
       load: () => import("./views/cup-view-verify-email.js"),
       render: () => html`<cup-view-verify-email></cup-view-verify-email>`,
     },
+    {
+      path: new URLPattern({ pathname: "/accept_club_invitation" }),
+      load: () => import("./views/cup-view-accept-club-invitation.js"),
+      render: () =>
+        authed(
+          html`<cup-view-accept-club-invitation></cup-view-accept-club-invitation>`,
+        ),
+    },
+    {
+      path: new URLPattern({ pathname: "/totp" }),
+      load: () => import("./views/cup-view-totp-enrollment.js"),
+      render: () =>
+        authed(html`<cup-view-totp-enrollment></cup-view-totp-enrollment>`),
+    },
     {
       path: new URLPattern({ pathname: "/request_password_reset" }),
       load: () => import("./views/cup-view-request-password-reset.js"),
@@ -16,20 +16,48 @@ type Judge = { birthdate: Date } & Omit<
   "birthdate"
 >;
 
-const ALL_CATEGORIES = [
-  "n_ew_u15",
-  "n_ew_o15",
-  "n_em_u15",
-  "n_em_o15",
-  "n_p_u15",
-  "n_p_o15",
-  // "s_e_u15",
-  // "s_e_o15",
-  // "s_p_u15",
-  // "s_p_o15",
+type Category = components["schemas"]["Category"];
+type JudgeRole = components["schemas"]["JudgeRole"];
+
+const ALL_JUDGE_ROLES: [JudgeRole, string][] = [
+  ["performance", "P"],
+  ["technique", "T"],
+  ["dismount", "A"],
 ];
 
-const ALL_JUDGE_PARTS = ["p", "t", "a"];
+type Qualification = "judge" | "hosp" | "none";
+
+const getQualification = (
+  judge: MaybeNewJudge,
+  category: string,
+  role: JudgeRole,
+): Qualification => {
+  const found = judge.qualifications.find(
+    (q) => q.category === category && q.role === role,
+  );
+  if (!found) {
+    return "none";
+  }
+  return found.hospitation ? "hosp" : "judge";
+};
+
+const setQualification = (
+  judge: MaybeNewJudge,
+  category: string,
+  role: JudgeRole,
+  value: Qualification,
+) => {
+  judge.qualifications = judge.qualifications.filter(
+    (q) => q.category !== category || q.role !== role,
+  );
+  if (value !== "none") {
+    judge.qualifications.push({
+      category,
+      role,
+      hospitation: value === "hosp",
+    });
+  }
+};
 
 type MaybeNewJudge = Omit<Omit<Judge, "id">, "club_id">;
 
@@ -254,145 +282,37 @@ export default class CupClubJudgeManager extends LitElement {
     args: () => [this.club?.id],
   });
 
+  categories = new Task(this, {
+    task: async () => {
+      const resp = await client.GET("/api/query/list_categories");
+      if (resp.error) {
+        throw new Error((resp.error as any).message);
+      }
+      return resp.data;
+    },
+    args: () => [],
+  });
+
   @state() judgeEdits = new Map<string, Judge>();
   @state() addJudge: MaybeNewJudge = {
     firstname: "",
     lastname: "",
     mail: "",
     birthdate: new Date(),
-    n_em_u15_p: false,
-    n_em_u15_p_hosp: false,
-    n_em_u15_t: false,
-    n_em_u15_t_hosp: false,
-    n_em_u15_a: false,
-    n_em_u15_a_hosp: false,
-    n_em_o15_p: false,
-    n_em_o15_p_hosp: false,
-    n_em_o15_t: false,
-    n_em_o15_t_hosp: false,
-    n_em_o15_a: false,
-    n_em_o15_a_hosp: false,
-    n_ew_u15_p: false,
-    n_ew_u15_p_hosp: false,
-    n_ew_u15_t: false,
-    n_ew_u15_t_hosp: false,
-    n_ew_u15_a: false,
-    n_ew_u15_a_hosp: false,
-    n_ew_o15_p: false,
-    n_ew_o15_p_hosp: false,
-    n_ew_o15_t: false,
-    n_ew_o15_t_hosp: false,
-    n_ew_o15_a: false,
-    n_ew_o15_a_hosp: false,
-    n_p_u15_p: false,
-    n_p_u15_p_hosp: false,
-    n_p_u15_t: false,
-    n_p_u15_t_hosp: false,
-    n_p_u15_a: false,
-    n_p_u15_a_hosp: false,
-    n_p_o15_p: false,
-    n_p_o15_p_hosp: false,
-    n_p_o15_t: false,
-    n_p_o15_t_hosp: false,
-    n_p_o15_a: false,
-    n_p_o15_a_hosp: false,
-    s_e_u15_p: false,
-    s_e_u15_p_hosp: false,
-    s_e_u15_t: false,
-    s_e_u15_t_hosp: false,
-    s_e_u15_a: false,
-    s_e_u15_a_hosp: false,
-    s_e_o15_p: false,
-    s_e_o15_p_hosp: false,
-    s_e_o15_t: false,
-    s_e_o15_t_hosp: false,
-    s_e_o15_a: false,
-    s_e_o15_a_hosp: false,
-    s_p_u15_p: false,
-    s_p_u15_p_hosp: false,
-    s_p_u15_t: false,
-    s_p_u15_t_hosp: false,
-    s_p_u15_a: false,
-    s_p_u15_a_hosp: false,
-    s_p_o15_p: false,
-    s_p_o15_p_hosp: false,
-    s_p_o15_t: false,
-    s_p_o15_t_hosp: false,
-    s_p_o15_a: false,
-    s_p_o15_a_hosp: false,
+    qualifications: [],
   };
 
   @state() addJudgeMode = false;
 
   get judgingCount() {
-    return this.judges.value?.filter(
-      (judge) =>
-        judge.n_em_u15_p ||
-        judge.n_em_u15_t ||
-        judge.n_em_u15_a ||
-        judge.n_em_o15_p ||
-        judge.n_em_o15_t ||
-        judge.n_em_o15_a ||
-        judge.n_ew_u15_p ||
-        judge.n_ew_u15_t ||
-        judge.n_ew_u15_a ||
-        judge.n_ew_o15_p ||
-        judge.n_ew_o15_t ||
-        judge.n_ew_o15_a ||
-        judge.n_p_u15_p ||
-        judge.n_p_u15_t ||
-        judge.n_p_u15_a ||
-        judge.n_p_o15_p ||
-        judge.n_p_o15_t ||
-        judge.n_p_o15_a ||
-        judge.s_e_u15_p ||
-        judge.s_e_u15_t ||
-        judge.s_e_u15_a ||
-        judge.s_e_o15_p ||
-        judge.s_e_o15_t ||
-        judge.s_e_o15_a ||
-        judge.s_p_u15_p ||
-        judge.s_p_u15_t ||
-        judge.s_p_u15_a ||
-        judge.s_p_o15_p ||
-        judge.s_p_o15_t ||
-        judge.s_p_o15_a,
+    return this.judges.value?.filter((judge) =>
+      judge.qualifications.some((q) => !q.hospitation),
     ).length;
   }
 
   get hospCount() {
-    return this.judges.value?.filter(
-      (judge) =>
-        judge.n_em_u15_p_hosp ||
-        judge.n_em_u15_t_hosp ||
-        judge.n_em_u15_a_hosp ||
-        judge.n_em_o15_p_hosp ||
-        judge.n_em_o15_t_hosp ||
-        judge.n_em_o15_a_hosp ||
-        judge.n_ew_u15_p_hosp ||
-        judge.n_ew_u15_t_hosp ||
-        judge.n_ew_u15_a_hosp ||
-        judge.n_ew_o15_p_hosp ||
-        judge.n_ew_o15_t_hosp ||
-        judge.n_ew_o15_a_hosp ||
-        judge.n_p_u15_p_hosp ||
-        judge.n_p_u15_t_hosp ||
-        judge.n_p_u15_a_hosp ||
-        judge.n_p_o15_p_hosp ||
-        judge.n_p_o15_t_hosp ||
-        judge.n_p_o15_a_hosp ||
-        judge.s_e_u15_p_hosp ||
-        judge.s_e_u15_t_hosp ||
-        judge.s_e_u15_a_hosp ||
-        judge.s_e_o15_p_hosp ||
-        judge.s_e_o15_t_hosp ||
-        judge.s_e_o15_a_hosp ||
-        judge.s_p_u15_p_hosp ||
-        judge.s_p_u15_t_hosp ||
-        judge.s_p_u15_a_hosp ||
-        judge.s_p_o15_p_hosp ||
-        judge.s_p_o15_t_hosp ||
-        judge.s_p_o15_a_hosp,
+    return this.judges.value?.filter((judge) =>
+      judge.qualifications.some((q) => q.hospitation),
     ).length;
   }
 
@@ -403,9 +323,9 @@ export default class CupClubJudgeManager extends LitElement {
           ? nothing
           : html`
             <p>
-              Die Altersgruppen sind nur in U15 und 15+ geteilt. Eine genauere
-              Einteilung wird nach Meldeschluss vorgenommen. Wir achten darauf,
-              dass alle Judges mit ausreichend Abstand zur eigenen Kür werten.
+              Wähle für jede Kategorie aus, in welchen Teilen gewertet werden
+              kann. Wir achten darauf, dass alle Judges mit ausreichend Abstand
+              zur eigenen Kür werten.
             </p>
             <p>
               Die Auswahl besteht aus "-" (Nichts), "✔️" (Judge) und "👀" (Judge
@@ -435,32 +355,16 @@ export default class CupClubJudgeManager extends LitElement {
       <table>
         <thead>
           <tr>
-            <th rowspan="4">Vorname</th>
-            <th rowspan="4">Nachname</th>
-            <th rowspan="4">Email</th>
-            <th rowspan="4">Geburtstag</th>
-            <th colspan="6">Nachwuchscup</th>
-            <th colspan="4" hidden>Sonderpokal</th>
-            <th rowspan="4"></th>
-          </tr>
-          <tr>
-            <th colspan="2">Einzel W</th>
-            <th colspan="2">Einzel M</th>
-            <th colspan="2">Paar</th>
-            <th hidden colspan="2">Einzel</th>
-            <th hidden colspan="2">Paar</th>
-          </tr>
-          <tr>
-            <th>U15</th>
-            <th>15+</th>
-            <th>U15</th>
-            <th>15+</th>
-            <th>U15</th>
-            <th>15+</th>
-            <th hidden>U15</th>
-            <th hidden>15+</th>
-            <th hidden>U15</th>
-            <th hidden>15+</th>
+            <th>Vorname</th>
+            <th>Nachname</th>
+            <th>Email</th>
+            <th>Geburtstag</th>
+            ${repeat(
+              this.categories.value ?? [],
+              (category) => category.name,
+              (category) => html`<th>${categoryName(category)}</th>`,
+            )}
+            <th></th>
           </tr>
         </thead>
         <tbody>
@@ -509,75 +413,7 @@ export default class CupClubJudgeManager extends LitElement {
                         @input=${this.updateAddJudgeBirthdate}
                     /></label>
                   </td>
-                  ${repeat(
-                    ALL_CATEGORIES,
-                    (x) => x,
-                    (category) => {
-                      const [cup, part, group] = category.split("_");
-                      let readableName = "";
-                      if (cup == "n") {
-                        readableName = "Nachwuchs";
-                      } else if (cup == "s") {
-                        readableName = "Sonderpokal";
-                      }
-                      if (part == "ew") {
-                        readableName += " Einzel W";
-                      } else if (part == "em") {
-                        readableName += " Einzel M";
-                      } else if (part == "p") {
-                        readableName += " Paar";
-                      } else {
-                        readableName += " Einzel";
-                      }
-                      if (group == "u15") {
-                        readableName += " U15";
-                      } else {
-                        readableName += " 15+";
-                      }
-                      return html`<td class="juryselect">
-                        <h6>${readableName}</h6>
-                        ${repeat(
-                          ALL_JUDGE_PARTS,
-                          (x) => x,
-                          (type) => {
-                            const isJudge = (this.addJudge as any)[
-                              category + "_" + type
-                            ];
-                            const isHosp = (this.addJudge as any)[
-                              category + "_" + type + "_hosp"
-                            ];
-                            return html` <label
-                              ><span class="type-label"
-                                >${type.toUpperCase()}</span
-                              ><select
-                                @input=${
-                                  // eslint-disable-next-line lit/no-template-arrow
-                                  (e: InputEvent) =>
-                                    this.updateAddJudgeCategory(
-                                      e,
-                                      category + "_" + type,
-                                    )
-                                }
-                              >
-                                <option value="judge" ?selected=${isJudge}>
-                                  ✔️
-                                </option>
-                                <option value="hosp" ?selected=${isHosp}>
-                                  👀
-                                </option>
-                                <option
-                                  value="none"
-                                  ?selected=${!isJudge && !isHosp}
-                                >
-                                  -
-                                </option>
-                              </select></label
-                            >`;
-                          },
-                        )}
-                      </td>`;
-                    },
-                  )}
+                  ${this.renderQualificationCells(this.addJudge, true)}
                   <td class="actionCol">
                     <button
                       class="green material-icon"
@@ -669,76 +505,7 @@ export default class CupClubJudgeManager extends LitElement {
                             }
                         /></label>
                       </td>
-                      ${repeat(
-                        ALL_CATEGORIES,
-                        (x) => x,
-                        (category) => {
-                          const [cup, part, group] = category.split("_");
-                          let readableName = "";
-                          if (cup == "n") {
-                            readableName = "Nachwuchs";
-                          } else if (cup == "s") {
-                            readableName = "Sonderpokal";
-                          }
-                          if (part == "ew") {
-                            readableName += " Einzel W";
-                          } else if (part == "em") {
-                            readableName += " Einzel M";
-                          } else if (part == "p") {
-                            readableName += " Paar";
-                          } else {
-                            readableName += " Einzel";
-                          }
-                          if (group == "u15") {
-                            readableName += " U15";
-                          } else {
-                            readableName += " 15+";
-                          }
-                          return html`<td class="juryselect">
-                            <h6>${readableName}</h6>
-                            ${repeat(
-                              ALL_JUDGE_PARTS,
-                              (x) => x,
-                              (type) => {
-                                const isJudge = (judge as any)[
-                                  category + "_" + type
-                                ];
-                                const isHosp = (judge as any)[
-                                  category + "_" + type + "_hosp"
-                                ];
-                                return html` <label
-                                  ><span class="type-label"
-                                    >${type.toUpperCase()}</span
-                                  ><select
-                                    @input=${
-                                      // eslint-disable-next-line lit/no-template-arrow
-                                      (e: InputEvent) =>
-                                        this.updateEditJudgeCategory(
-                                          editJudge,
-                                          e,
-                                          category + "_" + type,
-                                        )
-                                    }
-                                  >
-                                    <option value="judge" ?selected=${isJudge}>
-                                      ✔️
-                                    </option>
-                                    <option value="hosp" ?selected=${isHosp}>
-                                      👀
-                                    </option>
-                                    <option
-                                      value="none"
-                                      ?selected=${!isJudge && !isHosp}
-                                    >
-                                      -
-                                    </option>
-                                  </select></label
-                                >`;
-                              },
-                            )}
-                          </td>`;
-                        },
-                      )}
+                      ${this.renderQualificationCells(editJudge, true)}
                       <td class="actionCol">
                         <button
                           class="green material-icon"
@@ -774,53 +541,7 @@ export default class CupClubJudgeManager extends LitElement {
                         <label><span>Geburtstag</span></label>
                         ${judge.birthdate.toLocaleDateString()}
                       </td>
-                      ${repeat(
-                        ALL_CATEGORIES,
-                        (x) => x,
-                        (category) => {
-                          const [cup, part, group] = category.split("_");
-                          let readableName = "";
-                          if (cup == "n") {
-                            readableName = "Nachwuchs";
-                          } else if (cup == "s") {
-                            readableName = "Sonderpokal";
-                          }
-                          if (part == "ew") {
-                            readableName += " Einzel W";
-                          } else if (part == "em") {
-                            readableName += " Einzel M";
-                          } else if (part == "p") {
-                            readableName += " Paar";
-                          } else {
-                            readableName += " Einzel";
-                          }
-                          if (group == "u15") {
-                            readableName += " U15";
-                          } else {
-                            readableName += " 15+";
-                          }
-                          return html`<td class="juryselect">
-                            <h6>${readableName}</h6>
-                            ${repeat(
-                              ALL_JUDGE_PARTS,
-                              (x) => x,
-                              (type) => {
-                                const isJudge = (judge as any)[
-                                  category + "_" + type
-                                ];
-                                const isHosp = (judge as any)[
-                                  category + "_" + type + "_hosp"
-                                ];
-                                return html`<div class="type">
-                                  <span class="type-label"
-                                    >${type.toUpperCase()}</span
-                                  >${isJudge ? "✔️" : isHosp ? "👀" : "-"}
-                                </div>`;
-                              },
-                            )}
-                          </td>`;
-                        },
-                      )}
+                      ${this.renderQualificationCells(judge, false)}
                       <td class="actionCol">
                         ${
                           this.systemStatus?.can_register_judge ||
@@ -855,8 +576,64 @@ export default class CupClubJudgeManager extends LitElement {
       </table> `;
   }
 
+  renderQualificationCells(judge: MaybeNewJudge, editable: boolean) {
+    return repeat(
+      this.categories.value ?? [],
+      (category) => category.name,
+      (category) =>
+        html`<td class="juryselect">
+          <h6>${categoryName(category)}</h6>
+          ${ALL_JUDGE_ROLES.map(([role, label]) => {
+            const qualification = getQualification(judge, category.name, role);
+            return editable
+              ? html`<label
+                  ><span class="type-label">${label}</span
+                  ><select
+                    @input=${
+                      // eslint-disable-next-line lit/no-template-arrow
+                      (e: InputEvent) =>
+                        this.updateJudgeQualification(
+                          judge,
+                          e,
+                          category.name,
+                          role,
+                        )
+                    }
+                  >
+                    <option
+                      value="judge"
+                      ?selected=${qualification === "judge"}
+                    >
+                      ✔️
+                    </option>
+                    <option value="hosp" ?selected=${qualification === "hosp"}>
+                      👀
+                    </option>
+                    <option value="none" ?selected=${qualification === "none"}>
+                      -
+                    </option>
+                  </select></label
+                >`
+              : html`<div class="type">
+                  <span class="type-label">${label}</span
+                  >${
+                    qualification === "judge"
+                      ? "✔️"
+                      : qualification === "hosp"
+                        ? "👀"
+                        : "-"
+                  }
+                </div>`;
+          })}
+        </td>`,
+    );
+  }
+
   enableJudgeEdit(judge: Judge) {
-    this.judgeEdits.set(judge.id, { ...judge });
+    this.judgeEdits.set(judge.id, {
+      ...judge,
+      qualifications: [...judge.qualifications],
+    });
     this.requestUpdate();
   }
 
@@ -870,8 +647,6 @@ export default class CupClubJudgeManager extends LitElement {
       return;
     }
 
-    console.log(judge);
-
     const resp = await client.POST("/api/command/edit_club_judge", {
       body: {
         ...judge,
@@ -926,10 +701,18 @@ export default class CupClubJudgeManager extends LitElement {
     this.requestUpdate();
   }
 
-  updateEditJudgeCategory(judge: Judge, e: InputEvent, category: string) {
-    (judge as any)[category] = (e.target as HTMLSelectElement).value == "judge";
-    (judge as any)[category + "_hosp"] =
-      (e.target as HTMLSelectElement).value == "hosp";
+  updateJudgeQualification(
+    judge: MaybeNewJudge,
+    e: InputEvent,
+    category: string,
+    role: JudgeRole,
+  ) {
+    setQualification(
+      judge,
+      category,
+      role,
+      (e.target as HTMLSelectElement).value as Qualification,
+    );
     this.requestUpdate();
   }
 
@@ -953,14 +736,6 @@ export default class CupClubJudgeManager extends LitElement {
     this.requestUpdate();
   }
 
-  updateAddJudgeCategory(e: InputEvent, category: string) {
-    (this.addJudge as any)[category] =
-      (e.target as HTMLSelectElement).value == "judge";
-    (this.addJudge as any)[category + "_hosp"] =
-      (e.target as HTMLSelectElement).value == "hosp";
-    this.requestUpdate();
-  }
-
   async commitAddJudge() {
     if (!this.club || !validateJudge(this.addJudge)) {
       return;
@@ -988,66 +763,7 @@ export default class CupClubJudgeManager extends LitElement {
       lastname: "",
       mail: "",
       birthdate: new Date(),
-      n_em_u15_p: false,
-      n_em_u15_p_hosp: false,
-      n_em_u15_t: false,
-      n_em_u15_t_hosp: false,
-      n_em_u15_a: false,
-      n_em_u15_a_hosp: false,
-      n_em_o15_p: false,
-      n_em_o15_p_hosp: false,
-      n_em_o15_t: false,
-      n_em_o15_t_hosp: false,
-      n_em_o15_a: false,
-      n_em_o15_a_hosp: false,
-      n_ew_u15_p: false,
-      n_ew_u15_p_hosp: false,
-      n_ew_u15_t: false,
-      n_ew_u15_t_hosp: false,
-      n_ew_u15_a: false,
-      n_ew_u15_a_hosp: false,
-      n_ew_o15_p: false,
-      n_ew_o15_p_hosp: false,
-      n_ew_o15_t: false,
-      n_ew_o15_t_hosp: false,
-      n_ew_o15_a: false,
-      n_ew_o15_a_hosp: false,
-      n_p_u15_p: false,
-      n_p_u15_p_hosp: false,
-      n_p_u15_t: false,
-      n_p_u15_t_hosp: false,
-      n_p_u15_a: false,
-      n_p_u15_a_hosp: false,
-      n_p_o15_p: false,
-      n_p_o15_p_hosp: false,
-      n_p_o15_t: false,
-      n_p_o15_t_hosp: false,
-      n_p_o15_a: false,
-      n_p_o15_a_hosp: false,
-      s_e_u15_p: false,
-      s_e_u15_p_hosp: false,
-      s_e_u15_t: false,
-      s_e_u15_t_hosp: false,
-      s_e_u15_a: false,
-      s_e_u15_a_hosp: false,
-      s_e_o15_p: false,
-      s_e_o15_p_hosp: false,
-      s_e_o15_t: false,
-      s_e_o15_t_hosp: false,
-      s_e_o15_a: false,
-      s_e_o15_a_hosp: false,
-      s_p_u15_p: false,
-      s_p_u15_p_hosp: false,
-      s_p_u15_t: false,
-      s_p_u15_t_hosp: false,
-      s_p_u15_a: false,
-      s_p_u15_a_hosp: false,
-      s_p_o15_p: false,
-      s_p_o15_p_hosp: false,
-      s_p_o15_t: false,
-      s_p_o15_t_hosp: false,
-      s_p_o15_a: false,
-      s_p_o15_a_hosp: false,
+      qualifications: [],
     };
     this.addJudgeMode = false;
   }
@@ -1076,3 +792,6 @@ const validateJudge = (judge: MaybeNewJudge) => {
   }
   return true;
 };
+
+const categoryName = (category: Category) =>
+  category.description ?? category.name;
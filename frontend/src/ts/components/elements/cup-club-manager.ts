@@ -5,6 +5,7 @@ import { type Club, clubContext } from "../../contexts/club";
 import "./cup-club-starter-manager.js";
 import "./cup-club-judge-manager.js";
 import "./cup-club-act-manager.js";
+import "./cup-club-member-manager.js";
 import { Task } from "@lit/task";
 import { client } from "../../apiClient";
 import { getStarterPrice } from "./cup-club-starter-manager.js";
@@ -154,6 +155,8 @@ export default class CupClubManager extends LitElement {
       <hr />
       <cup-club-judge-manager
         ?admin-mode=${this.adminMode}
-      ></cup-club-judge-manager> `;
+      ></cup-club-judge-manager>
+      <hr />
+      <cup-club-member-manager></cup-club-member-manager> `;
   }
 }
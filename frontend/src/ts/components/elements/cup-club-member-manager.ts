@@ -0,0 +1,351 @@
+import { consume } from "@lit/context";
+import { Task } from "@lit/task";
+import { css, html, LitElement, nothing } from "lit";
+import { customElement, state } from "lit/decorators.js";
+import { repeat } from "lit/directives/repeat.js";
+import { client, type components } from "../../apiClient";
+import { type Club, clubContext } from "../../contexts/club";
+import { type User, userContext } from "../../contexts/user";
+
+type ClubRole = components["schemas"]["ClubRole"];
+type ClubMember = components["schemas"]["ClubMember"];
+
+const ALL_ROLES: [ClubRole, string][] = [
+  ["read_only", "Lesezugriff"],
+  ["trainer", "Trainer*in"],
+  ["owner", "Verantwortliche*r"],
+];
+
+const roleName = (role: ClubRole) =>
+  ALL_ROLES.find(([r]) => r === role)?.[1] ?? role;
+
+@customElement("cup-club-member-manager")
+export default class CupClubMemberManager extends LitElement {
+  static override styles = css`
+    * {
+      margin: 0;
+      padding: 0;
+      box-sizing: border-box;
+    }
+
+    .material-icon {
+      font-family: "Material Symbols Outlined";
+      font-weight: normal;
+      font-style: normal;
+      font-size: 24px;
+      line-height: 1;
+      letter-spacing: normal;
+      text-transform: none;
+      display: inline-block;
+      white-space: nowrap;
+      word-wrap: normal;
+      direction: ltr;
+      font-feature-settings: "liga";
+      -webkit-font-smoothing: antialiased;
+    }
+
+    h4,
+    h5,
+    p {
+      margin-top: 1em;
+      margin-bottom: 0.5em;
+    }
+
+    .green {
+      background: #009036;
+      color: #fff;
+    }
+
+    .red {
+      background: #e2001a;
+      color: #fff;
+    }
+
+    table {
+      width: 100%;
+      border-collapse: collapse;
+    }
+
+    tr {
+      border: 0.1rem solid #ddd;
+    }
+
+    tbody tr:nth-child(odd) {
+      background: #f8f8f8;
+    }
+
+    tbody tr:hover {
+      background: #eee;
+    }
+
+    th,
+    td {
+      padding: 0.25rem;
+      border: 0.1rem solid #ddd;
+      text-align: left;
+    }
+
+    form {
+      display: flex;
+      gap: 0.5rem;
+      flex-wrap: wrap;
+    }
+
+    input,
+    select,
+    button {
+      padding: 0.25rem;
+    }
+
+    button {
+      cursor: pointer;
+      padding: 0.25rem 0.75rem;
+      border: none;
+      border-radius: 100vh;
+    }
+
+    @media print {
+      :host {
+        display: none;
+      }
+    }
+  `;
+
+  @consume({ context: clubContext, subscribe: true }) club: Club | null = null;
+  @consume({ context: userContext, subscribe: true }) user: User | null = null;
+
+  @state() inviteEmail = "";
+  @state() inviteRole: ClubRole = "trainer";
+
+  members = new Task(this, {
+    task: async ([clubId]) => {
+      if (!clubId) {
+        return null;
+      }
+      const resp = await client.GET("/api/query/list_club_members", {
+        params: { query: { club_id: clubId } },
+      });
+      if (resp.error) {
+        throw new Error((resp.error as any).message);
+      }
+      return resp.data;
+    },
+    args: () => [this.club?.id],
+  });
+
+  get isOwner() {
+    return (
+      this.user?.is_admin ||
+      this.user?.memberships.some(
+        (m) => m.club_id === this.club?.id && m.role === "owner",
+      )
+    );
+  }
+
+  override render() {
+    return html`<h4>Mitglieder</h4>
+      <p>
+        Mitglieder können die Anmeldung des Vereins einsehen. Trainer*innen
+        melden Starter und Judges an, Verantwortliche verwalten zusätzlich die
+        Mitglieder.
+      </p>
+      ${this.members.render({
+        complete: (members) =>
+          members
+            ? html`<table>
+                  <thead>
+                    <tr>
+                      <th>Name</th>
+                      <th>Email</th>
+                      <th>Rolle</th>
+                      <th></th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    ${repeat(
+                      members.members,
+                      (member) => member.user_id,
+                      (member) => this.renderMember(member),
+                    )}
+                  </tbody>
+                </table>
+                ${
+                  this.isOwner
+                    ? html`<h5>Offene Einladungen</h5>
+                        ${
+                          members.invitations.length === 0
+                            ? html`<p>Keine offenen Einladungen.</p>`
+                            : html`<table>
+                                <thead>
+                                  <tr>
+                                    <th>Email</th>
+                                    <th>Rolle</th>
+                                    <th>Eingeladen von</th>
+                                    <th>Eingeladen am</th>
+                                  </tr>
+                                </thead>
+                                <tbody>
+                                  ${repeat(
+                                    members.invitations,
+                                    (invitation) => invitation.email,
+                                    (invitation) =>
+                                      html`<tr>
+                                        <td>${invitation.email}</td>
+                                        <td>${roleName(invitation.role)}</td>
+                                        <td>${invitation.invited_by ?? "-"}</td>
+                                        <td>
+                                          ${new Date(
+                                            invitation.created_at,
+                                          ).toLocaleDateString()}
+                                        </td>
+                                      </tr>`,
+                                  )}
+                                </tbody>
+                              </table>`
+                        }
+                        <h5>Mitglied einladen</h5>
+                        <form @submit=${this.invite}>
+                          <input
+                            type="email"
+                            placeholder="hallo@example.com"
+                            required
+                            .value=${this.inviteEmail}
+                            @input=${this.updateInviteEmail}
+                          />
+                          <select @input=${this.updateInviteRole}>
+                            ${ALL_ROLES.map(
+                              ([role, name]) =>
+                                html`<option
+                                  value=${role}
+                                  ?selected=${role === this.inviteRole}
+                                >
+                                  ${name}
+                                </option>`,
+                            )}
+                          </select>
+                          <button type="submit" class="green">Einladen</button>
+                        </form>`
+                    : nothing
+                }`
+            : nothing,
+        error: (error) => html`<p>${error}</p>`,
+        pending: () => html`<p>Lädt Mitglieder...</p>`,
+      })}`;
+  }
+
+  renderMember(member: ClubMember) {
+    const isSelf = member.user_id === this.user?.id;
+    return html`<tr>
+      <td>${member.name}</td>
+      <td>${member.email}</td>
+      <td>
+        ${
+          this.isOwner
+            ? html`<select
+                @input=${
+                  // eslint-disable-next-line lit/no-template-arrow
+                  (e: InputEvent) => this.setRole(member, e)
+                }
+              >
+                ${ALL_ROLES.map(
+                  ([role, name]) =>
+                    html`<option
+                      value=${role}
+                      ?selected=${role === member.role}
+                    >
+                      ${name}
+                    </option>`,
+                )}
+              </select>`
+            : roleName(member.role)
+        }
+      </td>
+      <td>
+        ${
+          isSelf || this.isOwner
+            ? html`<button
+                class="red material-icon"
+                title=${isSelf ? "Verein verlassen" : "Entfernen"}
+                @click=${
+                  // eslint-disable-next-line lit/no-template-arrow
+                  () => this.removeMember(member)
+                }
+              >
+                ${isSelf ? "logout" : "delete"}
+              </button>`
+            : nothing
+        }
+      </td>
+    </tr>`;
+  }
+
+  updateInviteEmail(e: InputEvent) {
+    this.inviteEmail = (e.target as HTMLInputElement).value;
+  }
+
+  updateInviteRole(e: InputEvent) {
+    this.inviteRole = (e.target as HTMLSelectElement).value as ClubRole;
+  }
+
+  async invite(e: SubmitEvent) {
+    e.preventDefault();
+    if (!this.club) {
+      return;
+    }
+    const resp = await client.POST("/api/command/invite_club_member", {
+      body: {
+        club_id: this.club.id,
+        email: this.inviteEmail,
+        role: this.inviteRole,
+      },
+    });
+    if (resp.error) {
+      alert("Fehler beim Einladen: " + (resp.error as any).message);
+      return;
+    }
+    this.inviteEmail = "";
+    this.members.run();
+  }
+
+  async setRole(member: ClubMember, e: InputEvent) {
+    if (!this.club) {
+      return;
+    }
+    const resp = await client.POST("/api/command/set_club_member_role", {
+      body: {
+        club_id: this.club.id,
+        user_id: member.user_id,
+        role: (e.target as HTMLSelectElement).value as ClubRole,
+      },
+    });
+    if (resp.error) {
+      alert("Fehler beim Speichern: " + (resp.error as any).message);
+    }
+    this.members.run();
+  }
+
+  async removeMember(member: ClubMember) {
+    if (!this.club) {
+      return;
+    }
+    const question =
+      member.user_id === this.user?.id
+        ? `Sicher, dass du ${this.club.name} verlassen willst?`
+        : `Sicher, dass du ${member.name} aus dem Verein entfernen willst?`;
+    if (!confirm(question)) {
+      return;
+    }
+    const resp = await client.POST("/api/command/remove_club_member", {
+      body: { club_id: this.club.id, user_id: member.user_id },
+    });
+    if (resp.error) {
+      alert("Fehler beim Entfernen: " + (resp.error as any).message);
+      return;
+    }
+    if (member.user_id === this.user?.id) {
+      window.location.href = "/";
+      return;
+    }
+    this.members.run();
+  }
+}
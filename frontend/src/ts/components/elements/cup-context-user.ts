@@ -4,6 +4,7 @@ import { customElement, state } from "lit/decorators.js";
 import { client } from "../../apiClient.js";
 import { type User, userContext } from "../../contexts/user.js";
 import "./cup-centered-icon-box.js";
+import "./cup-totp-enrollment.js";
 
 @customElement("cup-context-user")
 export default class CupContextUser extends LitElement {
@@ -100,6 +101,53 @@ export default class CupContextUser extends LitElement {
   @state() error?: string;
 
   override render() {
+    if (this.user?.second_factor === "required") {
+      return html`<cup-centered-icon-box>
+        <form @submit=${this.verifyTotp}>
+          <h1>NRW Freestyle Cup 2026</h1>
+          <h2>Zwei-Faktor-Anmeldung</h2>
+          <p>
+            Gib den Code aus deiner Authenticator App oder einen
+            Wiederherstellungscode ein.
+          </p>
+          ${this.error ? html`<p id="error">${this.error}</p>` : nothing}
+          <label>
+            Code
+            <input
+              type="text"
+              name="code"
+              placeholder="123456"
+              autocomplete="one-time-code"
+              required
+              id="code"
+            />
+          </label>
+          <div id="action-buttons">
+            <button type="submit">
+              <i class="material-icon">login</i> Anmelden
+            </button>
+            <button type="button" id="pw-reset" @click=${this.logout}>
+              <i class="material-icon">logout</i> Abbrechen
+            </button>
+          </div>
+        </form>
+      </cup-centered-icon-box>`;
+    }
+    if (this.user?.second_factor === "enrollment_required") {
+      return html`<cup-centered-icon-box>
+        <form>
+          <h1>NRW Freestyle Cup 2026</h1>
+          <h2>Zwei-Faktor-Anmeldung einrichten</h2>
+          <p>Als Admin musst du die Zwei-Faktor-Anmeldung einrichten.</p>
+          <cup-totp-enrollment @login=${this.getLogin}></cup-totp-enrollment>
+          <div id="action-buttons">
+            <button type="button" id="pw-reset" @click=${this.logout}>
+              <i class="material-icon">logout</i> Abbrechen
+            </button>
+          </div>
+        </form>
+      </cup-centered-icon-box>`;
+    }
     return this.user
       ? html`<slot @login=${this.getLogin} @logout=${this.getLogin}></slot>`
       : html`<cup-centered-icon-box>
@@ -169,10 +217,44 @@ export default class CupContextUser extends LitElement {
       this.error = "Login failed.";
       return;
     }
+    if (
+      response.data.second_factor === "required" ||
+      response.data.second_factor === "enrollment_required"
+    ) {
+      this.error = undefined;
+      this.getLogin();
+      return;
+    }
     window.location.reload();
     this.getLogin();
   }
 
+  async verifyTotp(event: SubmitEvent) {
+    event.preventDefault();
+    const formData = new FormData(event.target as HTMLFormElement);
+    const response = await client.POST("/api/command/verify_totp", {
+      body: { code: formData.get("code") as string },
+    });
+    if (response.response.status === 401) {
+      this.error = "Der Code ist falsch.";
+      return;
+    }
+    if (response.response.status === 429) {
+      this.error = "Zu viele Versuche, bitte warte etwas.";
+      return;
+    }
+    if (response.error) {
+      this.error = "Login failed.";
+      return;
+    }
+    window.location.reload();
+  }
+
+  async logout() {
+    await client.POST("/api/command/logout");
+    this.getLogin();
+  }
+
   async getLogin() {
     const response = await fetch("/api/query/whoami");
     if (!response.ok) {
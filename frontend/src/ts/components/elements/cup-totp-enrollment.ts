@@ -0,0 +1,147 @@
+import { css, html, LitElement, nothing } from "lit";
+import { customElement, state } from "lit/decorators.js";
+import { repeat } from "lit/directives/repeat.js";
+import { client, type components } from "../../apiClient.js";
+
+type Enrollment = components["schemas"]["StartTotpEnrollmentResponse"];
+
+@customElement("cup-totp-enrollment")
+export default class CupTotpEnrollment extends LitElement {
+  static override styles = css`
+    * {
+      margin: 0;
+      padding: 0;
+      box-sizing: border-box;
+    }
+
+    form,
+    div {
+      display: flex;
+      flex-direction: column;
+      gap: 1rem;
+    }
+
+    input,
+    button,
+    a {
+      padding: 0.5rem;
+    }
+
+    button {
+      background: #009036;
+      color: #fff;
+      border: none;
+      border-radius: 0.5rem;
+      cursor: pointer;
+    }
+
+    label {
+      display: flex;
+      flex-direction: column;
+      gap: 0.5rem;
+    }
+
+    code {
+      word-break: break-all;
+    }
+
+    ul {
+      list-style: none;
+      font-family: monospace;
+      font-size: 1.2rem;
+    }
+
+    #error {
+      border: 0.1rem solid #e2001a;
+      color: #e2001a;
+      padding: 0.5rem;
+      border-radius: 0.5rem;
+    }
+  `;
+
+  @state() enrollment?: Enrollment;
+  @state() recoveryCodes?: string[];
+  @state() error?: string;
+
+  override render() {
+    if (this.recoveryCodes) {
+      return html`<div>
+        <p>
+          Die Zwei-Faktor-Anmeldung ist eingerichtet. Bewahre diese
+          Wiederherstellungscodes sicher auf, falls du dein Handy verlierst.
+          Jeder Code funktioniert einmal und sie werden nur jetzt angezeigt.
+        </p>
+        <ul>
+          ${repeat(
+            this.recoveryCodes,
+            (code) => code,
+            (code) => html`<li>${code}</li>`,
+          )}
+        </ul>
+        <button @click=${this.done}>Weiter</button>
+      </div>`;
+    }
+    if (this.enrollment) {
+      return html`<form @submit=${this.confirm}>
+        <p>
+          Öffne den Link auf dem Handy mit deiner Authenticator App oder gib
+          den Schlüssel dort von Hand ein.
+        </p>
+        <a href=${this.enrollment.otpauth_url}>In Authenticator App öffnen</a>
+        <p>Schlüssel: <code>${this.enrollment.secret}</code></p>
+        ${this.error ? html`<p id="error">${this.error}</p>` : nothing}
+        <label>
+          Code aus der App
+          <input
+            type="text"
+            name="code"
+            inputmode="numeric"
+            autocomplete="one-time-code"
+            required
+          />
+        </label>
+        <button type="submit">Bestätigen</button>
+      </form>`;
+    }
+    return html`<div>
+      <p>
+        Mit der Zwei-Faktor-Anmeldung brauchst du beim Login zusätzlich einen
+        Code aus einer Authenticator App.
+      </p>
+      ${this.error ? html`<p id="error">${this.error}</p>` : nothing}
+      <button @click=${this.start}>Einrichten</button>
+    </div>`;
+  }
+
+  async start() {
+    const resp = await client.POST("/api/command/start_totp_enrollment");
+    if (resp.error) {
+      this.error = "Einrichtung fehlgeschlagen.";
+      return;
+    }
+    this.error = undefined;
+    this.enrollment = resp.data;
+  }
+
+  async confirm(event: SubmitEvent) {
+    event.preventDefault();
+    const formData = new FormData(event.target as HTMLFormElement);
+    const resp = await client.POST("/api/command/confirm_totp_enrollment", {
+      body: { code: formData.get("code") as string },
+    });
+    if (resp.response.status === 401) {
+      this.error = "Der Code ist falsch.";
+      return;
+    }
+    if (resp.error) {
+      this.error = "Einrichtung fehlgeschlagen.";
+      return;
+    }
+    this.error = undefined;
+    this.recoveryCodes = resp.data.recovery_codes;
+  }
+
+  done() {
+    this.dispatchEvent(new Event("login", { bubbles: true, composed: true }));
+  }
+}
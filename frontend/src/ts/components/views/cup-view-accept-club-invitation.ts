@@ -0,0 +1,71 @@
+import { consume } from "@lit/context";
+import { Task } from "@lit/task";
+import { css, html, LitElement } from "lit";
+import { customElement } from "lit/decorators.js";
+import { client } from "../../apiClient";
+import { type User, userContext } from "../../contexts/user";
+import "../elements/cup-centered-icon-box.js";
+
+@customElement("cup-view-accept-club-invitation")
+export default class CupViewAcceptClubInvitation extends LitElement {
+  static override styles = css`
+    * {
+      margin: 0;
+      padding: 0;
+      box-sizing: border-box;
+    }
+
+    #status {
+      display: flex;
+      flex-direction: column;
+      gap: 1rem;
+      padding: 2rem;
+    }
+
+    #error {
+      border: 0.1rem solid #e2001a;
+      color: #e2001a;
+      padding: 0.5rem;
+      border-radius: 0.5rem;
+    }
+  `;
+  @consume({ context: userContext, subscribe: true })
+  user: User | null = null;
+
+  acceptTask = new Task(this, {
+    task: async () => {
+      const url = new URL(window.location.href);
+      const token = url.searchParams.get("token");
+      if (!token) {
+        throw new Error("Kein Token angegeben.");
+      }
+      const resp = await client.POST("/api/command/accept_club_invitation", {
+        body: { token },
+      });
+      if (resp.response.status === 404) {
+        throw new Error(
+          "Die Einladung wurde schon verwendet oder zurückgezogen.",
+        );
+      }
+      if (resp.error) {
+        throw new Error((resp.error as { message?: string }).message || "");
+      }
+    },
+    args: () => [],
+  });
+
+  override render() {
+    return html`<cup-centered-icon-box>
+      <div id="status">
+        <h1>NRW Freestyle Cup 2026</h1>
+        <h2>Vereinseinladung</h2>
+        ${this.acceptTask.render({
+          complete: () => html`<p>Du bist dem Verein beigetreten!</p>`,
+          pending: () => html`<p>Nehme Einladung an...</p>`,
+          error: () => html`<p id="error">Fehler: ${this.acceptTask.error}</p>`,
+        })}
+        <a href="/">Zurück zur Startseite</a>
+      </div>
+    </cup-centered-icon-box>`;
+  }
+}
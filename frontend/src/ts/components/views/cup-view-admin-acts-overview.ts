@@ -8,6 +8,19 @@ import "../elements/cup-starter-table.js";
 import { cache } from "lit/directives/cache.js";
 import { repeat } from "lit/directives/repeat.js";
 
+/** Duration, codec and bitrate of the uploaded song. */
+function songInfo(act: components["schemas"]["Act"]) {
+  if (act.song_duration_seconds == null) {
+    return nothing;
+  }
+  const seconds = Math.round(act.song_duration_seconds);
+  const duration = `${Math.floor(seconds / 60)}:${String(seconds % 60).padStart(2, "0")}`;
+  const kbps = act.song_bitrate ? ` ${Math.round(act.song_bitrate / 1000)} kbit/s` : "";
+  return html`<span title="${act.song_codec ?? ""}${kbps}"
+    >${act.song_too_long ? "⚠️ " : ""}${duration}</span
+  >`;
+}
+
 @customElement("cup-view-admin-acts-overview")
 export default class CupViewAdminActsOverview extends LitElement {
   static override styles = css`
@@ -287,6 +300,7 @@ export default class CupViewAdminActsOverview extends LitElement {
                                       : "❌"
                                   }
                                 </td>
+                                <td>${songInfo(act)}</td>
                                 <td>
                                   ${
                                     act.song_file
@@ -7,23 +7,22 @@ import "../elements/cup-club-manager.js";
 import "../elements/cup-starter-table.js";
 import { repeat } from "lit/directives/repeat.js";
 
-const CATEGORY_TO_ATTRIBUTE: Record<string, string> = {
-  NEM: "n_em_u15",
-  NEWU11: "n_ew_u15",
-  NEWU13: "n_ew_u15",
-  NEWU14: "n_ew_u15",
-  NEWU15: "n_ew_u15",
-  "NEW15+": "n_ew_o15",
-  SEU15: "s_e_u15",
-  "SE15+": "s_e_o15",
-  "NPU9,5": "n_p_u15",
-  NPU11: "n_p_u15",
-  NPU13: "n_p_u15",
-  NPU15: "n_p_u15",
-  "NP15+": "n_p_o15",
-  SPU15: "s_p_u15",
-  "SP15+": "s_p_o15",
-};
+type Judge = components["schemas"]["Judge"];
+
+const judgesWith = (
+  all: Judge[],
+  category: string,
+  role: components["schemas"]["JudgeRole"],
+  hospitation: boolean,
+) =>
+  all.filter((j) =>
+    j.qualifications.some(
+      (q) =>
+        q.category === category &&
+        q.role === role &&
+        q.hospitation === hospitation,
+    ),
+  );
 
 @customElement("cup-view-admin-judges")
 export default class CupViewAdminJudges extends LitElement {
@@ -116,31 +115,22 @@ export default class CupViewAdminJudges extends LitElement {
         performance: JudgeCat;
       }
 
-      console.log(all);
-
       const judgesByCategory: Record<string, CategoryJudge> = {};
 
       for (const cat of categories) {
-        const cat_attr = CATEGORY_TO_ATTRIBUTE[cat.name] as string;
         judgesByCategory[cat.name] = {
           category: cat,
           technic: {
-            judge: all.filter((j) => (j as any)[cat_attr + "_t"] as boolean),
-            hospitation: all.filter(
-              (j) => (j as any)[cat_attr + "_t_hosp"] as boolean,
-            ),
+            judge: judgesWith(all, cat.name, "technique", false),
+            hospitation: judgesWith(all, cat.name, "technique", true),
           },
           dismounts: {
-            judge: all.filter((j) => (j as any)[cat_attr + "_a"] as boolean),
-            hospitation: all.filter(
-              (j) => (j as any)[cat_attr + "_a_hosp"] as boolean,
-            ),
+            judge: judgesWith(all, cat.name, "dismount", false),
+            hospitation: judgesWith(all, cat.name, "dismount", true),
           },
           performance: {
-            judge: all.filter((j) => (j as any)[cat_attr + "_p"] as boolean),
-            hospitation: all.filter(
-              (j) => (j as any)[cat_attr + "_p_hosp"] as boolean,
-            ),
+            judge: judgesWith(all, cat.name, "performance", false),
+            hospitation: judgesWith(all, cat.name, "performance", true),
           },
         };
       }
@@ -7,22 +7,20 @@ import "../elements/cup-context-club.js";
 import "../elements/cup-club-manager.js";
 import "../elements/cup-starter-table.js";
 
-const CATEGORY_TO_ATTRIBUTE: Record<string, string> = {
-  NEM: "n_em_u15",
-  NEWU11: "n_ew_u15",
-  NEWU13: "n_ew_u15",
-  NEWU14: "n_ew_u15",
-  NEWU15: "n_ew_u15",
-  "NEW15+": "n_ew_o15",
-  SEU15: "s_e_u15",
-  "SE15+": "s_e_o15",
-  "NPU9,5": "n_p_u15",
-  NPU11: "n_p_u15",
-  NPU13: "n_p_u15",
-  NPU15: "n_p_u15",
-  "NP15+": "n_p_o15",
-  SPU15: "s_p_u15",
-  "SP15+": "s_p_o15",
+type JudgeRole = components["schemas"]["JudgeRole"];
+
+const qualification = (
+  judge: components["schemas"]["Judge"],
+  category: string,
+  role: JudgeRole,
+) => {
+  const found = judge.qualifications.find(
+    (q) => q.category === category && q.role === role,
+  );
+  return {
+    judge: found !== undefined && !found.hospitation,
+    hospitation: found?.hospitation ?? false,
+  };
 };
 
 @customElement("cup-view-admin-judges")
@@ -136,31 +134,24 @@ export default class CupViewAdminJudges extends LitElement {
       }[] = [];
 
       for (const dataJudge of all) {
-        const judge = {
+        const judge: (typeof judges)[number] = {
           judge: dataJudge,
           categories: {},
         };
         for (const category of categories) {
-          const cat = CATEGORY_TO_ATTRIBUTE[category.name];
-          (judge.categories as any)[category.name] = {
-            technic: {
-              judge: (dataJudge as any)[cat + "_t"],
-              hospitation: (dataJudge as any)[cat + "_t_hosp"],
-            },
-            performance: {
-              judge: (dataJudge as any)[cat + "_p"],
-              hospitation: (dataJudge as any)[cat + "_p_hosp"],
-            },
-            dismounts: {
-              judge: (dataJudge as any)[cat + "_a"],
-              hospitation: (dataJudge as any)[cat + "_a_hosp"],
-            },
+          judge.categories[category.name] = {
+            technic: qualification(dataJudge, category.name, "technique"),
+            performance: qualification(
+              dataJudge,
+              category.name,
+              "performance",
+            ),
+            dismounts: qualification(dataJudge, category.name, "dismount"),
           };
         }
         judges.push(judge);
       }
 
-      console.log(judges);
       return judges;
     },
     args: () => [this.categories.value],
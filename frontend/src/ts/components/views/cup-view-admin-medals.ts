@@ -64,68 +64,27 @@ export default class CupViewAdminMedals extends LitElement {
     }
   `;
 
-  categoryNames = new Task(this, {
+  medals = new Task(this, {
     task: async () => {
-      const resp = await client.GET("/api/query/list_categories");
-      if (resp.error) {
-        return new Map();
-      }
-      const data = resp.data;
-      return new Map(data.map((c) => [c.name, c.description]));
-    },
-    args: () => [],
-  });
-
-  categories = new Task(this, {
-    task: async ([categoryNames]) => {
-      const resp = await client.GET("/api/query/startlist");
+      const resp = await client.GET("/api/query/results");
       if (resp.error) {
         return [];
       }
-      const data = resp.data.filter((entry) => entry.category);
-
-      const categories = new Map();
-      for (const entry of data) {
-        if (!categories.has(entry.category)) {
-          categories.set(entry.category, {
-            numberOfStarts: 0,
-            numberOfMedalsPerStart: 0,
-          });
-        }
-        const numberOfMedals = entry.participants.length;
-        const category = categories.get(entry.category);
-        category.numberOfStarts += 1;
-        category.numberOfMedalsPerStart = Math.max(
-          category.numberOfMedalsPerStart,
-          numberOfMedals,
-        );
-      }
-      return Array.from(categories.entries()).map(
-        ([category, { numberOfStarts, numberOfMedalsPerStart }]) => ({
-          category,
-          numberOfStarts,
-          numberOfMedalsPerStart,
-          name: categoryNames?.get(category) || category,
-        }),
-      );
-    },
-    args: () => [this.categoryNames.value],
-  });
-
-  medals = new Task(this, {
-    task: async ([categories]) => {
-      if (!categories) return [];
       const medals = [];
-      for (const category of categories) {
-        for (let i = 0; i < Math.min(category.numberOfStarts, 3); i++) {
-          for (let j = 0; j < category.numberOfMedalsPerStart; j++) {
-            medals.push(category.name);
-          }
+      for (const act of resp.data) {
+        if (act.rank == null || act.rank > 3) {
+          continue;
+        }
+        for (let i = 0; i < act.participants.length; i++) {
+          medals.push({
+            category: act.category_description || act.category,
+            rank: act.rank,
+          });
         }
       }
       return medals;
     },
-    args: () => [this.categories.value],
+    args: () => [],
   });
 
   chunkedMedals = new Task(this, {
@@ -153,7 +112,11 @@ export default class CupViewAdminMedals extends LitElement {
               ${repeat(chunks, (chunk) =>
                 repeat(
                   chunk,
-                  (medal) => html`<div class="sticker"><p>${medal}</p></div>`,
+                  (medal) =>
+                    html`<div class="sticker">
+                      <p>${medal.category}</p>
+                      <p>${medal.rank}. Platz</p>
+                    </div>`,
                 ),
               )}
             </div>`,
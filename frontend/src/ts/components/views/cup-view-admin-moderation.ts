@@ -2,6 +2,7 @@ import { Task } from "@lit/task";
 import { css, html, LitElement } from "lit";
 import { customElement, state } from "lit/decorators.js";
 import { client, type components } from "../../apiClient";
+import { onTimeplanChange } from "../../utils.js";
 import "../elements/cup-context-club.js";
 import "../elements/cup-club-manager.js";
 import "../elements/cup-starter-table.js";
@@ -71,26 +72,29 @@ export default class CupViewAdminModeration extends LitElement {
   });
 
   dataUpdateInterval?: number;
+  stopLiveUpdates?: () => void;
 
   override connectedCallback() {
     super.connectedCallback?.();
-    this.dataUpdateInterval = setInterval(async () => {
-      const newTimeplan = (await client.GET("/api/query/predict_timeplan"))
-        .data;
-      if (
-        JSON.stringify(newTimeplan) !== JSON.stringify(this.lastRawTimeplan)
-      ) {
-        this.predictedTimeplan = newTimeplan;
-        this.lastRawTimeplan = this.predictedTimeplan;
-      }
-    }, 1000);
+    this.stopLiveUpdates = onTimeplanChange(() => this.updateTimeplan());
+    // The predicted times move with the clock while an entry runs late
+    this.dataUpdateInterval = setInterval(() => this.updateTimeplan(), 60000);
   }
 
   override disconnectedCallback() {
     super.disconnectedCallback?.();
+    this.stopLiveUpdates?.();
     clearInterval(this.dataUpdateInterval);
   }
 
+  async updateTimeplan() {
+    const newTimeplan = (await client.GET("/api/query/predict_timeplan")).data;
+    if (JSON.stringify(newTimeplan) !== JSON.stringify(this.lastRawTimeplan)) {
+      this.predictedTimeplan = newTimeplan;
+      this.lastRawTimeplan = this.predictedTimeplan;
+    }
+  }
+
   override render() {
     return html`
       <div id="wrapper">
@@ -1,8 +1,11 @@
+import { consume } from "@lit/context";
 import { Task } from "@lit/task";
-import { css, html, LitElement } from "lit";
+import { css, html, LitElement, nothing } from "lit";
 import { customElement } from "lit/decorators.js";
 import { classMap } from "lit/directives/class-map.js";
 import { client } from "../../apiClient";
+import { type User, userContext } from "../../contexts/user";
+import { onTimeplanChange } from "../../utils.js";
 import "../elements/cup-context-club.js";
 import "../elements/cup-club-manager.js";
 import "../elements/cup-starter-table.js";
@@ -120,6 +123,9 @@ export default class CupViewAdminMusicControl extends LitElement {
     }
   `;
 
+  @consume({ context: userContext, subscribe: true })
+  user: User | null = null;
+
   predictedTimeplan = new Task(this, {
     task: async () => {
       const res = await client.GET("/api/query/predict_timeplan");
@@ -128,6 +134,18 @@ export default class CupViewAdminMusicControl extends LitElement {
     args: () => [],
   });
 
+  stopLiveUpdates?: () => void;
+
+  override connectedCallback() {
+    super.connectedCallback?.();
+    this.stopLiveUpdates = onTimeplanChange(() => this.predictedTimeplan.run());
+  }
+
+  override disconnectedCallback() {
+    super.disconnectedCallback?.();
+    this.stopLiveUpdates?.();
+  }
+
   currentTimeplanEntry = new Task(this, {
     task: async ([predictedTimeplan]) =>
       predictedTimeplan?.items.find((item) => item.status === "Started"),
@@ -173,10 +191,23 @@ export default class CupViewAdminMusicControl extends LitElement {
               })
             ).data
           : null;
+        const songUrl = completeAct?.song_file
+          ? (
+              await client.GET("/api/query/get_song_url", {
+                params: {
+                  query: {
+                    act_id: completeAct.id,
+                    normalized: true,
+                  },
+                },
+              })
+            ).data
+          : null;
         return {
           entry: entry,
           act: act,
           completeAct,
+          songUrl,
         };
       }
       return { entry };
@@ -257,9 +288,7 @@ export default class CupViewAdminMusicControl extends LitElement {
                       </section>
                       <audio
                         controls
-                        src="/songs/${
-                          currentStarter.completeAct?.song_file || ""
-                        }"
+                        src=${currentStarter.songUrl?.url || ""}
                         preload="auto"
                       ></audio>
                     `
@@ -282,29 +311,33 @@ export default class CupViewAdminMusicControl extends LitElement {
             }
           `,
         })}
-        <button
-          class="material-icon back-button"
-          @click=${this.timeplanBackward}
-        >
-          arrow_back
-        </button>
-        <button
-          class="material-icon next-button"
-          @click=${this.timeplanForward}
-        >
-          arrow_forward
-        </button>
+        ${
+          this.user?.is_admin
+            ? html`
+              <button
+                class="material-icon back-button"
+                @click=${this.timeplanBackward}
+              >
+                arrow_back
+              </button>
+              <button
+                class="material-icon next-button"
+                @click=${this.timeplanForward}
+              >
+                arrow_forward
+              </button>
+            `
+            : nothing
+        }
       </main>
     </div>`;
   }
 
   async timeplanBackward() {
     await client.POST("/api/command/timeplan_backward");
-    this.predictedTimeplan.run();
   }
 
   async timeplanForward() {
     await client.POST("/api/command/timeplan_forward");
-    this.predictedTimeplan.run();
   }
 }
@@ -101,6 +101,18 @@ export default class CupViewAdmin extends LitElement {
               <p>${user.email}</p>
               <p>${user.email_verified ? "✔️" : "❌"}</p>
               <p>${user.is_admin ? "✔️" : "❌"}</p>
+              <label>
+                <input
+                  type="checkbox"
+                  .checked=${user.is_music_operator}
+                  @change=${(e: Event) =>
+                    this.setMusicOperator(
+                      user.id,
+                      (e.target as HTMLInputElement).checked,
+                    )}
+                />
+                Musik Operator
+              </label>
               ${
                 user.club_id
                   ? html` <cup-context-club club-id=${user.club_id}>
@@ -116,6 +128,16 @@ export default class CupViewAdmin extends LitElement {
     `;
   }
 
+  async setMusicOperator(userId: string, isMusicOperator: boolean) {
+    await client.POST("/api/command/set_music_operator", {
+      body: {
+        user_id: userId,
+        is_music_operator: isMusicOperator,
+      },
+    });
+    this.users.run();
+  }
+
   async logout() {
     await client.POST("/api/command/logout");
     window.location.href = "/";
@@ -114,10 +114,26 @@ export default class CupViewHome extends LitElement {
             ? html`<p>Du bist Admin! <a href="/admin">Admininterface</a></p>`
             : nothing
         }
+        ${
+          this.user?.is_music_operator && !this.user?.is_admin
+            ? html`<p>
+                Du bist Musik Operator!
+                <a href="/admin-music-control">Musik Player</a>
+              </p>`
+            : nothing
+        }
         <p>
           Deine Email ist ${this.user?.email}. Verifiziert:
           ${this.user?.email_verified ? "✔️" : "❌"}
         </p>
+        <p>
+          Zwei-Faktor-Anmeldung:
+          ${
+            this.user?.second_factor === "verified"
+              ? "✔️"
+              : html`❌ <a href="/totp">Einrichten</a>`
+          }
+        </p>
         <button @click=${this.logout} id="logout">
           <i class="material-icon">logout</i> Logout
         </button>
@@ -12,6 +12,7 @@ import {
   lastTimeplanAct,
   lastTimeplanEntry,
   nextTimeplanEntry,
+  onTimeplanChange,
   TimeplanStatus,
   timeplanStatus,
 } from "../../utils.js";
@@ -316,26 +317,29 @@ export default class CupViewInfoBoard extends LitElement {
   });
 
   dataUpdateInterval?: number;
+  stopLiveUpdates?: () => void;
 
   override connectedCallback() {
     super.connectedCallback?.();
-    this.dataUpdateInterval = setInterval(async () => {
-      const newTimeplan = (await client.GET("/api/query/predict_timeplan"))
-        .data;
-      if (
-        JSON.stringify(newTimeplan) !== JSON.stringify(this.lastRawTimeplan)
-      ) {
-        this.predictedTimeplan = newTimeplan;
-        this.lastRawTimeplan = this.predictedTimeplan;
-      }
-    }, 1000);
+    this.stopLiveUpdates = onTimeplanChange(() => this.updateTimeplan());
+    // The predicted times move with the clock while an entry runs late
+    this.dataUpdateInterval = setInterval(() => this.updateTimeplan(), 60000);
   }
 
   override disconnectedCallback() {
     super.disconnectedCallback?.();
+    this.stopLiveUpdates?.();
     clearInterval(this.dataUpdateInterval);
   }
 
+  async updateTimeplan() {
+    const newTimeplan = (await client.GET("/api/query/predict_timeplan")).data;
+    if (JSON.stringify(newTimeplan) !== JSON.stringify(this.lastRawTimeplan)) {
+      this.predictedTimeplan = newTimeplan;
+      this.lastRawTimeplan = this.predictedTimeplan;
+    }
+  }
+
   renderMain() {
     if (!this.predictedTimeplan) {
       return html`<div class="break">
@@ -0,0 +1,50 @@
+import { consume } from "@lit/context";
+import { css, html, LitElement, nothing } from "lit";
+import { customElement } from "lit/decorators.js";
+import { type User, userContext } from "../../contexts/user";
+import "../elements/cup-centered-icon-box.js";
+import "../elements/cup-totp-enrollment.js";
+
+@customElement("cup-view-totp-enrollment")
+export default class CupViewTotpEnrollment extends LitElement {
+  static override styles = css`
+    * {
+      margin: 0;
+      padding: 0;
+      box-sizing: border-box;
+    }
+
+    #status {
+      display: flex;
+      flex-direction: column;
+      gap: 1rem;
+      padding: 2rem;
+    }
+  `;
+  @consume({ context: userContext, subscribe: true })
+  user: User | null = null;
+
+  override render() {
+    return html`<cup-centered-icon-box>
+      <div id="status">
+        <h1>NRW Freestyle Cup 2026</h1>
+        <h2>Zwei-Faktor-Anmeldung</h2>
+        ${
+          this.user?.second_factor === "verified"
+            ? html`<p>
+                Die Zwei-Faktor-Anmeldung ist schon eingerichtet. Eine neue
+                Einrichtung ersetzt die bisherige App und die
+                Wiederherstellungscodes.
+              </p>`
+            : nothing
+        }
+        <cup-totp-enrollment @login=${this.done}></cup-totp-enrollment>
+        <a href="/">Zurück zur Startseite</a>
+      </div>
+    </cup-centered-icon-box>`;
+  }
+
+  done() {
+    window.location.href = "/";
+  }
+}
@@ -1,5 +1,5 @@
 import { createContext } from "@lit/context";
 import type { components } from "../bindings.js";
 
-export type User = components["schemas"]["User"];
+export type User = components["schemas"]["WhoAmI"];
 export const userContext = createContext<User | null>(Symbol("user"));
@@ -103,3 +103,17 @@ export const lastTimeplanAct = (
     (act) => act.status == "Ended",
   );
 };
+
+/**
+ * Call `reload` whenever the timeplan of the active competition changes.
+ *
+ * The browser reconnects the event source on its own, `reload` is called on
+ * every connect so changes made while disconnected aren't missed. Returns a
+ * function that stops listening.
+ */
+export const onTimeplanChange = (reload: () => void) => {
+  const source = new EventSource("/api/live");
+  source.addEventListener("open", reload);
+  source.addEventListener("timeplan", reload);
+  return () => source.close();
+};
@@ -0,0 +1,318 @@
+-- no-transaction
+-- Add down migration script here
+-- Only the data of the active competition is kept.
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+DROP VIEW IF EXISTS view_act;
+DROP VIEW IF EXISTS view_starter;
+
+DELETE FROM act_participants
+WHERE
+  act_id IN (
+    SELECT
+      id
+    FROM
+      acts
+    WHERE
+      competition_id NOT IN (
+        SELECT
+          id
+        FROM
+          competitions
+        WHERE
+          is_active
+      )
+  );
+
+DELETE FROM acts
+WHERE
+  competition_id NOT IN (
+    SELECT
+      id
+    FROM
+      competitions
+    WHERE
+      is_active
+  );
+
+ALTER TABLE acts DROP COLUMN competition_id;
+
+DELETE FROM starter
+WHERE
+  club_id IN (
+    SELECT
+      id
+    FROM
+      clubs
+    WHERE
+      competition_id NOT IN (
+        SELECT
+          id
+        FROM
+          competitions
+        WHERE
+          is_active
+      )
+  );
+
+DELETE FROM judge
+WHERE
+  club_id IN (
+    SELECT
+      id
+    FROM
+      clubs
+    WHERE
+      competition_id NOT IN (
+        SELECT
+          id
+        FROM
+          competitions
+        WHERE
+          is_active
+      )
+  );
+
+CREATE TABLE clubs_old (
+  "id" BLOB PRIMARY KEY,
+  "name" TEXT NOT NULL UNIQUE,
+  "owner_id" BLOB NOT NULL,
+  payment REAL,
+  FOREIGN KEY ("owner_id") REFERENCES "users" ("id")
+);
+
+INSERT INTO clubs_old (id, name, owner_id, payment)
+SELECT
+  id,
+  name,
+  owner_id,
+  payment
+FROM
+  clubs
+WHERE
+  competition_id IN (
+    SELECT
+      id
+    FROM
+      competitions
+    WHERE
+      is_active
+  );
+
+DROP TABLE clubs;
+ALTER TABLE clubs_old RENAME TO clubs;
+
+CREATE TABLE categories_old (
+  name TEXT PRIMARY KEY,
+  description TEXT,
+  from_birthday DATETIME,
+  to_birthday DATETIME,
+  is_pair BOOLEAN NOT NULL DEFAULT FALSE,
+  is_sonderpokal BOOLEAN NOT NULL DEFAULT FALSE,
+  is_single_male BOOLEAN NOT NULL DEFAULT FALSE,
+  "einfahrzeit_seconds" INTEGER NOT NULL DEFAULT 0,
+  "act_duration_seconds" INTEGER NOT NULL DEFAULT 0,
+  "judge_duration_seconds" INTEGER NOT NULL DEFAULT 0,
+  "order" INTEGER
+);
+
+INSERT INTO categories_old
+SELECT
+  name,
+  description,
+  from_birthday,
+  to_birthday,
+  is_pair,
+  is_sonderpokal,
+  is_single_male,
+  "einfahrzeit_seconds",
+  "act_duration_seconds",
+  "judge_duration_seconds",
+  "order"
+FROM
+  categories
+WHERE
+  competition_id IN (
+    SELECT
+      id
+    FROM
+      competitions
+    WHERE
+      is_active
+  );
+
+CREATE TABLE timeplan_old (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  earliest_start_time DATETIME,
+  duration_seconds INTEGER,
+  label TEXT,
+  category TEXT,
+  started_at DATETIME,
+  ended_at DATETIME,
+  FOREIGN KEY (category) REFERENCES categories_old (name)
+);
+
+INSERT INTO timeplan_old (id, earliest_start_time, duration_seconds, label, category, started_at, ended_at)
+SELECT
+  id,
+  earliest_start_time,
+  duration_seconds,
+  label,
+  category,
+  started_at,
+  ended_at
+FROM
+  timeplan
+WHERE
+  competition_id IN (
+    SELECT
+      id
+    FROM
+      competitions
+    WHERE
+      is_active
+  );
+
+DROP TABLE timeplan;
+DROP TABLE categories;
+ALTER TABLE categories_old RENAME TO categories;
+ALTER TABLE timeplan_old RENAME TO timeplan;
+
+DROP TABLE competition_days;
+DROP TABLE competitions;
+
+ALTER TABLE starter
+ADD COLUMN age_on_competition REAL GENERATED ALWAYS AS (
+  (julianday ('2026-04-19') - julianday (birthdate)) / 365.2425
+) VIRTUAL NOT NULL;
+
+CREATE VIEW
+  view_act AS
+SELECT
+  a.*,
+  (
+    SELECT
+      MAX(s.age_on_competition)
+    FROM
+      starter s
+      JOIN act_participants p ON p.starter_id = s.id
+    WHERE
+      p.act_id = a.id
+  ) AS max_age,
+  (
+    CASE
+      WHEN a.is_pair THEN (
+        SELECT
+          MAX(s.pair_sonderpokal)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+      ELSE (
+        SELECT
+          MAX(s.single_sonderpokal)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+    END
+  ) AS is_sonderpokal,
+  (
+    SELECT
+      json_group_array (
+        json_object (
+          'firstname',
+          starter.firstname,
+          'lastname',
+          starter.lastname,
+          'id',
+          hex (starter.id),
+          'club_name',
+          clubs.name
+        )
+      )
+    FROM
+      starter
+      JOIN act_participants p ON p.starter_id = starter.id
+      JOIN clubs ON clubs.id = starter.club_id
+    WHERE
+      p.act_id = a.id
+  ) AS participants,
+  (
+    SELECT
+      categories.name
+    FROM
+      categories
+    WHERE
+      categories.is_pair = a.is_pair
+      AND categories.is_sonderpokal = (
+        CASE
+          WHEN a.is_pair THEN (
+            SELECT
+              MAX(s.pair_sonderpokal)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+          )
+          ELSE (
+            SELECT
+              MAX(s.single_sonderpokal)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+          )
+        END
+      )
+      AND (
+        CASE
+          WHEN categories.is_single_male THEN (
+            SELECT
+              MIN(s.single_male)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+            LIMIT
+              1
+          )
+          ELSE TRUE
+        END
+      )
+      AND categories.from_birthday <= (
+        SELECT
+          MIN(s.birthdate)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+      AND categories.to_birthday > (
+        SELECT
+          MIN(s.birthdate)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+    LIMIT
+      1
+  ) AS category
+FROM
+  acts a;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
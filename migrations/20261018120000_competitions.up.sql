@@ -0,0 +1,298 @@
+-- no-transaction
+-- Add up migration script here
+-- Rebuilding tables that other tables reference needs the foreign keys to be
+-- disabled, which is not possible inside of a transaction.
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+-- A competition (season) owns its categories, timeplan, clubs and acts.
+-- Exactly one competition is active; the API works on the active competition.
+CREATE TABLE competitions (
+  id BLOB PRIMARY KEY,
+  name TEXT NOT NULL,
+  venue TEXT,
+  start_register_date DATETIME NOT NULL,
+  end_register_date DATETIME NOT NULL,
+  end_music_upload_date DATETIME NOT NULL,
+  is_active BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE UNIQUE INDEX competitions_single_active ON competitions (is_active)
+WHERE
+  is_active;
+
+-- The first day is the reference date for the age of the starters
+CREATE TABLE competition_days (
+  competition_id BLOB NOT NULL,
+  day DATE NOT NULL,
+  PRIMARY KEY (competition_id, day),
+  FOREIGN KEY (competition_id) REFERENCES competitions (id) ON DELETE CASCADE
+);
+
+-- The registration deadlines are synced from the server arguments on startup
+INSERT INTO competitions (id, name, venue, start_register_date, end_register_date, end_music_upload_date, is_active)
+VALUES
+  (
+    X'0192F0C0000070008000000000002026',
+    'Freestyle Cup NRW 2026',
+    NULL,
+    datetime('2026-04-19'),
+    datetime('2026-04-19'),
+    datetime('2026-04-19'),
+    TRUE
+  );
+
+INSERT INTO competition_days (competition_id, day)
+VALUES
+  (X'0192F0C0000070008000000000002026', '2026-04-19');
+
+DROP VIEW IF EXISTS view_act;
+
+-- Categories are unique per competition
+CREATE TABLE categories_new (
+  competition_id BLOB NOT NULL,
+  name TEXT NOT NULL,
+  description TEXT,
+  from_birthday DATETIME,
+  to_birthday DATETIME,
+  is_pair BOOLEAN NOT NULL DEFAULT FALSE,
+  is_sonderpokal BOOLEAN NOT NULL DEFAULT FALSE,
+  is_single_male BOOLEAN NOT NULL DEFAULT FALSE,
+  "einfahrzeit_seconds" INTEGER NOT NULL DEFAULT 0,
+  "act_duration_seconds" INTEGER NOT NULL DEFAULT 0,
+  "judge_duration_seconds" INTEGER NOT NULL DEFAULT 0,
+  "order" INTEGER,
+  PRIMARY KEY (competition_id, name),
+  FOREIGN KEY (competition_id) REFERENCES competitions (id) ON DELETE CASCADE
+);
+
+INSERT INTO categories_new
+SELECT
+  X'0192F0C0000070008000000000002026',
+  name,
+  description,
+  from_birthday,
+  to_birthday,
+  is_pair,
+  is_sonderpokal,
+  is_single_male,
+  "einfahrzeit_seconds",
+  "act_duration_seconds",
+  "judge_duration_seconds",
+  "order"
+FROM
+  categories;
+
+CREATE TABLE timeplan_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  competition_id BLOB NOT NULL,
+  earliest_start_time DATETIME,
+  duration_seconds INTEGER,
+  label TEXT,
+  category TEXT,
+  started_at DATETIME,
+  ended_at DATETIME,
+  FOREIGN KEY (competition_id) REFERENCES competitions (id) ON DELETE CASCADE,
+  FOREIGN KEY (competition_id, category) REFERENCES categories_new (competition_id, name)
+);
+
+INSERT INTO timeplan_new (id, competition_id, earliest_start_time, duration_seconds, label, category, started_at, ended_at)
+SELECT
+  id,
+  X'0192F0C0000070008000000000002026',
+  earliest_start_time,
+  duration_seconds,
+  label,
+  category,
+  started_at,
+  ended_at
+FROM
+  timeplan;
+
+DROP TABLE timeplan;
+DROP TABLE categories;
+ALTER TABLE categories_new RENAME TO categories;
+ALTER TABLE timeplan_new RENAME TO timeplan;
+
+-- Club names only have to be unique within a competition
+CREATE TABLE clubs_new (
+  "id" BLOB PRIMARY KEY,
+  "competition_id" BLOB NOT NULL,
+  "name" TEXT NOT NULL,
+  "owner_id" BLOB NOT NULL,
+  payment REAL,
+  FOREIGN KEY ("competition_id") REFERENCES "competitions" ("id"),
+  FOREIGN KEY ("owner_id") REFERENCES "users" ("id"),
+  CONSTRAINT "unique_club_name" UNIQUE ("competition_id", "name")
+);
+
+INSERT INTO clubs_new (id, competition_id, name, owner_id, payment)
+SELECT
+  id,
+  X'0192F0C0000070008000000000002026',
+  name,
+  owner_id,
+  payment
+FROM
+  clubs;
+
+DROP TABLE clubs;
+ALTER TABLE clubs_new RENAME TO clubs;
+
+ALTER TABLE acts ADD COLUMN competition_id BLOB REFERENCES competitions (id);
+UPDATE acts SET competition_id = X'0192F0C0000070008000000000002026';
+
+-- The age depends on the competition, so it can't be a generated column anymore
+ALTER TABLE starter DROP COLUMN age_on_competition;
+
+CREATE VIEW
+  view_starter AS
+SELECT
+  s.*,
+  clubs.competition_id AS competition_id,
+  (
+    julianday (
+      (
+        SELECT
+          MIN(d.day)
+        FROM
+          competition_days d
+        WHERE
+          d.competition_id = clubs.competition_id
+      )
+    ) - julianday (s.birthdate)
+  ) / 365.2425 AS age_on_competition
+FROM
+  starter s
+  JOIN clubs ON clubs.id = s.club_id;
+
+CREATE VIEW
+  view_act AS
+SELECT
+  a.*,
+  (
+    SELECT
+      MAX(s.age_on_competition)
+    FROM
+      view_starter s
+      JOIN act_participants p ON p.starter_id = s.id
+    WHERE
+      p.act_id = a.id
+  ) AS max_age,
+  (
+    CASE
+      WHEN a.is_pair THEN (
+        SELECT
+          MAX(s.pair_sonderpokal)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+      ELSE (
+        SELECT
+          MAX(s.single_sonderpokal)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+    END
+  ) AS is_sonderpokal,
+  (
+    SELECT
+      json_group_array (
+        json_object (
+          'firstname',
+          starter.firstname,
+          'lastname',
+          starter.lastname,
+          'id',
+          hex (starter.id),
+          'club_name',
+          clubs.name
+        )
+      )
+    FROM
+      starter
+      JOIN act_participants p ON p.starter_id = starter.id
+      JOIN clubs ON clubs.id = starter.club_id
+    WHERE
+      p.act_id = a.id
+  ) AS participants,
+  (
+    SELECT
+      categories.name
+    FROM
+      categories
+    WHERE
+      categories.competition_id = a.competition_id
+      AND categories.is_pair = a.is_pair
+      AND categories.is_sonderpokal = (
+        CASE
+          WHEN a.is_pair THEN (
+            SELECT
+              MAX(s.pair_sonderpokal)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+          )
+          ELSE (
+            SELECT
+              MAX(s.single_sonderpokal)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+          )
+        END
+      )
+      AND (
+        CASE
+          WHEN categories.is_single_male THEN (
+            SELECT
+              MIN(s.single_male)
+            FROM
+              starter s
+              JOIN act_participants p ON p.starter_id = s.id
+            WHERE
+              p.act_id = a.id
+            LIMIT
+              1
+          )
+          ELSE TRUE
+        END
+      )
+      AND categories.from_birthday <= (
+        SELECT
+          MIN(s.birthdate)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+      AND categories.to_birthday > (
+        SELECT
+          MIN(s.birthdate)
+        FROM
+          starter s
+          JOIN act_participants p ON p.starter_id = s.id
+        WHERE
+          p.act_id = a.id
+      )
+    LIMIT
+      1
+  ) AS category
+FROM
+  acts a;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
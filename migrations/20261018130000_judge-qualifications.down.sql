@@ -0,0 +1,244 @@
+-- Add down migration script here
+ALTER TABLE judge ADD COLUMN n_ew_u15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_u15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_u15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_u15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_u15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_u15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_ew_o15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_u15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_em_o15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_u15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN n_p_o15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_u15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_e_o15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_u15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_p BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_p_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_t BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_t_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_a BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE judge ADD COLUMN s_p_o15_a_hosp BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE judge SET n_ew_u15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_u15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_u15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_u15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_u15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_u15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NEWU11', 'NEWU13', 'NEWU14', 'NEWU15')
+);
+UPDATE judge SET n_ew_o15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_ew_o15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_ew_o15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_ew_o15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_ew_o15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_ew_o15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NEW15+')
+);
+UPDATE judge SET n_em_u15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_u15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_u15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_u15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_u15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_u15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_em_o15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NEM')
+);
+UPDATE judge SET n_p_u15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_u15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_u15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_u15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_u15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_u15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NPU9,5', 'NPU11', 'NPU13', 'NPU15')
+);
+UPDATE judge SET n_p_o15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET n_p_o15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET n_p_o15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET n_p_o15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET n_p_o15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET n_p_o15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('NP15+')
+);
+UPDATE judge SET s_e_u15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_u15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_u15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_u15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_u15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_u15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('SEU15')
+);
+UPDATE judge SET s_e_o15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_e_o15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_e_o15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_e_o15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_e_o15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_e_o15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('SE15+')
+);
+UPDATE judge SET s_p_u15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_u15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_u15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_u15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_u15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_u15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('SPU15')
+);
+UPDATE judge SET s_p_o15_p = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND NOT hospitation AND category IN ('SP15+')
+);
+UPDATE judge SET s_p_o15_p_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'P' AND hospitation AND category IN ('SP15+')
+);
+UPDATE judge SET s_p_o15_t = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND NOT hospitation AND category IN ('SP15+')
+);
+UPDATE judge SET s_p_o15_t_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'T' AND hospitation AND category IN ('SP15+')
+);
+UPDATE judge SET s_p_o15_a = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND NOT hospitation AND category IN ('SP15+')
+);
+UPDATE judge SET s_p_o15_a_hosp = TRUE WHERE id IN (
+  SELECT judge_id FROM judge_qualifications WHERE role = 'A' AND hospitation AND category IN ('SP15+')
+);
+
+DROP TABLE judge_qualifications;
@@ -0,0 +1,237 @@
+-- Add up migration script here
+-- A judge can judge, or hospitate as, a role in a category of the competition
+-- of the judge's club.
+CREATE TABLE judge_qualifications (
+  judge_id BLOB NOT NULL,
+  competition_id BLOB NOT NULL,
+  category TEXT NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('P', 'T', 'A')),
+  hospitation BOOLEAN NOT NULL DEFAULT FALSE,
+  PRIMARY KEY (judge_id, category, role),
+  FOREIGN KEY (judge_id) REFERENCES judge (id) ON DELETE CASCADE,
+  FOREIGN KEY (competition_id, category) REFERENCES categories (competition_id, name)
+);
+
+-- The old flags cover groups of categories
+CREATE TEMP TABLE judge_group_categories (grp TEXT NOT NULL, category TEXT NOT NULL);
+
+INSERT INTO judge_group_categories (grp, category)
+VALUES
+  ('n_ew_u15', 'NEWU11'),
+  ('n_ew_u15', 'NEWU13'),
+  ('n_ew_u15', 'NEWU14'),
+  ('n_ew_u15', 'NEWU15'),
+  ('n_ew_o15', 'NEW15+'),
+  ('n_em_u15', 'NEM'),
+  ('n_em_o15', 'NEM'),
+  ('n_p_u15', 'NPU9,5'),
+  ('n_p_u15', 'NPU11'),
+  ('n_p_u15', 'NPU13'),
+  ('n_p_u15', 'NPU15'),
+  ('n_p_o15', 'NP15+'),
+  ('s_e_u15', 'SEU15'),
+  ('s_e_o15', 'SE15+'),
+  ('s_p_u15', 'SPU15'),
+  ('s_p_o15', 'SP15+');
+
+CREATE TEMP TABLE judge_flags AS
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_ew_u15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_ew_u15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_ew_u15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_ew_u15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_ew_u15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_u15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_ew_u15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_ew_o15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_ew_o15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_ew_o15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_ew_o15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_ew_o15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_ew_o15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_ew_o15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_em_u15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_em_u15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_em_u15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_em_u15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_em_u15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_u15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_em_u15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_em_o15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_em_o15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_em_o15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_em_o15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_em_o15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_em_o15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_em_o15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_p_u15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_p_u15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_p_u15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_p_u15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_p_u15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_u15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_p_u15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE n_p_o15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE n_p_o15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE n_p_o15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE n_p_o15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE n_p_o15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 'n_p_o15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE n_p_o15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE s_e_u15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE s_e_u15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE s_e_u15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE s_e_u15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE s_e_u15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_u15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE s_e_u15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE s_e_o15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE s_e_o15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE s_e_o15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE s_e_o15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE s_e_o15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 's_e_o15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE s_e_o15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE s_p_u15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE s_p_u15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE s_p_u15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE s_p_u15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE s_p_u15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_u15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE s_p_u15_a_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'P' AS role, FALSE AS hospitation FROM judge WHERE s_p_o15_p
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'P' AS role, TRUE AS hospitation FROM judge WHERE s_p_o15_p_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'T' AS role, FALSE AS hospitation FROM judge WHERE s_p_o15_t
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'T' AS role, TRUE AS hospitation FROM judge WHERE s_p_o15_t_hosp
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'A' AS role, FALSE AS hospitation FROM judge WHERE s_p_o15_a
+UNION ALL
+SELECT id AS judge_id, club_id, 's_p_o15' AS grp, 'A' AS role, TRUE AS hospitation FROM judge WHERE s_p_o15_a_hosp;
+
+-- Judging a role wins over hospitating it
+INSERT OR IGNORE INTO judge_qualifications (judge_id, competition_id, category, role, hospitation)
+SELECT DISTINCT
+  f.judge_id,
+  clubs.competition_id,
+  m.category,
+  f.role,
+  f.hospitation
+FROM
+  judge_flags f
+  JOIN clubs ON clubs.id = f.club_id
+  JOIN judge_group_categories m ON m.grp = f.grp
+  JOIN categories ON categories.competition_id = clubs.competition_id
+  AND categories.name = m.category
+ORDER BY
+  f.hospitation;
+
+DROP TABLE judge_flags;
+DROP TABLE judge_group_categories;
+
+ALTER TABLE judge DROP COLUMN n_ew_u15_p;
+ALTER TABLE judge DROP COLUMN n_ew_u15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_ew_u15_t;
+ALTER TABLE judge DROP COLUMN n_ew_u15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_ew_u15_a;
+ALTER TABLE judge DROP COLUMN n_ew_u15_a_hosp;
+ALTER TABLE judge DROP COLUMN n_ew_o15_p;
+ALTER TABLE judge DROP COLUMN n_ew_o15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_ew_o15_t;
+ALTER TABLE judge DROP COLUMN n_ew_o15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_ew_o15_a;
+ALTER TABLE judge DROP COLUMN n_ew_o15_a_hosp;
+ALTER TABLE judge DROP COLUMN n_em_u15_p;
+ALTER TABLE judge DROP COLUMN n_em_u15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_em_u15_t;
+ALTER TABLE judge DROP COLUMN n_em_u15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_em_u15_a;
+ALTER TABLE judge DROP COLUMN n_em_u15_a_hosp;
+ALTER TABLE judge DROP COLUMN n_em_o15_p;
+ALTER TABLE judge DROP COLUMN n_em_o15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_em_o15_t;
+ALTER TABLE judge DROP COLUMN n_em_o15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_em_o15_a;
+ALTER TABLE judge DROP COLUMN n_em_o15_a_hosp;
+ALTER TABLE judge DROP COLUMN n_p_u15_p;
+ALTER TABLE judge DROP COLUMN n_p_u15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_p_u15_t;
+ALTER TABLE judge DROP COLUMN n_p_u15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_p_u15_a;
+ALTER TABLE judge DROP COLUMN n_p_u15_a_hosp;
+ALTER TABLE judge DROP COLUMN n_p_o15_p;
+ALTER TABLE judge DROP COLUMN n_p_o15_p_hosp;
+ALTER TABLE judge DROP COLUMN n_p_o15_t;
+ALTER TABLE judge DROP COLUMN n_p_o15_t_hosp;
+ALTER TABLE judge DROP COLUMN n_p_o15_a;
+ALTER TABLE judge DROP COLUMN n_p_o15_a_hosp;
+ALTER TABLE judge DROP COLUMN s_e_u15_p;
+ALTER TABLE judge DROP COLUMN s_e_u15_p_hosp;
+ALTER TABLE judge DROP COLUMN s_e_u15_t;
+ALTER TABLE judge DROP COLUMN s_e_u15_t_hosp;
+ALTER TABLE judge DROP COLUMN s_e_u15_a;
+ALTER TABLE judge DROP COLUMN s_e_u15_a_hosp;
+ALTER TABLE judge DROP COLUMN s_e_o15_p;
+ALTER TABLE judge DROP COLUMN s_e_o15_p_hosp;
+ALTER TABLE judge DROP COLUMN s_e_o15_t;
+ALTER TABLE judge DROP COLUMN s_e_o15_t_hosp;
+ALTER TABLE judge DROP COLUMN s_e_o15_a;
+ALTER TABLE judge DROP COLUMN s_e_o15_a_hosp;
+ALTER TABLE judge DROP COLUMN s_p_u15_p;
+ALTER TABLE judge DROP COLUMN s_p_u15_p_hosp;
+ALTER TABLE judge DROP COLUMN s_p_u15_t;
+ALTER TABLE judge DROP COLUMN s_p_u15_t_hosp;
+ALTER TABLE judge DROP COLUMN s_p_u15_a;
+ALTER TABLE judge DROP COLUMN s_p_u15_a_hosp;
+ALTER TABLE judge DROP COLUMN s_p_o15_p;
+ALTER TABLE judge DROP COLUMN s_p_o15_p_hosp;
+ALTER TABLE judge DROP COLUMN s_p_o15_t;
+ALTER TABLE judge DROP COLUMN s_p_o15_t_hosp;
+ALTER TABLE judge DROP COLUMN s_p_o15_a;
+ALTER TABLE judge DROP COLUMN s_p_o15_a_hosp;
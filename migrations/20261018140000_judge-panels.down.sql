@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE judge_panel;
+
+ALTER TABLE categories DROP COLUMN panel_hospitation;
+ALTER TABLE categories DROP COLUMN panel_dismount;
+ALTER TABLE categories DROP COLUMN panel_technique;
+ALTER TABLE categories DROP COLUMN panel_performance;
@@ -0,0 +1,19 @@
+-- Add up migration script here
+-- How many judges of each role and how many hospitants a category needs
+ALTER TABLE categories ADD COLUMN panel_performance INTEGER NOT NULL DEFAULT 3;
+ALTER TABLE categories ADD COLUMN panel_technique INTEGER NOT NULL DEFAULT 3;
+ALTER TABLE categories ADD COLUMN panel_dismount INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE categories ADD COLUMN panel_hospitation INTEGER NOT NULL DEFAULT 0;
+
+-- The judges of a category entry of the timeplan
+CREATE TABLE judge_panel (
+  timeplan_id INTEGER NOT NULL,
+  judge_id BLOB NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('P', 'T', 'A')),
+  hospitation BOOLEAN NOT NULL DEFAULT FALSE,
+  -- Set by an admin and kept when the panels are assigned again
+  is_manual BOOLEAN NOT NULL DEFAULT FALSE,
+  PRIMARY KEY (timeplan_id, judge_id),
+  FOREIGN KEY (timeplan_id) REFERENCES timeplan (id) ON DELETE CASCADE,
+  FOREIGN KEY (judge_id) REFERENCES judge (id) ON DELETE CASCADE
+);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- One row per sub-criterion a judge scored for an act
+CREATE TABLE scores (
+  act_id BLOB NOT NULL,
+  judge_id BLOB NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('P', 'T', 'A')),
+  criterion TEXT NOT NULL,
+  value REAL NOT NULL,
+  created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (act_id, judge_id, criterion),
+  FOREIGN KEY (act_id) REFERENCES acts (id) ON DELETE CASCADE,
+  FOREIGN KEY (judge_id) REFERENCES judge (id) ON DELETE CASCADE
+);
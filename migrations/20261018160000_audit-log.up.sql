@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Every successful command with the user, the request and the changed state.
+CREATE TABLE audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  user_id BLOB,
+  request_id TEXT,
+  command TEXT NOT NULL,
+  payload TEXT,
+  "before" TEXT,
+  "after" TEXT
+);
+
+CREATE INDEX audit_log_command ON audit_log (command);
+
+CREATE INDEX audit_log_user_id ON audit_log (user_id);
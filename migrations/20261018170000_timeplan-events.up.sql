@@ -0,0 +1,58 @@
+-- Add up migration script here
+-- Append-only history of the timeplan progress. The started_at and ended_at
+-- columns of timeplan and acts are derived from it.
+--
+-- Every timeplan_forward is a step with one or more changes. Undo and redo
+-- events refer to the step they revert or restore. Acts and entries are not
+-- referenced by foreign keys, so the history survives their deletion.
+CREATE TABLE timeplan_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  competition_id BLOB NOT NULL,
+  step INTEGER NOT NULL,
+  kind TEXT NOT NULL CHECK (
+    kind IN (
+      'entry_started',
+      'entry_ended',
+      'act_started',
+      'act_ended',
+      'undo',
+      'redo'
+    )
+  ),
+  timeplan_id INTEGER,
+  act_id BLOB,
+  created_at DATETIME NOT NULL DEFAULT (datetime('now')),
+  FOREIGN KEY (competition_id) REFERENCES competitions (id) ON DELETE CASCADE
+);
+
+CREATE INDEX timeplan_events_competition ON timeplan_events (competition_id, id);
+
+-- Changes recorded at the same time were made by the same step
+INSERT INTO timeplan_events (competition_id, step, kind, timeplan_id, act_id, created_at)
+SELECT
+  competition_id,
+  DENSE_RANK() OVER (
+    PARTITION BY competition_id
+    ORDER BY created_at
+  ),
+  kind,
+  timeplan_id,
+  act_id,
+  created_at
+FROM
+  (
+    SELECT competition_id, 'entry_started' AS kind, id AS timeplan_id, NULL AS act_id, started_at AS created_at
+    FROM timeplan WHERE started_at IS NOT NULL
+    UNION ALL
+    SELECT competition_id, 'entry_ended', id, NULL, ended_at
+    FROM timeplan WHERE ended_at IS NOT NULL
+    UNION ALL
+    SELECT competition_id, 'act_started', NULL, id, started_at
+    FROM acts WHERE started_at IS NOT NULL AND competition_id IS NOT NULL
+    UNION ALL
+    SELECT competition_id, 'act_ended', NULL, id, ended_at
+    FROM acts WHERE ended_at IS NOT NULL AND competition_id IS NOT NULL
+  )
+ORDER BY
+  created_at,
+  kind IN ('entry_started', 'act_ended') DESC;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE acts DROP COLUMN song_bitrate;
+ALTER TABLE acts DROP COLUMN song_codec;
+ALTER TABLE acts DROP COLUMN song_duration_seconds;
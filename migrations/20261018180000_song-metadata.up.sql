@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- Read from the uploaded song, songs uploaded before stay unknown
+ALTER TABLE acts ADD COLUMN song_duration_seconds REAL;
+ALTER TABLE acts ADD COLUMN song_codec TEXT;
+-- Average bits per second
+ALTER TABLE acts ADD COLUMN song_bitrate INTEGER;
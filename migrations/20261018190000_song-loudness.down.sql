@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE acts DROP COLUMN song_normalized_file;
+ALTER TABLE acts DROP COLUMN song_peak_dbfs;
+ALTER TABLE acts DROP COLUMN song_loudness_lufs;
+ALTER TABLE acts DROP COLUMN song_analyzed_at;
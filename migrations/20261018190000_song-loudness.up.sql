@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- Filled in by the song analysis in the background, reset on every upload
+ALTER TABLE acts ADD COLUMN song_analyzed_at DATETIME;
+-- EBU R128 integrated loudness, NULL for silent or unreadable songs
+ALTER TABLE acts ADD COLUMN song_loudness_lufs REAL;
+ALTER TABLE acts ADD COLUMN song_peak_dbfs REAL;
+-- Copy of the song at the target loudness, next to the song in the data path
+ALTER TABLE acts ADD COLUMN song_normalized_file TEXT;
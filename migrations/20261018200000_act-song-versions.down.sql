@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE acts DROP COLUMN song_version_id;
+DROP TABLE act_song_versions;
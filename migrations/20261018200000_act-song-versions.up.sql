@@ -0,0 +1,36 @@
+-- Add up migration script here
+-- Every uploaded song of an act, files are never overwritten
+CREATE TABLE act_song_versions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  act_id BLOB NOT NULL,
+  version INTEGER NOT NULL,
+  file TEXT NOT NULL,
+  original_file_name TEXT,
+  -- Hex SHA-256 of the file, unknown for songs uploaded before versions
+  sha256 TEXT,
+  duration_seconds REAL,
+  codec TEXT,
+  bitrate INTEGER,
+  -- The check belongs to the version, a new upload is unchecked
+  checked BOOLEAN NOT NULL DEFAULT FALSE,
+  uploaded_by BLOB,
+  uploaded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE (act_id, version),
+  FOREIGN KEY (act_id) REFERENCES acts (id) ON DELETE CASCADE,
+  FOREIGN KEY (uploaded_by) REFERENCES users (id) ON DELETE SET NULL
+);
+
+INSERT INTO act_song_versions (
+  act_id, version, file, original_file_name, duration_seconds, codec, bitrate, checked, uploaded_at
+)
+SELECT id, 1, song_file, song_file_name, song_duration_seconds, song_codec, song_bitrate, song_checked,
+  COALESCE(created_at, CURRENT_TIMESTAMP)
+FROM acts
+WHERE song_file IS NOT NULL;
+
+-- The version the song columns of the act are copied from. No foreign key,
+-- SQLite can't drop columns that have one.
+ALTER TABLE acts ADD COLUMN song_version_id INTEGER;
+
+UPDATE acts
+SET song_version_id = (SELECT id FROM act_song_versions WHERE act_song_versions.act_id = acts.id);
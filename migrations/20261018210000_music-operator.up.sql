@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE users ADD COLUMN is_music_operator BOOLEAN NOT NULL DEFAULT FALSE;
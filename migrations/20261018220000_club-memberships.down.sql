@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE club_invitations;
+DROP TABLE club_memberships;
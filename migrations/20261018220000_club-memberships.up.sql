@@ -0,0 +1,31 @@
+-- Add up migration script here
+CREATE TABLE club_memberships (
+  club_id BLOB NOT NULL,
+  user_id BLOB NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('owner', 'trainer', 'read_only')),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (club_id, user_id),
+  FOREIGN KEY (club_id) REFERENCES clubs (id) ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX club_memberships_user_id ON club_memberships (user_id);
+
+-- Everybody who could manage a club so far owns it
+INSERT INTO club_memberships (club_id, user_id, role)
+SELECT id, owner_id, 'owner' FROM clubs;
+
+INSERT OR IGNORE INTO club_memberships (club_id, user_id, role)
+SELECT club_id, id, 'owner' FROM users
+WHERE club_id IN (SELECT id FROM clubs);
+
+CREATE TABLE club_invitations (
+  id BLOB PRIMARY KEY,
+  club_id BLOB NOT NULL,
+  email TEXT NOT NULL,
+  role TEXT NOT NULL CHECK (role IN ('owner', 'trainer', 'read_only')),
+  invited_by BLOB,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (club_id) REFERENCES clubs (id) ON DELETE CASCADE,
+  FOREIGN KEY (invited_by) REFERENCES users (id) ON DELETE SET NULL
+);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE sessions DROP COLUMN second_factor_verified;
+
+ALTER TABLE users DROP COLUMN totp_recovery_codes;
+ALTER TABLE users DROP COLUMN totp_last_step;
+ALTER TABLE users DROP COLUMN totp_pending_secret;
+ALTER TABLE users DROP COLUMN totp_secret;
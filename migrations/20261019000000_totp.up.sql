@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Base32 secrets, the pending one until the first code confirms it
+ALTER TABLE users ADD COLUMN totp_secret TEXT;
+ALTER TABLE users ADD COLUMN totp_pending_secret TEXT;
+-- Codes of this step or older were used already
+ALTER TABLE users ADD COLUMN totp_last_step INTEGER;
+-- JSON array of SHA-256 hashes of the unused recovery codes
+ALTER TABLE users ADD COLUMN totp_recovery_codes TEXT;
+
+ALTER TABLE sessions ADD COLUMN second_factor_verified BOOLEAN NOT NULL DEFAULT FALSE;
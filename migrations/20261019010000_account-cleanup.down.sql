@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX password_resets_created_at;
+DROP INDEX mail_verification_created_at;
+ALTER TABLE users DROP COLUMN deletion_notified_at;
+ALTER TABLE users DROP COLUMN created_at;
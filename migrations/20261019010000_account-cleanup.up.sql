@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- Unverified accounts are deleted some time after their registration,
+-- accounts from before count from now.
+ALTER TABLE users ADD COLUMN created_at TIMESTAMP;
+UPDATE users SET created_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now');
+-- When the user was told that the unverified account will be deleted
+ALTER TABLE users ADD COLUMN deletion_notified_at TIMESTAMP;
+
+CREATE INDEX mail_verification_created_at ON mail_verification (created_at);
+CREATE INDEX password_resets_created_at ON password_resets (created_at);
@@ -0,0 +1,253 @@
+use std::{sync::Arc, time::Duration};
+
+use askama::Template;
+use serde_json::json;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tracing::{error, info};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    mailer::Mailer, reloadable_sqlite::ReloadableSqlite, templates::AccountDeletionNoticeMail,
+};
+
+/// How long the cleanup sleeps between runs.
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How long tokens and unverified accounts are kept.
+#[derive(Debug, Clone)]
+pub struct AccountLifetimes {
+    pub email_verification: Duration,
+    pub password_reset: Duration,
+    /// Unverified accounts older than this are told that they will be
+    /// deleted once the verification link of that mail expires.
+    pub unverified_account: Duration,
+}
+
+impl Default for AccountLifetimes {
+    fn default() -> Self {
+        Self {
+            email_verification: Duration::from_secs(7 * 24 * 60 * 60),
+            password_reset: Duration::from_secs(60 * 60),
+            unverified_account: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// What a cleanup run did.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cleanup {
+    pub expired_tokens: u64,
+    pub notified_accounts: usize,
+    pub deleted_accounts: usize,
+}
+
+/// Clean up tokens and unverified accounts in the background until the
+/// server stops.
+pub fn spawn(
+    db: ReloadableSqlite,
+    mailer: Arc<dyn Mailer>,
+    base_url: String,
+    lifetimes: AccountLifetimes,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+            let pool = db.get().await.clone();
+            match run(
+                &pool,
+                mailer.as_ref(),
+                &base_url,
+                &lifetimes,
+                OffsetDateTime::now_utc(),
+            )
+            .await
+            {
+                Ok(cleanup) if cleanup == Cleanup::default() => {}
+                Ok(cleanup) => info!("Account cleanup: {cleanup:?}"),
+                Err(e) => error!("Account cleanup failed: {e}"),
+            }
+        }
+    });
+}
+
+/// Delete expired tokens and unverified accounts whose deletion notice
+/// expired, and send the deletion notice to unverified accounts that are
+/// old enough.
+///
+/// Accounts of admins and of club members are never deleted.
+pub async fn run(
+    db: &SqlitePool,
+    mailer: &dyn Mailer,
+    base_url: &str,
+    lifetimes: &AccountLifetimes,
+    now: OffsetDateTime,
+) -> sqlx::Result<Cleanup> {
+    let deleted_accounts = delete_unverified(db, now - lifetimes.email_verification).await?;
+    let notified_accounts = notify_unverified(db, mailer, base_url, lifetimes, now).await?;
+    let expired_tokens = delete_expired_tokens(db, lifetimes, now).await?;
+    Ok(Cleanup {
+        expired_tokens,
+        notified_accounts,
+        deleted_accounts,
+    })
+}
+
+async fn delete_expired_tokens(
+    db: &SqlitePool,
+    lifetimes: &AccountLifetimes,
+    now: OffsetDateTime,
+) -> sqlx::Result<u64> {
+    let verification_cutoff = now - lifetimes.email_verification;
+    let reset_cutoff = now - lifetimes.password_reset;
+    let verifications = sqlx::query!(
+        "DELETE FROM mail_verification WHERE created_at <= ?",
+        verification_cutoff
+    )
+    .execute(db)
+    .await?
+    .rows_affected();
+    let resets = sqlx::query!(
+        "DELETE FROM password_resets WHERE created_at <= ?",
+        reset_cutoff
+    )
+    .execute(db)
+    .await?
+    .rows_affected();
+    Ok(verifications + resets)
+}
+
+/// Delete the accounts that were notified before `cutoff` and are still
+/// unverified.
+async fn delete_unverified(db: &SqlitePool, cutoff: OffsetDateTime) -> sqlx::Result<usize> {
+    let users = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", email FROM users
+        WHERE NOT email_verified AND NOT is_admin AND deletion_notified_at <= ?
+          AND NOT EXISTS (SELECT 1 FROM clubs WHERE owner_id = users.id)
+          AND NOT EXISTS (SELECT 1 FROM club_memberships WHERE user_id = users.id)
+        "#,
+        cutoff
+    )
+    .fetch_all(db)
+    .await?;
+
+    for user in &users {
+        let mut tx = db.begin().await?;
+        sqlx::query!("DELETE FROM mail_verification WHERE user_id = ?", user.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM password_resets WHERE user_id = ?", user.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM users WHERE id = ?", user.id)
+            .execute(&mut *tx)
+            .await?;
+        let payload = json!({ "email": user.email }).to_string();
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (user_id, command, payload)
+            VALUES (?, 'delete_unverified_account', ?)
+            "#,
+            user.id,
+            payload
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        info!("Deleted unverified account {}", user.id);
+    }
+    Ok(users.len())
+}
+
+/// Send a new verification link to unverified accounts older than the grace
+/// period, with the notice that the account is deleted when it expires.
+async fn notify_unverified(
+    db: &SqlitePool,
+    mailer: &dyn Mailer,
+    base_url: &str,
+    lifetimes: &AccountLifetimes,
+    now: OffsetDateTime,
+) -> sqlx::Result<usize> {
+    let cutoff = now - lifetimes.unverified_account;
+    let users = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", name, email FROM users
+        WHERE NOT email_verified AND NOT is_admin AND deletion_notified_at IS NULL
+          AND created_at <= ?
+          AND NOT EXISTS (SELECT 1 FROM clubs WHERE owner_id = users.id)
+          AND NOT EXISTS (SELECT 1 FROM club_memberships WHERE user_id = users.id)
+        "#,
+        cutoff
+    )
+    .fetch_all(db)
+    .await?;
+
+    let days = lifetimes
+        .email_verification
+        .as_secs()
+        .div_ceil(24 * 60 * 60);
+    let mut notified = 0;
+    for user in users {
+        sqlx::query!("DELETE FROM mail_verification WHERE user_id = ?", user.id)
+            .execute(db)
+            .await?;
+        let email_token = Uuid::new_v4();
+        sqlx::query!(
+            r#"
+            INSERT INTO mail_verification (id, user_id, created_at)
+            VALUES (?, ?, ?)
+            "#,
+            email_token,
+            user.id,
+            now
+        )
+        .execute(db)
+        .await?;
+
+        let verify_link = Url::parse(base_url)
+            .expect("Invalid Base URL")
+            .join(&format!("/verify_email?token={}", email_token))
+            .expect("Invalid URL")
+            .to_string();
+        let body = match (AccountDeletionNoticeMail {
+            name: &user.name,
+            verify_link: &verify_link,
+            days,
+        })
+        .render()
+        {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Could not render the deletion notice: {e}");
+                continue;
+            }
+        };
+        // Without the notice the account is kept and notified next time
+        if let Err(e) = mailer
+            .send_text(
+                &user.email,
+                "Freestyle Cup NRW - Konto wird gelöscht",
+                &body,
+            )
+            .await
+        {
+            error!(
+                "Could not send the deletion notice to user {}: {e}",
+                user.id
+            );
+            continue;
+        }
+        sqlx::query!(
+            "UPDATE users SET deletion_notified_at = ? WHERE id = ?",
+            now,
+            user.id
+        )
+        .execute(db)
+        .await?;
+        notified += 1;
+    }
+    Ok(notified)
+}
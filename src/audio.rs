@@ -0,0 +1,234 @@
+use std::io::Cursor;
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{CODEC_TYPE_NULL, CodecParameters, Decoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader, Packet},
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+    units::TimeBase,
+};
+
+use crate::{
+    http_server::HttpError,
+    loudness::{Loudness, LoudnessMeter},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AudioError {
+    #[error("Die Datei ist keine unterstützte Audiodatei.")]
+    NotAudio,
+    #[error("Der Codec der Audiodatei wird nicht unterstützt.")]
+    UnsupportedCodec,
+    #[error("Die Audiodatei ist beschädigt.")]
+    Broken,
+    #[error("Die Audiodatei konnte nicht geschrieben werden.")]
+    Write,
+}
+
+impl From<AudioError> for HttpError {
+    fn from(e: AudioError) -> Self {
+        HttpError::ErrorMessages(e.to_string())
+    }
+}
+
+/// What the music checker needs to know about an uploaded song.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct AudioInfo {
+    pub duration_seconds: f64,
+    /// Short name of the codec, e.g. `mp3` or `aac`.
+    pub codec: String,
+    /// Average bits per second.
+    pub bitrate: i64,
+}
+
+/// Read the format, codec and duration of an audio file.
+///
+/// The extension is only a hint, the format is detected from the content.
+/// Files without a length in their header are read packet by packet.
+pub fn probe(data: &[u8], extension: Option<&str>) -> Result<AudioInfo, AudioError> {
+    let mut song = Song::open(data, extension)?;
+    let params = song.params.clone();
+
+    let codecs = symphonia::default::get_codecs();
+    let codec = codecs
+        .get_codec(params.codec)
+        .ok_or(AudioError::UnsupportedCodec)?
+        .short_name
+        .to_string();
+
+    let time_base = params
+        .time_base
+        .or(params.sample_rate.map(|rate| TimeBase::new(1, rate)))
+        .ok_or(AudioError::Broken)?;
+    let frames = match params.n_frames {
+        Some(frames) => frames,
+        None => {
+            let mut frames = 0;
+            while let Some(packet) = song.next_packet()? {
+                frames += packet.dur();
+            }
+            frames
+        }
+    };
+
+    let time = time_base.calc_time(frames);
+    let duration_seconds = time.seconds as f64 + time.frac;
+    if duration_seconds <= 0.0 {
+        return Err(AudioError::Broken);
+    }
+    Ok(AudioInfo {
+        duration_seconds,
+        codec,
+        bitrate: (data.len() as f64 * 8.0 / duration_seconds).round() as i64,
+    })
+}
+
+/// The first audio track of a song and its decoder.
+struct Song {
+    format: Box<dyn FormatReader>,
+    track_id: u32,
+    params: CodecParameters,
+    decoder: Box<dyn Decoder>,
+}
+
+impl Song {
+    fn open(data: &[u8], extension: Option<&str>) -> Result<Self, AudioError> {
+        let mut hint = Hint::new();
+        if let Some(extension) = extension {
+            hint.with_extension(extension);
+        }
+        let source =
+            MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+        let format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                source,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|_| AudioError::NotAudio)?
+            .format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(AudioError::NotAudio)?;
+        let track_id = track.id;
+        let params = track.codec_params.clone();
+        let decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .map_err(|_| AudioError::UnsupportedCodec)?;
+
+        Ok(Self {
+            format,
+            track_id,
+            params,
+            decoder,
+        })
+    }
+
+    /// The next packet of the track, `None` at the end.
+    fn next_packet(&mut self) -> Result<Option<Packet>, AudioError> {
+        loop {
+            match self.format.next_packet() {
+                Ok(packet) if packet.track_id() == self.track_id => return Ok(Some(packet)),
+                Ok(_) => {}
+                Err(SymphoniaError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(None);
+                }
+                Err(_) => return Err(AudioError::Broken),
+            }
+        }
+    }
+
+    /// Decode the whole track into interleaved samples with the sample rate
+    /// and the number of channels.
+    ///
+    /// Single broken frames are skipped like a player would.
+    fn decode(
+        mut self,
+        mut on_samples: impl FnMut(u32, usize, &[f32]) -> Result<(), AudioError>,
+    ) -> Result<(), AudioError> {
+        let mut buffer: Option<SampleBuffer<f32>> = None;
+        while let Some(packet) = self.next_packet()? {
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(_) => return Err(AudioError::Broken),
+            };
+            let spec = *decoded.spec();
+            if buffer
+                .as_ref()
+                .is_none_or(|buffer| buffer.capacity() < decoded.capacity())
+            {
+                buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+            }
+            let buffer = buffer.as_mut().unwrap();
+            buffer.copy_interleaved_ref(decoded);
+            on_samples(spec.rate, spec.channels.count(), buffer.samples())?;
+        }
+        Ok(())
+    }
+}
+
+/// Measure the loudness of a song, `None` if it is silent.
+pub fn measure_loudness(
+    data: &[u8],
+    extension: Option<&str>,
+) -> Result<Option<Loudness>, AudioError> {
+    let mut meter: Option<LoudnessMeter> = None;
+    Song::open(data, extension)?.decode(|rate, channels, samples| {
+        meter
+            .get_or_insert_with(|| LoudnessMeter::new(rate, channels))
+            .add(samples);
+        Ok(())
+    })?;
+    Ok(meter.and_then(|meter| {
+        Some(Loudness {
+            integrated_lufs: meter.integrated_lufs()?,
+            peak_dbfs: meter.peak_dbfs(),
+        })
+    }))
+}
+
+/// A copy of the song with the gain applied as 16 bit WAV.
+pub fn normalized_wav(
+    data: &[u8],
+    extension: Option<&str>,
+    gain_db: f64,
+) -> Result<Vec<u8>, AudioError> {
+    let gain = 10f64.powf(gain_db / 20.0) as f32;
+    let mut wav = Cursor::new(Vec::new());
+    let mut target = Some(&mut wav);
+    let mut writer = None;
+    Song::open(data, extension)?.decode(|rate, channels, samples| {
+        if let Some(target) = target.take() {
+            let spec = hound::WavSpec {
+                channels: channels as u16,
+                sample_rate: rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            writer = Some(hound::WavWriter::new(target, spec).map_err(|_| AudioError::Write)?);
+        }
+        let writer = writer.as_mut().ok_or(AudioError::Write)?;
+        for sample in samples {
+            let sample = (sample * gain).clamp(-1.0, 1.0) * i16::MAX as f32;
+            writer
+                .write_sample(sample as i16)
+                .map_err(|_| AudioError::Write)?;
+        }
+        Ok(())
+    })?;
+    writer
+        .ok_or(AudioError::Broken)?
+        .finalize()
+        .map_err(|_| AudioError::Write)?;
+    Ok(wav.into_inner())
+}
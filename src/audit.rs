@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Query, Request},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{http_server::HttpError, reloadable_sqlite::ReloadableSqlite};
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+/// The body limit of the command routes.
+const MAX_BODY_SIZE: usize = 1024 * 1024 * 10;
+
+/// The state before and after a command, set by the command handler.
+///
+/// The handle is available as request extension in all command routes.
+/// Commands that don't set it are still recorded with their payload. The
+/// user is set by the auth extractor, so the session is only looked up once.
+#[derive(Debug, Clone, Default)]
+pub struct Audit(Arc<Mutex<AuditChange>>);
+
+#[derive(Debug, Default)]
+struct AuditChange {
+    user_id: Option<Uuid>,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl Audit {
+    pub fn user(&self, user_id: Uuid) {
+        self.0.lock().unwrap().user_id = Some(user_id);
+    }
+
+    pub fn before(&self, value: impl Serialize) {
+        self.0.lock().unwrap().before = serde_json::to_value(value).ok();
+    }
+
+    pub fn after(&self, value: impl Serialize) {
+        self.0.lock().unwrap().after = serde_json::to_value(value).ok();
+    }
+}
+
+/// Replace passwords, tokens and codes so they never end up in the log.
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.contains("password") || key.contains("token") || key.contains("code") {
+                    *value = Value::String("***".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Record every successful command in the audit log.
+///
+/// The payload is the JSON body of the request, or its query parameters if
+/// it has no JSON body.
+pub async fn audit_command(request: Request, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let request_id = parts
+        .headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let command = parts
+        .uri
+        .path()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    let (body, mut payload) = if is_json {
+        let Ok(bytes) = to_bytes(body, MAX_BODY_SIZE).await else {
+            return HttpError::StatusCode(axum::http::StatusCode::PAYLOAD_TOO_LARGE)
+                .into_response();
+        };
+        let payload = serde_json::from_slice(&bytes).ok();
+        (Body::from(bytes), payload)
+    } else {
+        let payload = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .ok()
+            .filter(|query| !query.is_empty())
+            .and_then(|query| serde_json::to_value(query.0).ok());
+        (body, payload)
+    };
+    if let Some(payload) = &mut payload {
+        redact(payload);
+    }
+
+    let audit = Audit::default();
+    parts.extensions.insert(audit.clone());
+    let db = parts.extensions.get::<ReloadableSqlite>().cloned();
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    if response.status().is_success()
+        && let Some(db) = db
+    {
+        let change = std::mem::take(&mut *audit.0.lock().unwrap());
+        let user_id = change.user_id;
+        let payload = payload.as_ref().map(Value::to_string);
+        let before = change.before.as_ref().map(Value::to_string);
+        let after = change.after.as_ref().map(Value::to_string);
+        let db = db.get().await.clone();
+        if let Err(e) = sqlx::query!(
+            r#"
+            INSERT INTO audit_log (user_id, request_id, command, payload, "before", "after")
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            user_id,
+            request_id,
+            command,
+            payload,
+            before,
+            after,
+        )
+        .execute(&db)
+        .await
+        {
+            error!("Could not write the audit log for {command}: {e}");
+        }
+    }
+    response
+}
@@ -1,12 +1,14 @@
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
 use clap::Parser;
 use nrw_freestyle_cup_registration::{
+    account_cleanup::{self, AccountLifetimes},
     http_server::{HttpServer, HttpServerOptions},
     jwt::JWTConfig,
-    mailer::Mailer,
+    mailer::{Mailer, SmtpMailer},
     reloadable_sqlite::ReloadableSqlite,
-    system_status::StatusOptions,
+    song_analysis,
+    song_storage::{LocalSongStorage, S3Config, S3SongStorage, SongStorage},
     utils,
 };
 use password_auth::generate_hash;
@@ -14,11 +16,14 @@ use serde::Deserialize;
 use sqlx::migrate;
 use time::{OffsetDateTime, format_description::well_known::Iso8601};
 use tokio::signal;
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::FmtSubscriber;
 use url::Url;
 use uuid::Uuid;
 
+/// The migration that creates the first competition.
+const COMPETITIONS_MIGRATION: i64 = 20261018120000;
+
 #[derive(Debug, Parser)]
 struct Args {
     #[clap(long, default_value = "http://localhost:3000", env = "BASE_URL")]
@@ -37,22 +42,53 @@ struct Args {
         env = "DATABASE"
     )]
     pub db: PathBuf,
+    /// Directory of the songs unless they are kept in S3.
     #[clap(long, default_value = "./data", env = "data")]
     pub data: PathBuf,
+    /// Keep the songs in this S3 bucket instead of the data directory.
+    #[clap(long, env = "S3_BUCKET")]
+    pub s3_bucket: Option<String>,
+    /// Endpoint of an S3 compatible server like MinIO, AWS if not set.
+    #[clap(long, env = "S3_ENDPOINT")]
+    pub s3_endpoint: Option<String>,
+    #[clap(long, env = "S3_REGION", default_value = "us-east-1")]
+    pub s3_region: String,
+    #[clap(long, env = "S3_ACCESS_KEY_ID", default_value = "")]
+    pub s3_access_key_id: String,
+    #[clap(long, env = "S3_SECRET_ACCESS_KEY", default_value = "")]
+    pub s3_secret_access_key: String,
+    #[clap(long, env = "S3_PREFIX")]
+    pub s3_prefix: Option<String>,
     #[clap(long, env = "JWT_SECRET", default_value = "supersecretsupersecret")]
     pub jwt_secret: String,
     #[clap(long, env = "ADMIN")]
     pub admin: Option<AdminArgs>,
+    /// Deadlines of the competition created on the first start. Later they
+    /// are changed with `edit_competition` and these are ignored.
     #[clap(long, env = "START_REGISTER_DATE", value_parser = parse_date)]
-    pub start_register_date: OffsetDateTime,
+    pub start_register_date: Option<OffsetDateTime>,
     #[clap(long, env = "END_REGISTER_DATE", value_parser = parse_date)]
-    pub end_register_date: OffsetDateTime,
+    pub end_register_date: Option<OffsetDateTime>,
     #[clap(long, env = "END_MUSIC_UPLOAD_DATE", value_parser = parse_date)]
-    pub end_music_upload_date: OffsetDateTime,
+    pub end_music_upload_date: Option<OffsetDateTime>,
     #[clap(long, env = "INSECURE_COOKIES")]
     pub insecure_cookies: bool,
     #[clap(long, env = "RELOAD_DB_TOKEN", default_value = "reload_db")]
     pub reload_db_token: String,
+    /// Rate limit logins by the `X-Forwarded-For` header of a reverse proxy.
+    #[clap(long, env = "TRUST_FORWARDED_FOR")]
+    pub trust_forwarded_for: bool,
+    /// Write a copy of every song at the target loudness of EBU R128.
+    #[clap(long, env = "NORMALIZE_SONGS")]
+    pub normalize_songs: bool,
+    #[clap(long, env = "EMAIL_VERIFICATION_TTL_HOURS", default_value_t = 7 * 24)]
+    pub email_verification_ttl_hours: u64,
+    #[clap(long, env = "PASSWORD_RESET_TTL_MINUTES", default_value_t = 60)]
+    pub password_reset_ttl_minutes: u64,
+    /// Unverified accounts are deleted this long after their registration,
+    /// plus the validity of the verification link sent with the notice.
+    #[clap(long, env = "UNVERIFIED_ACCOUNT_GRACE_DAYS", default_value_t = 30)]
+    pub unverified_account_grace_days: u64,
 }
 
 fn parse_date(s: &str) -> Result<OffsetDateTime, time::error::Parse> {
@@ -90,12 +126,12 @@ async fn main() -> eyre::Result<()> {
     info!("Starting registration system.");
     info!("Build Mail client");
 
-    let mailer = Mailer::new(
+    let mailer: Arc<dyn Mailer> = Arc::new(SmtpMailer::new(
         &args.smtp_server,
         &args.smtp_username,
         &args.smtp_password,
         &args.smtp_username,
-    );
+    ));
 
     info!("Connecting to database");
 
@@ -103,6 +139,7 @@ async fn main() -> eyre::Result<()> {
         .await
         .expect("Couldn't connect to database");
 
+    let seeds_competition = !migration_applied(&db, COMPETITIONS_MIGRATION).await;
     info!("Running database migrations");
     migrate!("./migrations").run(&db).await?;
 
@@ -111,6 +148,24 @@ async fn main() -> eyre::Result<()> {
         insert_admin_user(&db, &admin.name, &admin.email, &admin.password).await?;
     }
 
+    let deadlines = [
+        args.start_register_date,
+        args.end_register_date,
+        args.end_music_upload_date,
+    ];
+    if seeds_competition {
+        info!("Setting the deadlines of the new competition");
+        update_active_competition_deadlines(
+            &db,
+            args.start_register_date,
+            args.end_register_date,
+            args.end_music_upload_date,
+        )
+        .await?;
+    } else if deadlines.iter().any(Option::is_some) {
+        warn!("Ignoring the deadline arguments, the competition exists already");
+    }
+
     info!("Initializing acts");
     utils::initialize_acts(&db).await.unwrap();
 
@@ -126,22 +181,52 @@ async fn main() -> eyre::Result<()> {
         args.insecure_cookies,
     );
 
+    let db = ReloadableSqlite::new(db, args.db.to_string_lossy().to_string());
+
+    let storage: Arc<dyn SongStorage> = match args.s3_bucket {
+        Some(bucket) => {
+            info!("Keeping songs in S3 bucket {bucket}");
+            Arc::new(S3SongStorage::new(S3Config {
+                endpoint: args.s3_endpoint,
+                region: args.s3_region,
+                bucket,
+                access_key_id: args.s3_access_key_id,
+                secret_access_key: args.s3_secret_access_key,
+                prefix: args.s3_prefix,
+            })?)
+        }
+        None => Arc::new(LocalSongStorage::new(args.data)),
+    };
+
+    info!("Starting song analysis");
+    song_analysis::spawn(db.clone(), storage.clone(), args.normalize_songs);
+
+    let lifetimes = AccountLifetimes {
+        email_verification: Duration::from_secs(args.email_verification_ttl_hours * 60 * 60),
+        password_reset: Duration::from_secs(args.password_reset_ttl_minutes * 60),
+        unverified_account: Duration::from_secs(args.unverified_account_grace_days * 24 * 60 * 60),
+    };
+    info!("Starting account cleanup");
+    account_cleanup::spawn(
+        db.clone(),
+        mailer.clone(),
+        args.base_url.to_string(),
+        lifetimes.clone(),
+    );
+
     info!("Starting HTTP server");
     HttpServer::new(
         HttpServerOptions {
             bind_address: args.http_address,
             base_url: args.base_url.to_string(),
-            data_path: args.data,
             reload_db_token: args.reload_db_token,
+            trust_forwarded_for: args.trust_forwarded_for,
+            lifetimes,
         },
-        ReloadableSqlite::new(db, args.db.to_string_lossy().to_string()),
+        db,
         Arc::new(jwt_config),
-        Arc::new(mailer),
-        Arc::new(StatusOptions {
-            start_register_date: args.start_register_date,
-            end_register_date: args.end_register_date,
-            end_music_upload_date: args.end_music_upload_date,
-        }),
+        mailer,
+        storage,
     )
     .start(shutdown_signal())
     .await?;
@@ -186,6 +271,41 @@ async fn insert_admin_user(
     Ok(())
 }
 
+/// Whether a migration was applied before this start, `false` for a new
+/// database.
+async fn migration_applied(db: &sqlx::SqlitePool, version: i64) -> bool {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM _sqlx_migrations WHERE version = ? AND success)",
+    )
+    .bind(version)
+    .fetch_one(db)
+    .await
+    .unwrap_or(false)
+}
+
+async fn update_active_competition_deadlines(
+    db: &sqlx::SqlitePool,
+    start_register_date: Option<OffsetDateTime>,
+    end_register_date: Option<OffsetDateTime>,
+    end_music_upload_date: Option<OffsetDateTime>,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE competitions SET
+            start_register_date = COALESCE(?, start_register_date),
+            end_register_date = COALESCE(?, end_register_date),
+            end_music_upload_date = COALESCE(?, end_music_upload_date)
+        WHERE is_active
+        "#,
+        start_register_date,
+        end_register_date,
+        end_music_upload_date
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -0,0 +1,123 @@
+use sqlx::{SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::http_server::HttpError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MembershipError {
+    #[error("{0} ist bereits Mitglied des Vereins.")]
+    AlreadyMember(String),
+    #[error("Ein Verein braucht mindestens eine*n Verantwortliche*n.")]
+    LastOwner,
+    #[error("Die Einladung gilt für eine andere Email Adresse.")]
+    OtherEmail,
+    #[error("Bitte bestätige zuerst deine Email Adresse.")]
+    EmailNotVerified,
+}
+
+impl From<MembershipError> for HttpError {
+    fn from(e: MembershipError) -> Self {
+        HttpError::ErrorMessages(e.to_string())
+    }
+}
+
+/// What a member may do in a club, from the least to the most.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum ClubRole {
+    /// Sees the starters, judges and songs of the club.
+    ReadOnly,
+    /// Registers starters and judges and uploads their music.
+    Trainer,
+    /// Renames the club and manages its members.
+    Owner,
+}
+
+impl ClubRole {
+    /// Name of the role in mails.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            ClubRole::ReadOnly => "Lesezugriff",
+            ClubRole::Trainer => "Trainer*in",
+            ClubRole::Owner => "Verantwortliche*r",
+        }
+    }
+}
+
+/// The role of a user in a club.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct ClubMembership {
+    pub club_id: Uuid,
+    pub role: ClubRole,
+}
+
+/// All clubs a user is a member of.
+pub async fn list(db: &SqlitePool, user_id: Uuid) -> Result<Vec<ClubMembership>, sqlx::Error> {
+    sqlx::query_as!(
+        ClubMembership,
+        r#"
+        SELECT club_id as "club_id!: Uuid", role as "role!: ClubRole"
+        FROM club_memberships
+        WHERE user_id = ?
+        ORDER BY created_at, club_id
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Add a user to a club or change the role of a member.
+///
+/// Users without a club see the new club from now on.
+pub async fn set(
+    db: &mut SqliteConnection,
+    club_id: Uuid,
+    user_id: Uuid,
+    role: ClubRole,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO club_memberships (club_id, user_id, role) VALUES (?, ?, ?)
+        ON CONFLICT (club_id, user_id) DO UPDATE SET role = excluded.role
+        "#,
+        club_id,
+        user_id,
+        role
+    )
+    .execute(&mut *db)
+    .await?;
+    sqlx::query!(
+        "UPDATE users SET club_id = ? WHERE id = ? AND club_id IS NULL",
+        club_id,
+        user_id
+    )
+    .execute(&mut *db)
+    .await?;
+    Ok(())
+}
+
+/// Number of owners left in a club.
+pub async fn count_owners(db: &mut SqliteConnection, club_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM club_memberships WHERE club_id = ? AND role = 'owner'",
+        club_id
+    )
+    .fetch_one(&mut *db)
+    .await
+}
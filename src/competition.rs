@@ -0,0 +1,184 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use sqlx::{SqliteConnection, SqlitePool};
+use time::{Date, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    http_server::HttpError, reloadable_sqlite::ReloadableSqlite, system_status::Capabilities,
+};
+
+time::serde::format_description!(iso_date, Date, "[year]-[month]-[day]");
+
+/// A season of the cup with its own categories, timeplan, clubs and acts.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct Competition {
+    pub id: Uuid,
+    pub name: String,
+    pub venue: Option<String>,
+    /// Days of the competition in ascending order.
+    #[serde(with = "iso_date_list")]
+    #[schema(value_type = Vec<Date>)]
+    pub days: Vec<Date>,
+    #[serde(with = "time::serde::iso8601")]
+    pub start_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub end_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub end_music_upload_date: OffsetDateTime,
+    pub is_active: bool,
+}
+
+/// (De)serializes a list of dates as `YYYY-MM-DD` strings.
+pub(crate) mod iso_date_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use time::Date;
+
+    #[derive(Serialize, Deserialize)]
+    struct IsoDate(#[serde(with = "super::iso_date")] Date);
+
+    pub fn serialize<S: Serializer>(days: &[Date], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(days.iter().copied().map(IsoDate))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Date>, D::Error> {
+        let days = Vec::<IsoDate>::deserialize(deserializer)?;
+        Ok(days.into_iter().map(|day| day.0).collect())
+    }
+}
+
+impl Competition {
+    pub async fn get(db: &SqlitePool, id: Uuid) -> sqlx::Result<Option<Self>> {
+        let Some(competition) = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!: Uuid",
+                name,
+                venue,
+                start_register_date as "start_register_date: OffsetDateTime",
+                end_register_date as "end_register_date: OffsetDateTime",
+                end_music_upload_date as "end_music_upload_date: OffsetDateTime",
+                is_active
+            FROM competitions WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(db)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            id: competition.id,
+            name: competition.name,
+            venue: competition.venue,
+            days: Self::get_days(db, competition.id).await?,
+            start_register_date: competition.start_register_date,
+            end_register_date: competition.end_register_date,
+            end_music_upload_date: competition.end_music_upload_date,
+            is_active: competition.is_active,
+        }))
+    }
+
+    /// The competition all registrations currently go to.
+    pub async fn get_active(db: &SqlitePool) -> sqlx::Result<Self> {
+        let id = sqlx::query!(r#"SELECT id as "id!: Uuid" FROM competitions WHERE is_active"#)
+            .fetch_one(db)
+            .await?
+            .id;
+        Self::get(db, id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn list(db: &SqlitePool) -> sqlx::Result<Vec<Self>> {
+        let ids = sqlx::query!(
+            r#"SELECT id as "id!: Uuid" FROM competitions ORDER BY start_register_date DESC"#
+        )
+        .fetch_all(db)
+        .await?;
+        let mut competitions = Vec::with_capacity(ids.len());
+        for row in ids {
+            if let Some(competition) = Self::get(db, row.id).await? {
+                competitions.push(competition);
+            }
+        }
+        Ok(competitions)
+    }
+
+    async fn get_days(db: &SqlitePool, id: Uuid) -> sqlx::Result<Vec<Date>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT day as "day!: Date" FROM competition_days WHERE competition_id = ? ORDER BY day
+            "#,
+            id
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| row.day)
+        .collect())
+    }
+
+    /// Replace the days of a competition.
+    pub async fn set_days(db: &mut SqliteConnection, id: Uuid, days: &[Date]) -> sqlx::Result<()> {
+        sqlx::query!("DELETE FROM competition_days WHERE competition_id = ?", id)
+            .execute(&mut *db)
+            .await?;
+        for day in days {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO competition_days (competition_id, day) VALUES (?, ?)",
+                id,
+                day
+            )
+            .execute(&mut *db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// The reference date for the age of the starters.
+    pub fn first_day(&self) -> Option<Date> {
+        self.days.first().copied()
+    }
+
+    pub fn get_system_status(&self) -> Capabilities {
+        let now = OffsetDateTime::now_utc();
+
+        let in_register_period = now >= self.start_register_date && now <= self.end_register_date;
+
+        Capabilities {
+            can_register: in_register_period,
+            can_create_club: in_register_period,
+            can_register_starter: in_register_period,
+            can_register_judge: in_register_period,
+            can_upload_music: now <= self.end_music_upload_date,
+        }
+    }
+}
+
+/// The currently active competition.
+///
+/// Categories, timeplan entries, clubs and acts of other competitions are
+/// hidden from the API.
+#[derive(Debug, Clone)]
+pub struct ActiveCompetition(pub Competition);
+
+impl std::ops::Deref for ActiveCompetition {
+    type Target = Competition;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for ActiveCompetition
+where
+    S: Send + Sync,
+{
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let db = parts.extensions.get::<ReloadableSqlite>().unwrap();
+        let db = db.get().await.clone();
+        Ok(ActiveCompetition(Competition::get_active(&db).await?))
+    }
+}
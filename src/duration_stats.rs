@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use time::OffsetDateTime;
+
+/// The recorded progress of a category in one competition.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryRun {
+    pub category: String,
+    /// When the timeplan entry of the category was started.
+    pub entry_started_at: Option<OffsetDateTime>,
+    /// Started and ended time of the acts that were started.
+    pub acts: Vec<(OffsetDateTime, Option<OffsetDateTime>)>,
+}
+
+/// Statistics of a duration in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct DurationStats {
+    pub samples: usize,
+    pub mean_seconds: f64,
+    pub median_seconds: f64,
+    pub p90_seconds: f64,
+}
+
+impl DurationStats {
+    /// Negative samples come from clock or handling errors and are ignored.
+    pub fn from_samples(samples: &[i64]) -> Option<Self> {
+        let mut samples: Vec<_> = samples.iter().copied().filter(|&s| s >= 0).collect();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        let count = samples.len();
+        let mean_seconds = samples.iter().sum::<i64>() as f64 / count as f64;
+        let median_seconds = if count % 2 == 0 {
+            (samples[count / 2 - 1] + samples[count / 2]) as f64 / 2.0
+        } else {
+            samples[count / 2] as f64
+        };
+        // Nearest rank
+        let p90_seconds = samples[(count * 9).div_ceil(10) - 1] as f64;
+        Some(Self {
+            samples: count,
+            mean_seconds,
+            median_seconds,
+            p90_seconds,
+        })
+    }
+
+    /// The median rounded up to full seconds.
+    ///
+    /// Unlike the mean it is not skewed by breaks between the acts.
+    pub fn suggested_seconds(&self) -> i64 {
+        self.median_seconds.ceil() as i64
+    }
+}
+
+/// The measured durations of a category over all its runs.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct CategoryDurations {
+    pub category: String,
+    /// From the start of the timeplan entry to the start of the first act.
+    pub einfahrzeit: Option<DurationStats>,
+    /// From the start to the end of an act.
+    pub act: Option<DurationStats>,
+    /// From the end of an act to the start of the next act.
+    pub judge: Option<DurationStats>,
+}
+
+/// Measure the durations of the runs, grouped by category name.
+pub fn category_durations(runs: &[CategoryRun]) -> Vec<CategoryDurations> {
+    #[derive(Default)]
+    struct Samples {
+        einfahrzeit: Vec<i64>,
+        act: Vec<i64>,
+        judge: Vec<i64>,
+    }
+
+    let mut by_category = BTreeMap::<&str, Samples>::new();
+    for run in runs {
+        let samples = by_category.entry(&run.category).or_default();
+        let mut acts = run.acts.clone();
+        acts.sort_by_key(|(started_at, _)| *started_at);
+
+        if let (Some(entry_started_at), Some((first_started_at, _))) =
+            (run.entry_started_at, acts.first())
+        {
+            samples
+                .einfahrzeit
+                .push((*first_started_at - entry_started_at).whole_seconds());
+        }
+        for (started_at, ended_at) in &acts {
+            if let Some(ended_at) = ended_at {
+                samples.act.push((*ended_at - *started_at).whole_seconds());
+            }
+        }
+        for pair in acts.windows(2) {
+            if let (Some(ended_at), (next_started_at, _)) = (pair[0].1, pair[1]) {
+                samples
+                    .judge
+                    .push((next_started_at - ended_at).whole_seconds());
+            }
+        }
+    }
+
+    by_category
+        .into_iter()
+        .map(|(category, samples)| CategoryDurations {
+            category: category.to_string(),
+            einfahrzeit: DurationStats::from_samples(&samples.einfahrzeit),
+            act: DurationStats::from_samples(&samples.act),
+            judge: DurationStats::from_samples(&samples.judge),
+        })
+        .collect()
+}
@@ -1,12 +1,12 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{Json, response::IntoResponse};
 use serde::Serialize;
 use tracing::info;
 
 use crate::{
-    jwt::JWTConfig, mailer::Mailer, reloadable_sqlite::ReloadableSqlite,
-    system_status::StatusOptions,
+    account_cleanup::AccountLifetimes, jwt::JWTConfig, mailer::Mailer,
+    reloadable_sqlite::ReloadableSqlite, song_storage::SongStorage,
 };
 
 pub mod extractor;
@@ -23,13 +23,15 @@ pub enum HttpError {
     #[error("Database error: {0}")]
     DBError(#[from] sqlx::Error),
     #[error("Mail error: {0}")]
-    MailError(#[from] lettre::transport::smtp::Error),
+    MailError(#[from] crate::mailer::MailError),
     #[error("{0}")]
     ErrorMessages(String),
     #[error("Invalid credentials")]
     InvalidCredentials,
     #[error("Status code: {0}")]
     StatusCode(axum::http::StatusCode),
+    #[error("Too many requests, retry after {0:?}")]
+    TooManyRequests(std::time::Duration),
 }
 
 impl IntoResponse for HttpError {
@@ -51,6 +53,9 @@ pub enum ClientError {
     Generic(String),
     #[error("Status code: {0}")]
     StatusCode(u16),
+    /// Seconds until the next attempt is allowed.
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
 }
 
 impl From<HttpError> for ClientError {
@@ -64,23 +69,38 @@ impl From<HttpError> for ClientError {
             HttpError::ErrorMessages(e) => ClientError::Generic(format!("{e:?}")),
             HttpError::InvalidCredentials => ClientError::InvalidCredentials,
             HttpError::StatusCode(code) => ClientError::StatusCode(code.as_u16()),
+            // Round up, so clients never retry too early
+            HttpError::TooManyRequests(retry_after) => {
+                ClientError::TooManyRequests(retry_after.as_secs() + 1)
+            }
         }
     }
 }
 
 impl IntoResponse for ClientError {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
-        (
+        let retry_after = match &self {
+            Self::TooManyRequests(seconds) => Some(axum::http::HeaderValue::from(*seconds)),
+            _ => None,
+        };
+        let mut response = (
             match &self {
                 Self::NotFound => axum::http::StatusCode::NOT_FOUND,
                 Self::InternalServerError => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
                 Self::Generic(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
                 Self::InvalidCredentials => axum::http::StatusCode::UNAUTHORIZED,
                 Self::StatusCode(code) => axum::http::StatusCode::from_u16(*code).unwrap(),
+                Self::TooManyRequests(_) => axum::http::StatusCode::TOO_MANY_REQUESTS,
             },
             Json(self),
         )
-            .into_response()
+            .into_response();
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, retry_after);
+        }
+        response
     }
 }
 
@@ -88,16 +108,20 @@ impl IntoResponse for ClientError {
 pub struct HttpServerOptions {
     pub bind_address: String,
     pub base_url: String,
-    pub data_path: PathBuf,
     pub reload_db_token: String,
+    /// Take the client address from the last `X-Forwarded-For` entry, only
+    /// safe behind a reverse proxy that sets it.
+    pub trust_forwarded_for: bool,
+    /// How long email verification and password reset links are valid.
+    pub lifetimes: AccountLifetimes,
 }
 
 pub struct HttpServer {
     db: ReloadableSqlite,
-    mailer: Arc<Mailer>,
+    mailer: Arc<dyn Mailer>,
     jwt: Arc<JWTConfig>,
     options: Arc<HttpServerOptions>,
-    status_options: Arc<StatusOptions>,
+    storage: Arc<dyn SongStorage>,
 }
 
 impl HttpServer {
@@ -105,15 +129,15 @@ impl HttpServer {
         options: HttpServerOptions,
         db: ReloadableSqlite,
         jwt: Arc<JWTConfig>,
-        mailer: Arc<Mailer>,
-        status_options: Arc<StatusOptions>,
+        mailer: Arc<dyn Mailer>,
+        storage: Arc<dyn SongStorage>,
     ) -> Self {
         Self {
             db,
             mailer,
             options: Arc::new(options),
             jwt,
-            status_options,
+            storage,
         }
     }
 
@@ -126,17 +150,20 @@ impl HttpServer {
             self.db.clone(),
             self.mailer.clone(),
             self.jwt.clone(),
-            self.status_options.clone(),
+            self.storage.clone(),
         );
         let address = self.options.bind_address.clone();
 
         tokio::spawn(async move {
             info!("Starting server on {}", address);
             let listener = tokio::net::TcpListener::bind(address).await.unwrap();
-            axum::serve(listener, app)
-                .with_graceful_shutdown(shutdown_signal)
-                .await
-                .unwrap();
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_signal)
+            .await
+            .unwrap();
         })
     }
 }
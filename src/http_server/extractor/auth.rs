@@ -9,26 +9,58 @@ use axum_extra::extract::CookieJar;
 use uuid::Uuid;
 
 use crate::{
-    jwt::{JWTClaims, JWTConfig},
+    audit::Audit,
+    club_membership::{self, ClubMembership, ClubRole},
+    jwt::JWTConfig,
     reloadable_sqlite::ReloadableSqlite,
+    session,
+    totp::SecondFactor,
 };
 
 #[derive(Debug)]
 pub struct Auth {
     pub user_id: Uuid,
+    /// The session of the access token.
+    pub session_id: Uuid,
     pub email: String,
     pub name: String,
     pub is_admin: bool,
+    pub is_music_operator: bool,
+    /// The club shown to the user, one of the memberships.
     pub club_id: Option<Uuid>,
+    pub memberships: Vec<ClubMembership>,
     pub email_verified: bool,
+    /// Whether the session still needs the second factor.
+    pub second_factor: SecondFactor,
 }
 
 impl Auth {
     pub fn is_admin(&self) -> bool {
         self.is_admin
     }
+
+    /// Admins and music operators run the music of the competition and
+    /// hear every song.
+    pub fn may_control_music(&self) -> bool {
+        self.is_admin || self.is_music_operator
+    }
+
+    /// The role of the user in a club, `None` for non-members.
+    pub fn club_role(&self, club_id: Uuid) -> Option<ClubRole> {
+        self.memberships
+            .iter()
+            .find(|membership| membership.club_id == club_id)
+            .map(|membership| membership.role)
+    }
 }
 
+/// A session that may still need the second factor.
+///
+/// Only for `whoami` and the commands that complete the login, everything
+/// else takes a fully authenticated [`Auth`].
+#[derive(Debug)]
+pub struct PartialAuth(pub Auth);
+
 impl<S> FromRequestParts<S> for Auth
 where
     S: Send + Sync,
@@ -36,15 +68,50 @@ where
     type Rejection = Error;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        <Auth as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+        let auth = <PartialAuth as FromRequestParts<S>>::from_request_parts(parts, state)
             .await?
-            .ok_or(Error::JwtMissing)
+            .0;
+        if auth.second_factor.is_satisfied() {
+            Ok(auth)
+        } else {
+            Err(Error::SecondFactorRequired)
+        }
     }
 }
 
+/// Sessions without the second factor are treated like anonymous users.
 impl<S: Send + Sync> OptionalFromRequestParts<S> for Auth {
     type Rejection = Error;
 
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(
+            <PartialAuth as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+                .await?
+                .map(|auth| auth.0)
+                .filter(|auth| auth.second_factor.is_satisfied()),
+        )
+    }
+}
+
+impl<S> FromRequestParts<S> for PartialAuth
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        <PartialAuth as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+            .await?
+            .ok_or(Error::JwtMissing)
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for PartialAuth {
+    type Rejection = Error;
+
     async fn from_request_parts(
         parts: &mut Parts,
         state: &S,
@@ -56,31 +123,48 @@ impl<S: Send + Sync> OptionalFromRequestParts<S> for Auth {
         if let Some(jwt) = cookies.get("jwt") {
             let jwt_config = parts.extensions.get::<Arc<JWTConfig>>().unwrap();
 
-            let token_data = jsonwebtoken::decode::<JWTClaims>(
-                jwt.value_trimmed(),
-                jwt_config.decode_key(),
-                jwt_config.validation(),
-            )
-            .map_err(|_| Error::JwtInvalid)?;
-            let user_id = token_data.claims.sub();
+            let claims = jwt_config
+                .decode_access_token(jwt.value_trimmed())
+                .ok_or(Error::JwtInvalid)?;
+            let user_id = claims.sub();
             let db = parts.extensions.get::<ReloadableSqlite>().unwrap();
+            let db = db.get().await.clone();
+            let second_factor_verified =
+                session::second_factor_verified(&db, claims.jti(), user_id)
+                    .await
+                    .map_err(|_| Error::UserNotFound)?
+                    .ok_or(Error::SessionRevoked)?;
             let user = sqlx::query!(
                 r#"
-                SELECT email, name, is_admin, club_id as "club_id: Uuid", email_verified FROM users WHERE id = ?
+                SELECT email, name, is_admin, is_music_operator, club_id as "club_id: Uuid", email_verified,
+                    totp_secret IS NOT NULL as "totp_enabled!: bool"
+                FROM users WHERE id = ?
                 "#,
                 user_id,
             )
-            .fetch_one(&db.get().await.clone())
+            .fetch_one(&db)
             .await
             .map_err(|_| Error::UserNotFound)?;
-            Ok(Some(Auth {
+            let memberships = club_membership::list(&db, user_id)
+                .await
+                .map_err(|_| Error::UserNotFound)?;
+            let second_factor =
+                SecondFactor::new(user.is_admin, user.totp_enabled, second_factor_verified);
+            if let Some(audit) = parts.extensions.get::<Audit>() {
+                audit.user(user_id);
+            }
+            Ok(Some(PartialAuth(Auth {
                 user_id,
+                session_id: claims.jti(),
                 email: user.email,
                 name: user.name,
                 is_admin: user.is_admin,
+                is_music_operator: user.is_music_operator,
                 club_id: user.club_id,
+                memberships,
                 email_verified: user.email_verified,
-            }))
+                second_factor,
+            })))
         } else {
             Ok(None)
         }
@@ -97,6 +181,10 @@ pub enum Error {
     JwtMissing,
     #[error("JWT invalid")]
     JwtInvalid,
+    #[error("Second factor required")]
+    SecondFactorRequired,
+    #[error("Session revoked")]
+    SessionRevoked,
     #[error("User not found")]
     UserNotFound,
 }
@@ -105,9 +193,11 @@ impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response<axum::body::Body> {
         let status = match self {
             Error::AdminRequired => StatusCode::FORBIDDEN,
-            Error::CookiesMissing | Error::JwtInvalid | Error::JwtMissing => {
-                StatusCode::UNAUTHORIZED
-            }
+            Error::CookiesMissing
+            | Error::JwtInvalid
+            | Error::JwtMissing
+            | Error::SecondFactorRequired
+            | Error::SessionRevoked => StatusCode::UNAUTHORIZED,
             Error::UserNotFound => StatusCode::INTERNAL_SERVER_ERROR,
         };
         (status, self.to_string()).into_response()
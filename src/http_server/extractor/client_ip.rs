@@ -0,0 +1,50 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+
+use crate::http_server::HttpServerOptions;
+
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// The address of the client, `None` if it's unknown.
+///
+/// Behind a trusted reverse proxy it's the last `X-Forwarded-For` entry,
+/// which the proxy appended, otherwise the peer of the connection.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let trust_forwarded_for = parts
+            .extensions
+            .get::<Arc<HttpServerOptions>>()
+            .is_some_and(|options| options.trust_forwarded_for);
+        if trust_forwarded_for {
+            return Ok(ClientIp(
+                parts
+                    .headers
+                    .get_all(FORWARDED_FOR_HEADER)
+                    .iter()
+                    .filter_map(|value| value.to_str().ok())
+                    .flat_map(|value| value.split(','))
+                    .next_back()
+                    .and_then(|ip| ip.trim().parse().ok()),
+            ));
+        }
+        Ok(ClientIp(
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip()),
+        ))
+    }
+}
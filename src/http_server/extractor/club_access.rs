@@ -0,0 +1,127 @@
+use std::fmt::Debug;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    club_membership::ClubRole,
+    http_server::{
+        HttpError,
+        extractor::auth::{self, Auth},
+    },
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+/// Authorization for club scoped resources.
+///
+/// Resolves the club owning a starter, judge or act and rejects access from
+/// users that aren't members of it or whose role is below the required one.
+/// Admins can access every club.
+pub struct ClubAccess {
+    pub auth: Auth,
+    db: SqlitePool,
+}
+
+impl Debug for ClubAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClubAccess")
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+impl ClubAccess {
+    fn has_role(&self, club_id: Uuid, role: ClubRole) -> bool {
+        self.auth.is_admin()
+            || self
+                .auth
+                .club_role(club_id)
+                .is_some_and(|member_role| member_role >= role)
+    }
+
+    /// Ensure that the user has at least the given role in the club.
+    pub fn check_club(&self, club_id: Uuid, role: ClubRole) -> Result<(), HttpError> {
+        if self.has_role(club_id, role) {
+            Ok(())
+        } else {
+            Err(HttpError::StatusCode(StatusCode::FORBIDDEN))
+        }
+    }
+
+    /// Ensure that the user has at least the given role in the club of the
+    /// starter.
+    pub async fn check_starter(&self, starter_id: Uuid, role: ClubRole) -> Result<(), HttpError> {
+        let club_id = sqlx::query!(
+            r#"
+            SELECT club_id as "club_id!: Uuid" FROM starter WHERE id = ?
+            "#,
+            starter_id,
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(HttpError::NotFound)?
+        .club_id;
+        self.check_club(club_id, role)
+    }
+
+    /// Ensure that the user has at least the given role in the club of the
+    /// judge.
+    pub async fn check_judge(&self, judge_id: Uuid, role: ClubRole) -> Result<(), HttpError> {
+        let club_id = sqlx::query!(
+            r#"
+            SELECT club_id as "club_id!: Uuid" FROM judge WHERE id = ?
+            "#,
+            judge_id,
+        )
+        .fetch_optional(&self.db)
+        .await?
+        .ok_or(HttpError::NotFound)?
+        .club_id;
+        self.check_club(club_id, role)
+    }
+
+    /// Ensure that the user has at least the given role in a club of the act.
+    ///
+    /// Pairs can consist of starters from different clubs, so every club with
+    /// a participant in the act has access.
+    pub async fn check_act(&self, act_id: Uuid, role: ClubRole) -> Result<(), HttpError> {
+        let club_ids = sqlx::query!(
+            r#"
+            SELECT DISTINCT starter.club_id as "club_id!: Uuid"
+            FROM act_participants JOIN starter ON act_participants.starter_id = starter.id
+            WHERE act_participants.act_id = ?
+            "#,
+            act_id,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        if club_ids.is_empty() {
+            return Err(HttpError::NotFound);
+        }
+        if club_ids.iter().any(|row| self.has_role(row.club_id, role)) {
+            Ok(())
+        } else {
+            Err(HttpError::StatusCode(StatusCode::FORBIDDEN))
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for ClubAccess
+where
+    S: Send + Sync,
+{
+    type Rejection = auth::Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth = Auth::from_request_parts(parts, state).await?;
+        let db = parts.extensions.get::<ReloadableSqlite>().unwrap();
+        Ok(ClubAccess {
+            auth,
+            db: db.get().await.clone(),
+        })
+    }
+}
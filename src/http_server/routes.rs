@@ -1,25 +1,26 @@
 pub mod command;
 pub mod http_types;
+pub mod live;
 pub mod query;
+pub mod songs;
 
-use axum::{Extension, Router, extract::Request, http::HeaderName};
+use axum::{Extension, Router, extract::Request, http::HeaderName, middleware, routing::get};
 use axum_embed::{FallbackBehavior, ServeEmbed};
 use rust_embed::RustEmbed;
 use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::{
     request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
-    services::ServeDir,
     trace::TraceLayer,
 };
 use tracing::{error, info_span};
 use utoipa::{OpenApi as OpenApiTrait, openapi::OpenApi};
-use utoipa_axum::router::OpenApiRouter;
+use utoipa_axum::{router::OpenApiRouter, routes};
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    jwt::JWTConfig, mailer::Mailer, reloadable_sqlite::ReloadableSqlite,
-    system_status::StatusOptions,
+    jwt::JWTConfig, live::LiveHub, login_throttle::LoginThrottle, mailer::Mailer,
+    reloadable_sqlite::ReloadableSqlite, session::refresh_session, song_storage::SongStorage,
 };
 
 use super::HttpServerOptions;
@@ -51,7 +52,8 @@ pub struct ApiDoc;
 fn get_openapi_router() -> (Router, OpenApi) {
     let command_query_router = OpenApiRouter::new()
         .nest("/command", command::get_command_router())
-        .nest("/query", query::get_query_router());
+        .nest("/query", query::get_query_router())
+        .routes(routes!(live::live));
     OpenApiRouter::with_openapi(ApiDoc::openapi())
         .nest("/api", command_query_router)
         .split_for_parts()
@@ -60,18 +62,21 @@ fn get_openapi_router() -> (Router, OpenApi) {
 pub fn get_api_router(
     http_options: Arc<HttpServerOptions>,
     db: ReloadableSqlite,
-    mailer: Arc<Mailer>,
+    mailer: Arc<dyn Mailer>,
     jwt_config: Arc<JWTConfig>,
-    status_options: Arc<StatusOptions>,
+    storage: Arc<dyn SongStorage>,
 ) -> Router {
     let (router, openapi) = get_openapi_router();
     router
         .merge(SwaggerUi::new("/swagger").url("/openapi.json", openapi))
+        .layer(middleware::from_fn(refresh_session))
         .layer(Extension(db))
+        .layer(Extension(LiveHub::new()))
+        .layer(Extension(LoginThrottle::new()))
         .layer(Extension(mailer))
+        .layer(Extension(storage))
         .layer(Extension(jwt_config))
         .layer(Extension(http_options))
-        .layer(Extension(status_options))
 }
 
 #[must_use]
@@ -82,9 +87,9 @@ pub fn get_api_openapi() -> OpenApi {
 pub fn get_router(
     http_options: Arc<HttpServerOptions>,
     db: ReloadableSqlite,
-    mailer: Arc<Mailer>,
+    mailer: Arc<dyn Mailer>,
     jwt_config: Arc<JWTConfig>,
-    status_options: Arc<StatusOptions>,
+    storage: Arc<dyn SongStorage>,
 ) -> Router {
     let request_id_layer = ServiceBuilder::new()
         .layer(SetRequestIdLayer::new(
@@ -120,15 +125,22 @@ pub fn get_router(
         Some("index.html".into()),
     );
 
+    let songs = Router::new()
+        .route("/songs/{name}", get(songs::get_song))
+        .layer(middleware::from_fn(refresh_session))
+        .layer(Extension(db.clone()))
+        .layer(Extension(storage.clone()))
+        .layer(Extension(jwt_config.clone()));
+
     Router::new()
         .fallback_service(serve_assets)
-        .nest_service("/songs", ServeDir::new(http_options.data_path.clone()))
+        .merge(songs)
         .merge(get_api_router(
             http_options,
             db,
             mailer,
             jwt_config,
-            status_options,
+            storage,
         ))
         .layer(request_id_layer)
 }
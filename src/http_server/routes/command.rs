@@ -1,39 +1,63 @@
-use axum::extract::DefaultBodyLimit;
+use axum::{extract::DefaultBodyLimit, middleware};
 use utoipa_axum::{router::OpenApiRouter, routes};
 
+use crate::audit::audit_command;
+
+mod accept_club_invitation;
+mod activate_competition;
 mod add_category;
 mod add_club_judge;
 mod add_club_starter;
+mod add_competition;
 mod add_timeplan_entry;
+mod apply_suggested_durations;
+mod assign_judge_panels;
+mod collect_song_garbage;
+mod confirm_totp_enrollment;
 mod create_club;
 mod delete_category;
 mod delete_club_judge;
 mod delete_club_starter;
+mod delete_judge_panel_assignment;
 mod delete_timeplan_entry;
+mod disable_totp;
 mod edit_category;
 mod edit_club_act;
 mod edit_club_judge;
 mod edit_club_starter;
+mod edit_competition;
 mod edit_timeplan_entry;
+mod invite_club_member;
 mod login;
 mod logout;
+mod logout_everywhere;
 mod move_category_down;
 mod move_category_up;
 mod move_timeplan_down;
 mod move_timeplan_up;
 mod register;
 mod reload_db;
+mod remove_club_member;
 mod rename_club;
 mod request_password_reset;
 mod resend_mail_validation;
 mod reset_password;
+mod rollback_act_song;
 mod save_act_song;
 mod set_act_order;
+mod set_club_member_role;
+mod set_judge_panel_assignment;
+mod set_music_operator;
+mod set_panel_requirements;
 mod set_payment;
 mod set_song_checked;
+mod start_totp_enrollment;
+mod submit_score;
 mod timeplan_backward;
 mod timeplan_forward;
+mod timeplan_redo;
 mod verify_email;
+mod verify_totp;
 
 pub fn get_command_router() -> OpenApiRouter {
     OpenApiRouter::new()
@@ -44,31 +68,60 @@ pub fn get_command_router() -> OpenApiRouter {
         .routes(routes!(reset_password::reset_password))
         .routes(routes!(login::login))
         .routes(routes!(logout::logout))
+        .routes(routes!(logout_everywhere::logout_everywhere))
+        .routes(routes!(verify_totp::verify_totp))
+        .routes(routes!(start_totp_enrollment::start_totp_enrollment))
+        .routes(routes!(confirm_totp_enrollment::confirm_totp_enrollment))
+        .routes(routes!(disable_totp::disable_totp))
+        .routes(routes!(add_competition::add_competition))
+        .routes(routes!(edit_competition::edit_competition))
+        .routes(routes!(activate_competition::activate_competition))
         .routes(routes!(add_category::add_category))
         .routes(routes!(edit_category::edit_category))
         .routes(routes!(delete_category::delete_category))
         .routes(routes!(move_category_up::move_category_up))
         .routes(routes!(move_category_down::move_category_down))
+        .routes(routes!(set_panel_requirements::set_panel_requirements))
         .routes(routes!(add_timeplan_entry::add_timeplan_entry))
         .routes(routes!(edit_timeplan_entry::edit_timeplan_entry))
         .routes(routes!(delete_timeplan_entry::delete_timeplan_entry))
         .routes(routes!(create_club::create_club))
         .routes(routes!(rename_club::rename_club))
+        .routes(routes!(invite_club_member::invite_club_member))
+        .routes(routes!(accept_club_invitation::accept_club_invitation))
+        .routes(routes!(set_club_member_role::set_club_member_role))
+        .routes(routes!(remove_club_member::remove_club_member))
         .routes(routes!(add_club_starter::add_club_starter))
         .routes(routes!(delete_club_starter::delete_club_starter))
         .routes(routes!(edit_club_starter::edit_club_starter))
         .routes(routes!(add_club_judge::add_club_judge))
         .routes(routes!(delete_club_judge::delete_club_judge))
         .routes(routes!(edit_club_judge::edit_club_judge))
+        .routes(routes!(assign_judge_panels::assign_judge_panels))
+        .routes(routes!(
+            set_judge_panel_assignment::set_judge_panel_assignment
+        ))
+        .routes(routes!(
+            delete_judge_panel_assignment::delete_judge_panel_assignment
+        ))
+        .routes(routes!(submit_score::submit_score))
         .routes(routes!(edit_club_act::edit_club_act))
         .routes(routes!(save_act_song::save_act_song))
+        .routes(routes!(rollback_act_song::rollback_act_song))
+        .routes(routes!(collect_song_garbage::collect_song_garbage))
         .routes(routes!(set_payment::set_payment))
         .routes(routes!(set_song_checked::set_song_checked))
+        .routes(routes!(set_music_operator::set_music_operator))
         .routes(routes!(set_act_order::set_act_order))
         .routes(routes!(timeplan_forward::timeplan_forward))
         .routes(routes!(timeplan_backward::timeplan_backward))
+        .routes(routes!(timeplan_redo::timeplan_redo))
+        .routes(routes!(
+            apply_suggested_durations::apply_suggested_durations
+        ))
         .routes(routes!(move_timeplan_up::move_timeplan_up))
         .routes(routes!(move_timeplan_down::move_timeplan_down))
         .routes(routes!(reload_db::reload_db))
         .layer(DefaultBodyLimit::max(1024 * 1024 * 10))
+        .layer(middleware::from_fn(audit_command))
 }
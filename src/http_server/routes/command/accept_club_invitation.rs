@@ -0,0 +1,92 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    club_membership::{self, ClubRole, MembershipError},
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AcceptClubInvitationResponse {
+    club_id: Uuid,
+    role: ClubRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptClubInvitationBody {
+    token: Uuid,
+}
+
+/// Join a club with the token of an invitation mail.
+///
+/// Only the invited, verified email address can accept. Members keep their
+/// role if it is higher than the invited one.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/accept_club_invitation",
+    request_body=AcceptClubInvitationBody,
+    responses(
+        (status=200, content_type="application/json", body=AcceptClubInvitationResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn accept_club_invitation(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<AcceptClubInvitationBody>,
+) -> Result<Json<AcceptClubInvitationResponse>, HttpError> {
+    let db = db.get().await.clone();
+    let invitation = sqlx::query!(
+        r#"
+        SELECT club_id as "club_id!: Uuid", email, role as "role!: ClubRole"
+        FROM club_invitations WHERE id = ?
+        "#,
+        body.token
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+    if !invitation.email.eq_ignore_ascii_case(&auth.email) {
+        return Err(MembershipError::OtherEmail.into());
+    }
+    if !auth.email_verified {
+        return Err(MembershipError::EmailNotVerified.into());
+    }
+
+    let role = auth
+        .club_role(invitation.club_id)
+        .map_or(invitation.role, |role| role.max(invitation.role));
+    info!(
+        "User {} joins club {} as {:?}",
+        auth.user_id, invitation.club_id, role
+    );
+    let mut tx = db.begin().await?;
+    club_membership::set(&mut tx, invitation.club_id, auth.user_id, role).await?;
+    sqlx::query!("DELETE FROM club_invitations WHERE id = ?", body.token)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    audit.before(json!({
+        "club_id": invitation.club_id,
+        "user_id": auth.user_id,
+        "role": auth.club_role(invitation.club_id),
+    }));
+    audit.after(json!({ "club_id": invitation.club_id, "user_id": auth.user_id, "role": role }));
+
+    Ok(Json(AcceptClubInvitationResponse {
+        club_id: invitation.club_id,
+        role,
+    }))
+}
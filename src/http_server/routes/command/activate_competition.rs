@@ -0,0 +1,74 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActivateCompetitionResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ActivateCompetitionBody {
+    id: Uuid,
+}
+
+/// Make a competition the active one.
+///
+/// All registrations, categories and the timeplan are managed for the active
+/// competition only.
+#[utoipa::path(
+    post,
+    tags=["command", "competition"],
+    path="/activate_competition",
+    request_body=ActivateCompetitionBody,
+    responses(
+        (status=200, content_type="application/json", body=ActivateCompetitionResponse),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn activate_competition(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<ActivateCompetitionBody>,
+) -> Result<Json<ActivateCompetitionResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    let mut tx = db.begin().await?;
+    let before =
+        sqlx::query_scalar!(r#"SELECT id as "id!: Uuid" FROM competitions WHERE is_active"#)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+    sqlx::query!("UPDATE competitions SET is_active = FALSE WHERE is_active")
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query!(
+        "UPDATE competitions SET is_active = TRUE WHERE id = ?",
+        body.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(HttpError::NotFound);
+    }
+
+    tx.commit().await?;
+    audit.before(json!({ "active_competition_id": before }));
+    audit.after(json!({ "active_competition_id": body.id }));
+    info!("Activated competition {}", body.id);
+
+    Ok(Json(ActivateCompetitionResponse {}))
+}
@@ -4,7 +4,11 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth, routes::query::list_categories::get_category,
+    },
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -39,10 +43,12 @@ pub struct AddCategoryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn add_category(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<AddCategoryBody>,
 ) -> Result<Json<AddCategoryResponse>, HttpError> {
@@ -54,6 +60,7 @@ pub async fn add_category(
     sqlx::query!(
         r#"
         INSERT INTO categories (
+            competition_id,
             name, 
             description, 
             from_birthday, 
@@ -65,8 +72,9 @@ pub async fn add_category(
             act_duration_seconds, 
             judge_duration_seconds
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         "#,
+        competition.id,
         body.name,
         body.description,
         body.from_birthday,
@@ -80,6 +88,7 @@ pub async fn add_category(
     )
     .execute(&db)
     .await?;
+    audit.after(get_category(&db, competition.id, &body.name).await?);
 
     Ok(Json(AddCategoryResponse {}))
-}
\ No newline at end of file
+}
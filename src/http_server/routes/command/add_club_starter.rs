@@ -5,10 +5,15 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{
+        ClientError, HttpError, extractor::club_access::ClubAccess,
+        routes::query::list_club_starters::get_club_starter,
+    },
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
-    utils::set_act,
+    utils::{get_competition_id_for_club_id, set_act},
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -46,17 +51,22 @@ pub struct AddClubStarterBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn add_club_starter(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<AddClubStarterBody>,
 ) -> Result<Json<AddClubStarterResponse>, HttpError> {
     if !capabilities.can_register_starter {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access.check_club(body.club_id, ClubRole::Trainer)?;
+    if let Some(partner_id) = body.partner_id {
+        access.check_starter(partner_id, ClubRole::Trainer).await?;
+    }
     let db = db.get().await.clone();
     let starter_id = Uuid::now_v7();
 
@@ -127,17 +137,31 @@ pub async fn add_club_starter(
     .execute(&db)
     .await?;
 
+    let competition_id = get_competition_id_for_club_id(&db, body.club_id)
+        .await
+        .map_err(HttpError::ErrorMessages)?;
+
     if body.single_female || body.single_male {
-        set_act(&db, "", &[starter_id], None, false)
+        set_act(&db, competition_id, "", &[starter_id], None, false)
             .await
             .map_err(HttpError::ErrorMessages)?;
     }
 
     if let Some(partner_id) = partner_id {
-        set_act(&db, "", &[starter_id, partner_id], None, true)
-            .await
-            .map_err(HttpError::ErrorMessages)?;
-        info!("Updating partner {:?} to link to starter {:?}", partner_id, starter_id);
+        set_act(
+            &db,
+            competition_id,
+            "",
+            &[starter_id, partner_id],
+            None,
+            true,
+        )
+        .await
+        .map_err(HttpError::ErrorMessages)?;
+        info!(
+            "Updating partner {:?} to link to starter {:?}",
+            partner_id, starter_id
+        );
         sqlx::query!(
             r#"
                 UPDATE starter SET partner_id = ?, partner_name = ? WHERE id = ?
@@ -150,5 +174,7 @@ pub async fn add_club_starter(
         .await?;
     }
 
+    audit.after(get_club_starter(&db, starter_id).await?);
+
     Ok(Json(AddClubStarterResponse { starter_id }))
 }
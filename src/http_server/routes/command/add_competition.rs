@@ -0,0 +1,192 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    competition::Competition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AddCompetitionResponse {
+    competition_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddCompetitionBody {
+    name: String,
+    venue: Option<String>,
+    #[serde(with = "crate::competition::iso_date_list")]
+    #[schema(value_type = Vec<Date>)]
+    days: Vec<Date>,
+    #[serde(with = "time::serde::iso8601")]
+    start_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    end_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    end_music_upload_date: OffsetDateTime,
+    /// Copy the categories and the timeplan of this competition.
+    ///
+    /// Birthday windows are moved by the years and timeplan entries by the
+    /// days between the first days of both competitions.
+    copy_from: Option<Uuid>,
+}
+
+/// Add a new competition.
+///
+/// The new competition is not active until it is activated.
+#[utoipa::path(
+    post,
+    tags=["command", "competition"],
+    path="/add_competition",
+    request_body=AddCompetitionBody,
+    responses(
+        (status=200, content_type="application/json", body=AddCompetitionResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn add_competition(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(mut body): Json<AddCompetitionBody>,
+) -> Result<Json<AddCompetitionResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    body.days.sort();
+    let Some(first_day) = body.days.first().copied() else {
+        return Err(HttpError::ErrorMessages(
+            "Der Wettbewerb braucht mindestens einen Tag.".to_string(),
+        ));
+    };
+    let db = db.get().await.clone();
+
+    let source = match body.copy_from {
+        Some(source_id) => Some(
+            Competition::get(&db, source_id)
+                .await?
+                .ok_or(HttpError::NotFound)?,
+        ),
+        None => None,
+    };
+
+    let competition_id = Uuid::now_v7();
+    let mut tx = db.begin().await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO competitions (
+            id,
+            name,
+            venue,
+            start_register_date,
+            end_register_date,
+            end_music_upload_date
+        ) VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        competition_id,
+        body.name,
+        body.venue,
+        body.start_register_date,
+        body.end_register_date,
+        body.end_music_upload_date,
+    )
+    .execute(&mut *tx)
+    .await?;
+    Competition::set_days(&mut tx, competition_id, &body.days).await?;
+
+    if let Some(source) = source {
+        let source_first_day = source.first_day().unwrap_or(first_day);
+        let years = format!("{:+} years", first_day.year() - source_first_day.year());
+        let days = format!("{:+} days", (first_day - source_first_day).whole_days());
+        info!(
+            "Copying categories and timeplan from {} ({years}, {days})",
+            source.id
+        );
+
+        sqlx::query!(
+            r#"
+            INSERT INTO categories (
+                competition_id,
+                name,
+                description,
+                from_birthday,
+                to_birthday,
+                is_pair,
+                is_sonderpokal,
+                is_single_male,
+                einfahrzeit_seconds,
+                act_duration_seconds,
+                judge_duration_seconds,
+                "order",
+                panel_performance,
+                panel_technique,
+                panel_dismount,
+                panel_hospitation
+            )
+            SELECT
+                ?,
+                name,
+                description,
+                datetime(from_birthday, ?),
+                datetime(to_birthday, ?),
+                is_pair,
+                is_sonderpokal,
+                is_single_male,
+                einfahrzeit_seconds,
+                act_duration_seconds,
+                judge_duration_seconds,
+                "order",
+                panel_performance,
+                panel_technique,
+                panel_dismount,
+                panel_hospitation
+            FROM categories WHERE competition_id = ?
+            "#,
+            competition_id,
+            years,
+            years,
+            source.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO timeplan (
+                competition_id,
+                earliest_start_time,
+                duration_seconds,
+                label,
+                category
+            )
+            SELECT
+                ?,
+                datetime(earliest_start_time, ?),
+                duration_seconds,
+                label,
+                category
+            FROM timeplan WHERE competition_id = ? ORDER BY id
+            "#,
+            competition_id,
+            days,
+            source.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    audit.after(Competition::get(&db, competition_id).await?);
+
+    Ok(Json(AddCompetitionResponse { competition_id }))
+}
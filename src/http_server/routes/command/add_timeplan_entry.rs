@@ -4,7 +4,13 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth,
+        routes::query::list_timeplan::get_timeplan_entry,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -33,10 +39,13 @@ pub struct AddTimeplanEntryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn add_timeplan_entry(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<AddTimeplanEntryBody>,
 ) -> Result<Json<AddTimeplanEntryResponse>, HttpError> {
@@ -45,23 +54,29 @@ pub async fn add_timeplan_entry(
     }
     let db = db.get().await.clone();
 
-    sqlx::query!(
+    let id = sqlx::query!(
         r#"
         INSERT INTO timeplan (
+            competition_id,
             earliest_start_time, 
             duration_seconds, 
             label, 
             category
         )
-        VALUES ($1, $2, $3, $4)
+        VALUES ($1, $2, $3, $4, $5)
         "#,
+        competition.id,
         body.earliest_start_time,
         body.duration_seconds,
         body.label,
         body.category,
     )
     .execute(&db)
-    .await?;
+    .await?
+    .last_insert_rowid();
+    audit.after(get_timeplan_entry(&db, competition.id, id).await?);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(AddTimeplanEntryResponse {}))
 }
@@ -0,0 +1,147 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    duration_stats::{DurationStats, category_durations},
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth,
+        routes::query::get_duration_statistics::load_runs,
+    },
+    live::{LiveChange, LiveHub},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApplySuggestedDurationsResponse {
+    /// Categories whose durations were changed.
+    updated: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ApplySuggestedDurationsBody {
+    /// The competition whose categories get the suggested durations.
+    competition_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct Durations {
+    category: String,
+    einfahrzeit_seconds: Option<i64>,
+    act_duration_seconds: Option<i64>,
+    judge_duration_seconds: Option<i64>,
+}
+
+/// Apply the durations measured in the other competitions to the categories
+/// of a competition.
+///
+/// Categories are matched by name. Durations without measurements are kept.
+#[utoipa::path(
+    post,
+    tags=["command", "category"],
+    path="/apply_suggested_durations",
+    request_body=ApplySuggestedDurationsBody,
+    responses(
+        (status=200, content_type="application/json", body=ApplySuggestedDurationsResponse),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit, live))]
+#[axum::debug_handler]
+pub async fn apply_suggested_durations(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    auth: Auth,
+    Json(body): Json<ApplySuggestedDurationsBody>,
+) -> Result<Json<ApplySuggestedDurationsResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let before = sqlx::query_as!(
+        Durations,
+        r#"
+        SELECT name as "category!", einfahrzeit_seconds, act_duration_seconds, judge_duration_seconds
+        FROM categories WHERE competition_id = ? ORDER BY "order"
+        "#,
+        body.competition_id
+    )
+    .fetch_all(&db)
+    .await?;
+    if before.is_empty() {
+        return Err(HttpError::NotFound);
+    }
+
+    let runs: Vec<_> = load_runs(&db)
+        .await?
+        .into_iter()
+        .filter(|(competition_id, _)| *competition_id != body.competition_id)
+        .map(|(_, run)| run)
+        .collect();
+    let suggested = |stats: Option<DurationStats>| stats.map(|s| s.suggested_seconds());
+
+    let mut tx = db.begin().await?;
+    let mut updated = vec![];
+    for durations in category_durations(&runs) {
+        if !before.iter().any(|c| c.category == durations.category) {
+            continue;
+        }
+        let einfahrzeit_seconds = suggested(durations.einfahrzeit);
+        let act_duration_seconds = suggested(durations.act);
+        let judge_duration_seconds = suggested(durations.judge);
+        let changed = sqlx::query!(
+            r#"
+            UPDATE categories
+            SET
+                einfahrzeit_seconds = COALESCE($1, einfahrzeit_seconds),
+                act_duration_seconds = COALESCE($2, act_duration_seconds),
+                judge_duration_seconds = COALESCE($3, judge_duration_seconds)
+            WHERE competition_id = $4 AND name = $5
+              AND (
+                einfahrzeit_seconds IS NOT COALESCE($1, einfahrzeit_seconds)
+                OR act_duration_seconds IS NOT COALESCE($2, act_duration_seconds)
+                OR judge_duration_seconds IS NOT COALESCE($3, judge_duration_seconds)
+              )
+            "#,
+            einfahrzeit_seconds,
+            act_duration_seconds,
+            judge_duration_seconds,
+            body.competition_id,
+            durations.category,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if changed > 0 {
+            updated.push(durations.category);
+        }
+    }
+
+    let after = sqlx::query_as!(
+        Durations,
+        r#"
+        SELECT name as "category!", einfahrzeit_seconds, act_duration_seconds, judge_duration_seconds
+        FROM categories WHERE competition_id = ? ORDER BY "order"
+        "#,
+        body.competition_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    audit.before(&before);
+    audit.after(&after);
+
+    if !updated.is_empty() {
+        live.publish(body.competition_id, LiveChange::TimeplanChanged);
+    }
+
+    Ok(Json(ApplySuggestedDurationsResponse { updated }))
+}
@@ -0,0 +1,65 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge_panel::{assign, get_assignments, get_judges, get_slots, set_automatic_assignments},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AssignJudgePanelsResponse {}
+
+/// Assign judges to the panels of all categories in the timeplan.
+///
+/// Manual assignments are kept, all automatic assignments are replaced.
+#[utoipa::path(
+    post,
+    tags=["command", "judge"],
+    path="/assign_judge_panels",
+    responses(
+        (status=200, content_type="application/json", body=AssignJudgePanelsResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn assign_judge_panels(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<Json<AssignJudgePanelsResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let slots = get_slots(&db, competition.id).await?;
+    let judges = get_judges(&db, competition.id).await?;
+    let before = get_assignments(&db, competition.id).await?;
+    let manual: Vec<_> = before
+        .iter()
+        .filter(|assignment| assignment.is_manual)
+        .cloned()
+        .collect();
+    let assignments = assign(&slots, &judges, &manual);
+    info!(
+        "Assigned {} judges to {} panels",
+        assignments.len() - manual.len(),
+        slots.len()
+    );
+
+    let mut tx = db.begin().await?;
+    set_automatic_assignments(&mut tx, competition.id, &assignments).await?;
+    tx.commit().await?;
+    audit.before(before);
+    audit.after(assignments);
+
+    Ok(Json(AssignJudgePanelsResponse {}))
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, http::StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    audit::Audit,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+    song_storage::{self, GARBAGE_MIN_AGE, OrphanAction, SongStorage, StorageReport},
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CollectSongGarbageBody {
+    action: OrphanAction,
+}
+
+/// Report the disk usage of the songs and remove or archive the files no
+/// act references anymore.
+///
+/// Songs of deleted acts and replaced normalized copies stay on disk until
+/// this runs. Files of the last ten minutes are kept, they may belong to a
+/// running upload.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/collect_song_garbage",
+    request_body=CollectSongGarbageBody,
+    responses(
+        (status=200, content_type="application/json", body=StorageReport),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, storage, audit))]
+#[axum::debug_handler]
+pub async fn collect_song_garbage(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(storage): Extension<Arc<dyn SongStorage>>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<CollectSongGarbageBody>,
+) -> Result<Json<StorageReport>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let report =
+        song_storage::collect_garbage(&db, storage.as_ref(), body.action, GARBAGE_MIN_AGE).await?;
+    if body.action != OrphanAction::Report {
+        audit.after(json!({
+            "orphaned_files": report.orphaned_files,
+            "adopted_files": report.adopted_files,
+        }));
+    }
+
+    Ok(Json(report))
+}
@@ -0,0 +1,85 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{ClientError, HttpError, extractor::auth::PartialAuth},
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+    totp::{self, TotpError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmTotpEnrollmentResponse {
+    /// Each code can be used once instead of a TOTP code. They are only
+    /// shown now.
+    recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmTotpEnrollmentBody {
+    /// A code of the authenticator app.
+    code: String,
+}
+
+/// Enable TOTP with the secret of `start_totp_enrollment`.
+///
+/// Replaces the previous secret and recovery codes. The current session
+/// counts as verified.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/confirm_totp_enrollment",
+    request_body=ConfirmTotpEnrollmentBody,
+    responses(
+        (status=200, content_type="application/json", body=ConfirmTotpEnrollmentResponse),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, body))]
+#[axum::debug_handler]
+pub async fn confirm_totp_enrollment(
+    Extension(db): Extension<ReloadableSqlite>,
+    PartialAuth(auth): PartialAuth,
+    Json(body): Json<ConfirmTotpEnrollmentBody>,
+) -> Result<Json<ConfirmTotpEnrollmentResponse>, HttpError> {
+    if !auth.second_factor.may_enroll() {
+        return Err(HttpError::StatusCode(StatusCode::UNAUTHORIZED));
+    }
+    let db = db.get().await.clone();
+    let secret = sqlx::query_scalar!(
+        "SELECT totp_pending_secret FROM users WHERE id = ?",
+        auth.user_id
+    )
+    .fetch_one(&db)
+    .await?
+    .ok_or(TotpError::NotEnrolled)?;
+    let step = totp::verify_code(&secret, &body.code, None)?;
+
+    let recovery_codes = totp::generate_recovery_codes();
+    let hashes = serde_json::to_string(
+        &recovery_codes
+            .iter()
+            .map(|code| totp::hash_recovery_code(code))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET totp_secret = totp_pending_secret, totp_pending_secret = NULL, totp_last_step = ?, totp_recovery_codes = ?
+        WHERE id = ?
+        "#,
+        step,
+        hashes,
+        auth.user_id
+    )
+    .execute(&db)
+    .await?;
+    session::verify_second_factor(&db, auth.session_id).await?;
+    info!("Enabled TOTP for user {}", auth.user_id);
+
+    Ok(Json(ConfirmTotpEnrollmentResponse { recovery_codes }))
+}
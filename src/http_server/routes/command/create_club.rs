@@ -1,10 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
+    club_membership::{self, ClubRole},
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
@@ -34,10 +38,12 @@ pub struct CreateClubBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn create_club(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
     auth: Auth,
     capabilities: Capabilities,
     Json(body): Json<CreateClubBody>,
@@ -47,19 +53,24 @@ pub async fn create_club(
     }
     let db = db.get().await.clone();
     let club_id = Uuid::now_v7();
+    let mut tx = db.begin().await?;
     sqlx::query!(
         r#"
-        INSERT INTO clubs (id, name, owner_id) VALUES (?, ?, ?);
+        INSERT INTO clubs (id, competition_id, name, owner_id) VALUES (?, ?, ?, ?);
         UPDATE users SET club_id = ? WHERE id = ?;
         "#,
         club_id,
+        competition.id,
         body.name,
         auth.user_id,
         club_id,
         auth.user_id,
     )
-    .execute(&db)
+    .execute(&mut *tx)
     .await?;
+    club_membership::set(&mut tx, club_id, auth.user_id, ClubRole::Owner).await?;
+    tx.commit().await?;
+    audit.after(json!({ "club_id": club_id, "name": body.name }));
 
     Ok(Json(CreateClubResponse { club_id }))
 }
@@ -4,7 +4,12 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth, routes::query::list_categories::get_category,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -30,10 +35,13 @@ pub struct DeleteCategoryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit, live))]
 #[axum::debug_handler]
 pub async fn delete_category(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<DeleteCategoryBody>,
 ) -> Result<Json<DeleteCategoryResponse>, HttpError> {
@@ -44,13 +52,26 @@ pub async fn delete_category(
 
     // Start a transaction to ensure atomicity
     let mut tx = db.begin().await?;
+    let before = get_category(&mut *tx, competition.id, &body.name).await?;
 
     // Delete timeplan entries that reference this category
     sqlx::query!(
         r#"
-        DELETE FROM timeplan WHERE category = $1
+        DELETE FROM timeplan WHERE category = $1 AND competition_id = $2
         "#,
         body.name,
+        competition.id,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Delete judge qualifications for this category
+    sqlx::query!(
+        r#"
+        DELETE FROM judge_qualifications WHERE category = $1 AND competition_id = $2
+        "#,
+        body.name,
+        competition.id,
     )
     .execute(&mut *tx)
     .await?;
@@ -59,9 +80,10 @@ pub async fn delete_category(
     let result = sqlx::query!(
         r#"
         DELETE FROM categories
-        WHERE name = $1
+        WHERE name = $1 AND competition_id = $2
         "#,
         body.name,
+        competition.id,
     )
     .execute(&mut *tx)
     .await?;
@@ -72,6 +94,9 @@ pub async fn delete_category(
 
     // Commit the transaction
     tx.commit().await?;
+    audit.before(before);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(DeleteCategoryResponse {}))
-}
\ No newline at end of file
+}
@@ -5,7 +5,12 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{
+        ClientError, HttpError, extractor::club_access::ClubAccess,
+        routes::query::list_club_judges::get_club_judge,
+    },
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
 };
@@ -32,18 +37,21 @@ pub struct DeleteClubJudgeBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn delete_club_judge(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<DeleteClubJudgeBody>,
 ) -> Result<Json<DeleteClubJudgeResponse>, HttpError> {
     if !capabilities.can_register_judge {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access.check_judge(body.judge_id, ClubRole::Trainer).await?;
     let db = db.get().await.clone();
+    let before = get_club_judge(&db, body.judge_id).await?;
     sqlx::query!(
         r#"
         DELETE FROM judge WHERE id = ?;
@@ -52,6 +60,7 @@ pub async fn delete_club_judge(
     )
     .execute(&db)
     .await?;
+    audit.before(before);
 
     Ok(Json(DeleteClubJudgeResponse {}))
 }
@@ -1,11 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
 };
@@ -32,20 +35,39 @@ pub struct DeleteClubStarterBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn delete_club_starter(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<DeleteClubStarterBody>,
 ) -> Result<Json<DeleteClubStarterResponse>, HttpError> {
     if !capabilities.can_register_starter {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access
+        .check_starter(body.starter_id, ClubRole::Trainer)
+        .await?;
     let db = db.get().await.clone();
     let mut transaction = db.begin().await?;
 
+    let starter = sqlx::query!(
+        r#"
+        SELECT
+            club_id as "club_id!: Uuid",
+            firstname,
+            lastname,
+            birthdate as "birthdate: String"
+        FROM starter WHERE id = ?
+        "#,
+        body.starter_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
     // delete all acts and act_participant entries that reference this starter
     let act_ids = sqlx::query!(
         r#"
@@ -98,6 +120,14 @@ pub async fn delete_club_starter(
     .await?;
 
     transaction.commit().await?;
+    audit.before(json!({
+        "starter_id": body.starter_id,
+        "club_id": starter.club_id,
+        "firstname": starter.firstname,
+        "lastname": starter.lastname,
+        "birthdate": starter.birthdate,
+        "act_ids": act_ids,
+    }));
 
     Ok(Json(DeleteClubStarterResponse {}))
 }
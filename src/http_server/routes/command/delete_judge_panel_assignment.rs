@@ -0,0 +1,90 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge::JudgeRole,
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteJudgePanelAssignmentResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeleteJudgePanelAssignmentBody {
+    timeplan_id: i64,
+    judge_id: Uuid,
+}
+
+/// Remove a judge from the panel of a timeplan entry.
+///
+/// An automatic assignment may come back when the panels are assigned again.
+#[utoipa::path(
+    post,
+    tags=["command", "judge"],
+    path="/delete_judge_panel_assignment",
+    request_body=DeleteJudgePanelAssignmentBody,
+    responses(
+        (status=200, content_type="application/json", body=DeleteJudgePanelAssignmentResponse),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn delete_judge_panel_assignment(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+    Json(body): Json<DeleteJudgePanelAssignmentBody>,
+) -> Result<Json<DeleteJudgePanelAssignmentResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let before = sqlx::query!(
+        r#"
+        SELECT role as "role: JudgeRole", hospitation, is_manual
+        FROM judge_panel WHERE timeplan_id = ? AND judge_id = ?
+        "#,
+        body.timeplan_id,
+        body.judge_id,
+    )
+    .fetch_optional(&db)
+    .await?;
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM judge_panel
+        WHERE timeplan_id = ? AND judge_id = ?
+          AND timeplan_id IN (SELECT id FROM timeplan WHERE competition_id = ?)
+        "#,
+        body.timeplan_id,
+        body.judge_id,
+        competition.id,
+    )
+    .execute(&db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(HttpError::NotFound);
+    }
+    if let Some(before) = before {
+        audit.before(json!({
+            "timeplan_id": body.timeplan_id,
+            "judge_id": body.judge_id,
+            "role": before.role,
+            "hospitation": before.hospitation,
+            "is_manual": before.is_manual,
+        }));
+    }
+
+    Ok(Json(DeleteJudgePanelAssignmentResponse {}))
+}
@@ -4,7 +4,13 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth,
+        routes::query::list_timeplan::get_timeplan_entry,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -30,10 +36,13 @@ pub struct DeleteTimeplanEntryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn delete_timeplan_entry(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<DeleteTimeplanEntryBody>,
 ) -> Result<Json<DeleteTimeplanEntryResponse>, HttpError> {
@@ -41,13 +50,15 @@ pub async fn delete_timeplan_entry(
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
     let db = db.get().await.clone();
+    let before = get_timeplan_entry(&db, competition.id, body.id).await?;
 
     let result = sqlx::query!(
         r#"
         DELETE FROM timeplan
-        WHERE id = $1
+        WHERE id = $1 AND competition_id = $2
         "#,
         body.id,
+        competition.id,
     )
     .execute(&db)
     .await?;
@@ -55,6 +66,9 @@ pub async fn delete_timeplan_entry(
     if result.rows_affected() == 0 {
         return Err(HttpError::StatusCode(StatusCode::NOT_FOUND));
     }
+    audit.before(before);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(DeleteTimeplanEntryResponse {}))
 }
@@ -0,0 +1,74 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{
+        ClientError, HttpError,
+        extractor::{auth::Auth, client_ip::ClientIp},
+    },
+    login_throttle::{Action, LoginThrottle, ThrottleKey},
+    reloadable_sqlite::ReloadableSqlite,
+    totp::{self, TotpError},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisableTotpResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DisableTotpBody {
+    /// A code of the authenticator app or a recovery code.
+    code: String,
+}
+
+/// Turn off TOTP for the current user.
+///
+/// Admins can't, they have to use TOTP.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/disable_totp",
+    request_body=DisableTotpBody,
+    responses(
+        (status=200, content_type="application/json", body=DisableTotpResponse),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=429, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, throttle, body))]
+#[axum::debug_handler]
+pub async fn disable_totp(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(throttle): Extension<LoginThrottle>,
+    ClientIp(ip): ClientIp,
+    auth: Auth,
+    Json(body): Json<DisableTotpBody>,
+) -> Result<Json<DisableTotpResponse>, HttpError> {
+    if auth.is_admin() {
+        return Err(TotpError::RequiredForAdmins.into());
+    }
+    let db = db.get().await.clone();
+    let keys = ThrottleKey::for_request(ip, &auth.email);
+    throttle.check(&db, Action::SecondFactor, &keys).await?;
+    if let Err(e) = totp::verify(&db, auth.user_id, &body.code).await {
+        throttle.record(&db, Action::SecondFactor, &keys).await;
+        return Err(e);
+    }
+    throttle.reset(Action::SecondFactor, &keys);
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET totp_secret = NULL, totp_pending_secret = NULL, totp_last_step = NULL, totp_recovery_codes = NULL
+        WHERE id = ?
+        "#,
+        auth.user_id
+    )
+    .execute(&db)
+    .await?;
+    info!("Disabled TOTP for user {}", auth.user_id);
+
+    Ok(Json(DisableTotpResponse {}))
+}
@@ -4,7 +4,12 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth, routes::query::list_categories::get_category,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -40,10 +45,13 @@ pub struct EditCategoryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit, live))]
 #[axum::debug_handler]
 pub async fn edit_category(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<EditCategoryBody>,
 ) -> Result<Json<EditCategoryResponse>, HttpError> {
@@ -55,18 +63,38 @@ pub async fn edit_category(
     // Start a transaction to ensure atomicity
     let mut tx = db.begin().await?;
 
-    sqlx::query!("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+    sqlx::query!("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *tx)
+        .await?;
 
-    // Update timeplan table first to maintain foreign key constraint
+    let before = get_category(&mut *tx, competition.id, &body.name)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+
+    // Update timeplan and judge qualifications first to maintain foreign key constraints
     if body.new_name != body.name {
         sqlx::query!(
             r#"
             UPDATE timeplan 
             SET category = $1 
-            WHERE category = $2
+            WHERE category = $2 AND competition_id = $3
+            "#,
+            body.new_name,
+            body.name,
+            competition.id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE judge_qualifications
+            SET category = $1
+            WHERE category = $2 AND competition_id = $3
             "#,
             body.new_name,
             body.name,
+            competition.id,
         )
         .execute(&mut *tx)
         .await?;
@@ -87,7 +115,7 @@ pub async fn edit_category(
             einfahrzeit_seconds = $8,
             act_duration_seconds = $9,
             judge_duration_seconds = $10
-        WHERE name = $11
+        WHERE name = $11 AND competition_id = $12
         "#,
         body.new_name,
         body.description,
@@ -100,12 +128,18 @@ pub async fn edit_category(
         body.act_duration_seconds,
         body.judge_duration_seconds,
         body.name,
+        competition.id,
     )
     .execute(&mut *tx)
     .await?;
+    let after = get_category(&mut *tx, competition.id, &body.new_name).await?;
 
     // Commit the transaction
     tx.commit().await?;
+    audit.before(before);
+    audit.after(after);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(EditCategoryResponse {}))
-}
\ No newline at end of file
+}
@@ -1,11 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
 };
@@ -34,18 +37,24 @@ pub struct EditClubActBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn edit_club_act(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<EditClubActBody>,
 ) -> Result<Json<EditClubActResponse>, HttpError> {
     if !capabilities.can_upload_music {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access.check_act(body.id, ClubRole::Trainer).await?;
     let db = db.get().await.clone();
+    let before = sqlx::query!("SELECT name, description FROM acts WHERE id = ?", body.id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
 
     sqlx::query!(
         r#"
@@ -59,6 +68,16 @@ pub async fn edit_club_act(
     )
     .execute(&db)
     .await?;
+    audit.before(json!({
+        "act_id": body.id,
+        "name": before.name,
+        "description": before.description,
+    }));
+    audit.after(json!({
+        "act_id": body.id,
+        "name": body.name,
+        "description": body.description,
+    }));
 
     Ok(Json(EditClubActResponse {}))
 }
@@ -5,7 +5,13 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{
+        ClientError, HttpError, extractor::club_access::ClubAccess,
+        routes::query::list_club_judges::get_club_judge,
+    },
+    judge::{JudgeQualification, set_qualifications},
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
 };
@@ -22,66 +28,7 @@ pub struct EditClubJudgeBody {
     mail: String,
     #[serde(with = "time::serde::iso8601")]
     birthdate: time::OffsetDateTime,
-    n_ew_u15_p: bool,
-    n_ew_u15_p_hosp: bool,
-    n_ew_u15_t: bool,
-    n_ew_u15_t_hosp: bool,
-    n_ew_u15_a: bool,
-    n_ew_u15_a_hosp: bool,
-    n_ew_o15_p: bool,
-    n_ew_o15_p_hosp: bool,
-    n_ew_o15_t: bool,
-    n_ew_o15_t_hosp: bool,
-    n_ew_o15_a: bool,
-    n_ew_o15_a_hosp: bool,
-    n_em_u15_p: bool,
-    n_em_u15_p_hosp: bool,
-    n_em_u15_t: bool,
-    n_em_u15_t_hosp: bool,
-    n_em_u15_a: bool,
-    n_em_u15_a_hosp: bool,
-    n_em_o15_p: bool,
-    n_em_o15_p_hosp: bool,
-    n_em_o15_t: bool,
-    n_em_o15_t_hosp: bool,
-    n_em_o15_a: bool,
-    n_em_o15_a_hosp: bool,
-    n_p_u15_p: bool,
-    n_p_u15_p_hosp: bool,
-    n_p_u15_t: bool,
-    n_p_u15_t_hosp: bool,
-    n_p_u15_a: bool,
-    n_p_u15_a_hosp: bool,
-    n_p_o15_p: bool,
-    n_p_o15_p_hosp: bool,
-    n_p_o15_t: bool,
-    n_p_o15_t_hosp: bool,
-    n_p_o15_a: bool,
-    n_p_o15_a_hosp: bool,
-    s_e_u15_p: bool,
-    s_e_u15_p_hosp: bool,
-    s_e_u15_t: bool,
-    s_e_u15_t_hosp: bool,
-    s_e_u15_a: bool,
-    s_e_u15_a_hosp: bool,
-    s_e_o15_p: bool,
-    s_e_o15_p_hosp: bool,
-    s_e_o15_t: bool,
-    s_e_o15_t_hosp: bool,
-    s_e_o15_a: bool,
-    s_e_o15_a_hosp: bool,
-    s_p_u15_p: bool,
-    s_p_u15_p_hosp: bool,
-    s_p_u15_t: bool,
-    s_p_u15_t_hosp: bool,
-    s_p_u15_a: bool,
-    s_p_u15_a_hosp: bool,
-    s_p_o15_p: bool,
-    s_p_o15_p_hosp: bool,
-    s_p_o15_t: bool,
-    s_p_o15_t_hosp: bool,
-    s_p_o15_a: bool,
-    s_p_o15_a_hosp: bool,
+    qualifications: Vec<JudgeQualification>,
 }
 
 /// EditClubJudge a new user.
@@ -98,18 +45,23 @@ pub struct EditClubJudgeBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn edit_club_judge(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<EditClubJudgeBody>,
 ) -> Result<Json<EditClubJudgeResponse>, HttpError> {
     if !capabilities.can_register_judge {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access.check_judge(body.judge_id, ClubRole::Trainer).await?;
+    access.check_club(body.club_id, ClubRole::Trainer)?;
     let db = db.get().await.clone();
+    let before = get_club_judge(&db, body.judge_id).await?;
+    let mut tx = db.begin().await?;
     sqlx::query!(
         r#"
         UPDATE judge
@@ -118,67 +70,7 @@ pub async fn edit_club_judge(
             firstname = ?,
             lastname = ?,
             mail = ?,
-            birthdate = ?,
-            n_ew_u15_p = ?,
-            n_ew_u15_p_hosp = ?,
-            n_ew_u15_t = ?,
-            n_ew_u15_t_hosp = ?,
-            n_ew_u15_a = ?,
-            n_ew_u15_a_hosp = ?,
-            n_ew_o15_p = ?,
-            n_ew_o15_p_hosp = ?,
-            n_ew_o15_t = ?,
-            n_ew_o15_t_hosp = ?,
-            n_ew_o15_a = ?,
-            n_ew_o15_a_hosp = ?,
-            n_em_u15_p = ?,
-            n_em_u15_p_hosp = ?,
-            n_em_u15_t = ?,
-            n_em_u15_t_hosp = ?,
-            n_em_u15_a = ?,
-            n_em_u15_a_hosp = ?,
-            n_em_o15_p = ?,
-            n_em_o15_p_hosp = ?,
-            n_em_o15_t = ?,
-            n_em_o15_t_hosp = ?,
-            n_em_o15_a = ?,
-            n_em_o15_a_hosp = ?,
-            n_p_u15_p = ?,
-            n_p_u15_p_hosp = ?,
-            n_p_u15_t = ?,
-            n_p_u15_t_hosp = ?,
-            n_p_u15_a = ?,
-            n_p_u15_a_hosp = ?,
-            n_p_o15_p = ?,
-            n_p_o15_p_hosp = ?,
-            n_p_o15_t = ?,
-            n_p_o15_t_hosp = ?,
-            n_p_o15_a = ?,
-            n_p_o15_a_hosp = ?,
-            s_e_u15_p = ?,
-            s_e_u15_p_hosp = ?,
-            s_e_u15_t = ?,
-            s_e_u15_t_hosp = ?,
-            s_e_u15_a = ?,
-            s_e_u15_a_hosp = ?,
-            s_e_o15_p = ?,
-            s_e_o15_p_hosp = ?,
-            s_e_o15_t = ?,
-            s_e_o15_t_hosp = ?,
-            s_e_o15_a = ?,
-            s_e_o15_a_hosp = ?,
-            s_p_u15_p = ?,
-            s_p_u15_p_hosp = ?,
-            s_p_u15_t = ?,
-            s_p_u15_t_hosp = ?,
-            s_p_u15_a = ?,
-            s_p_u15_a_hosp = ?,
-            s_p_o15_p = ?,
-            s_p_o15_p_hosp = ?,
-            s_p_o15_t = ?,
-            s_p_o15_t_hosp = ?,
-            s_p_o15_a = ?,
-            s_p_o15_a_hosp = ?
+            birthdate = ?
         WHERE id = ?;
         "#,
         body.club_id,
@@ -186,70 +78,14 @@ pub async fn edit_club_judge(
         body.lastname,
         body.mail,
         body.birthdate,
-        body.n_ew_u15_p,
-        body.n_ew_u15_p_hosp,
-        body.n_ew_u15_t,
-        body.n_ew_u15_t_hosp,
-        body.n_ew_u15_a,
-        body.n_ew_u15_a_hosp,
-        body.n_ew_o15_p,
-        body.n_ew_o15_p_hosp,
-        body.n_ew_o15_t,
-        body.n_ew_o15_t_hosp,
-        body.n_ew_o15_a,
-        body.n_ew_o15_a_hosp,
-        body.n_em_u15_p,
-        body.n_em_u15_p_hosp,
-        body.n_em_u15_t,
-        body.n_em_u15_t_hosp,
-        body.n_em_u15_a,
-        body.n_em_u15_a_hosp,
-        body.n_em_o15_p,
-        body.n_em_o15_p_hosp,
-        body.n_em_o15_t,
-        body.n_em_o15_t_hosp,
-        body.n_em_o15_a,
-        body.n_em_o15_a_hosp,
-        body.n_p_u15_p,
-        body.n_p_u15_p_hosp,
-        body.n_p_u15_t,
-        body.n_p_u15_t_hosp,
-        body.n_p_u15_a,
-        body.n_p_u15_a_hosp,
-        body.n_p_o15_p,
-        body.n_p_o15_p_hosp,
-        body.n_p_o15_t,
-        body.n_p_o15_t_hosp,
-        body.n_p_o15_a,
-        body.n_p_o15_a_hosp,
-        body.s_e_u15_p,
-        body.s_e_u15_p_hosp,
-        body.s_e_u15_t,
-        body.s_e_u15_t_hosp,
-        body.s_e_u15_a,
-        body.s_e_u15_a_hosp,
-        body.s_e_o15_p,
-        body.s_e_o15_p_hosp,
-        body.s_e_o15_t,
-        body.s_e_o15_t_hosp,
-        body.s_e_o15_a,
-        body.s_e_o15_a_hosp,
-        body.s_p_u15_p,
-        body.s_p_u15_p_hosp,
-        body.s_p_u15_t,
-        body.s_p_u15_t_hosp,
-        body.s_p_u15_a,
-        body.s_p_u15_a_hosp,
-        body.s_p_o15_p,
-        body.s_p_o15_p_hosp,
-        body.s_p_o15_t,
-        body.s_p_o15_t_hosp,
-        body.s_p_o15_a,
-        body.s_p_o15_a_hosp,
         body.judge_id,
     )
-    .execute(&db)
+    .execute(&mut *tx)
     .await?;
+    set_qualifications(&mut tx, body.judge_id, &body.qualifications).await?;
+    tx.commit().await?;
+    audit.before(before);
+    audit.after(get_club_judge(&db, body.judge_id).await?);
 
     Ok(Json(EditClubJudgeResponse {}))
 }
@@ -5,10 +5,15 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{
+        ClientError, HttpError, extractor::club_access::ClubAccess,
+        routes::query::list_club_starters::get_club_starter,
+    },
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
-    utils::{delete_act, get_act_id_for_starter_id, set_act},
+    utils::{delete_act, get_act_id_for_starter_id, get_competition_id_for_club_id, set_act},
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -44,21 +49,42 @@ pub struct EditClubStarterBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn edit_club_starter(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(mut body): Json<EditClubStarterBody>,
 ) -> Result<Json<EditClubStarterResponse>, HttpError> {
     if !capabilities.can_register_starter {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access
+        .check_starter(body.starter_id, ClubRole::Trainer)
+        .await?;
+    if let Some(partner_id) = body.partner_id {
+        access.check_starter(partner_id, ClubRole::Trainer).await?;
+    }
     let db = db.get().await.clone();
 
+    let before = get_club_starter(&db, body.starter_id).await?;
     let self_name = format!("{} {}", body.firstname, body.lastname);
 
+    let self_club_id = sqlx::query!(
+        r#"
+        SELECT club_id as "club_id: Uuid" FROM starter WHERE id = ?
+        "#,
+        body.starter_id,
+    )
+    .fetch_one(&db)
+    .await?
+    .club_id;
+    let competition_id = get_competition_id_for_club_id(&db, self_club_id)
+        .await
+        .map_err(HttpError::ErrorMessages)?;
+
     let existing_partner_name = sqlx::query!(
         r#"
         SELECT partner_name FROM starter WHERE id = ?
@@ -77,15 +103,6 @@ pub async fn edit_club_starter(
     let partner_id = if let Some(partner_id) = body.partner_id {
         Some(partner_id)
     } else {
-        let self_club_id = sqlx::query!(
-            r#"
-            SELECT club_id as "club_id: Uuid" FROM starter WHERE id = ?
-            "#,
-            body.starter_id,
-        )
-        .fetch_one(&db)
-        .await?
-        .club_id;
         let rows = sqlx::query!(
             r#"
             SELECT id as "id!: Uuid" FROM starter WHERE concat_ws(" ", firstname, lastname) = ? AND pair = TRUE AND club_id = ?
@@ -173,9 +190,16 @@ pub async fn edit_club_starter(
                     .map_err(HttpError::ErrorMessages)?;
             }
 
-            set_act(&db, "", &[body.starter_id, partner_id], None, true)
-                .await
-                .map_err(HttpError::ErrorMessages)?;
+            set_act(
+                &db,
+                competition_id,
+                "",
+                &[body.starter_id, partner_id],
+                None,
+                true,
+            )
+            .await
+            .map_err(HttpError::ErrorMessages)?;
         }
     }
 
@@ -213,7 +237,7 @@ pub async fn edit_club_starter(
         .await
         .map_err(HttpError::ErrorMessages)?;
     if (body.single_female || body.single_male) && existing_act.is_none() {
-        set_act(&db, "", &[body.starter_id], None, false)
+        set_act(&db, competition_id, "", &[body.starter_id], None, false)
             .await
             .map_err(HttpError::ErrorMessages)?;
     } else if (!body.single_female && !body.single_male) && existing_act.is_some() {
@@ -222,5 +246,8 @@ pub async fn edit_club_starter(
             .map_err(HttpError::ErrorMessages)?;
     }
 
+    audit.before(before);
+    audit.after(get_club_starter(&db, body.starter_id).await?);
+
     Ok(Json(EditClubStarterResponse {}))
 }
@@ -0,0 +1,98 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime};
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    competition::Competition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EditCompetitionResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EditCompetitionBody {
+    id: Uuid,
+    name: String,
+    venue: Option<String>,
+    #[serde(with = "crate::competition::iso_date_list")]
+    #[schema(value_type = Vec<Date>)]
+    days: Vec<Date>,
+    #[serde(with = "time::serde::iso8601")]
+    start_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    end_register_date: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    end_music_upload_date: OffsetDateTime,
+}
+
+/// Edit the name, venue, days and deadlines of a competition.
+#[utoipa::path(
+    post,
+    tags=["command", "competition"],
+    path="/edit_competition",
+    request_body=EditCompetitionBody,
+    responses(
+        (status=200, content_type="application/json", body=EditCompetitionResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn edit_competition(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<EditCompetitionBody>,
+) -> Result<Json<EditCompetitionResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    if body.days.is_empty() {
+        return Err(HttpError::ErrorMessages(
+            "Der Wettbewerb braucht mindestens einen Tag.".to_string(),
+        ));
+    }
+    let db = db.get().await.clone();
+    let before = Competition::get(&db, body.id)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE competitions SET
+            name = ?,
+            venue = ?,
+            start_register_date = ?,
+            end_register_date = ?,
+            end_music_upload_date = ?
+        WHERE id = ?
+        "#,
+        body.name,
+        body.venue,
+        body.start_register_date,
+        body.end_register_date,
+        body.end_music_upload_date,
+        body.id,
+    )
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(HttpError::NotFound);
+    }
+    Competition::set_days(&mut tx, body.id, &body.days).await?;
+
+    tx.commit().await?;
+    audit.before(before);
+    audit.after(Competition::get(&db, body.id).await?);
+
+    Ok(Json(EditCompetitionResponse {}))
+}
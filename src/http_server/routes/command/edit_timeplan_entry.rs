@@ -4,7 +4,13 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth,
+        routes::query::list_timeplan::get_timeplan_entry,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -13,7 +19,7 @@ pub struct EditTimeplanEntryResponse {}
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct EditTimeplanEntryBody {
-    id: i64, // Primary key - timeplan entry to edit
+    id: i64,                             // Primary key - timeplan entry to edit
     earliest_start_time: Option<String>, // ISO datetime string
     duration_seconds: Option<i32>,
     label: Option<String>,
@@ -34,10 +40,13 @@ pub struct EditTimeplanEntryBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn edit_timeplan_entry(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<EditTimeplanEntryBody>,
 ) -> Result<Json<EditTimeplanEntryResponse>, HttpError> {
@@ -45,6 +54,9 @@ pub async fn edit_timeplan_entry(
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
     let db = db.get().await.clone();
+    let before = get_timeplan_entry(&db, competition.id, body.id)
+        .await?
+        .ok_or(HttpError::NotFound)?;
 
     sqlx::query!(
         r#"
@@ -54,16 +66,21 @@ pub async fn edit_timeplan_entry(
             duration_seconds = $2,
             label = $3,
             category = $4
-        WHERE id = $5
+        WHERE id = $5 AND competition_id = $6
         "#,
         body.earliest_start_time,
         body.duration_seconds,
         body.label,
         body.category,
         body.id,
+        competition.id,
     )
     .execute(&db)
     .await?;
+    audit.before(before);
+    audit.after(get_timeplan_entry(&db, competition.id, body.id).await?);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(EditTimeplanEntryResponse {}))
 }
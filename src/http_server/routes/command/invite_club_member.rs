@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use askama::Template;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use url::Url;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    club_membership::{ClubRole, MembershipError},
+    http_server::{ClientError, HttpError, HttpServerOptions, extractor::club_access::ClubAccess},
+    mailer::Mailer,
+    reloadable_sqlite::ReloadableSqlite,
+    templates::ClubInvitationMail,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InviteClubMemberResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteClubMemberBody {
+    club_id: Uuid,
+    email: String,
+    role: ClubRole,
+}
+
+/// Invite somebody to a club by email.
+///
+/// The invitation is accepted with `accept_club_invitation` after logging in
+/// with the invited address. Inviting the same address again replaces the
+/// previous invitation.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/invite_club_member",
+    request_body=InviteClubMemberBody,
+    responses(
+        (status=200, content_type="application/json", body=InviteClubMemberResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, mailer, audit))]
+#[axum::debug_handler]
+pub async fn invite_club_member(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
+    Extension(http_options): Extension<Arc<HttpServerOptions>>,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
+    Json(body): Json<InviteClubMemberBody>,
+) -> Result<Json<InviteClubMemberResponse>, HttpError> {
+    access.check_club(body.club_id, ClubRole::Owner)?;
+    let db = db.get().await.clone();
+    let email = body.email.trim();
+
+    let club_name = sqlx::query_scalar!("SELECT name FROM clubs WHERE id = ?", body.club_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+    let is_member = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM club_memberships JOIN users ON club_memberships.user_id = users.id
+            WHERE club_memberships.club_id = ? AND lower(users.email) = lower(?)
+        ) as "is_member!: bool"
+        "#,
+        body.club_id,
+        email
+    )
+    .fetch_one(&db)
+    .await?;
+    if is_member {
+        return Err(MembershipError::AlreadyMember(email.to_string()).into());
+    }
+
+    info!(
+        "Inviting {} to club {} as {:?}",
+        email, body.club_id, body.role
+    );
+    let token = Uuid::new_v4();
+    let mut tx = db.begin().await?;
+    sqlx::query!(
+        "DELETE FROM club_invitations WHERE club_id = ? AND lower(email) = lower(?)",
+        body.club_id,
+        email
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO club_invitations (id, club_id, email, role, invited_by)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        token,
+        body.club_id,
+        email,
+        body.role,
+        access.auth.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let invitation_link = Url::parse(&http_options.base_url)
+        .expect("Invalid Base URL")
+        .join(&format!("/accept_club_invitation?token={token}"))
+        .expect("Invalid URL")
+        .to_string();
+    mailer
+        .send_text(
+            email,
+            &format!("Freestyle Cup NRW - Einladung in den Verein {club_name}"),
+            &ClubInvitationMail {
+                inviter: &access.auth.name,
+                club_name: &club_name,
+                role: body.role.label(),
+                invitation_link: &invitation_link,
+            }
+            .render()?,
+        )
+        .await?;
+    audit.after(json!({ "club_id": body.club_id, "email": email, "role": body.role }));
+
+    Ok(Json(InviteClubMemberResponse {}))
+}
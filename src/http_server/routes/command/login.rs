@@ -1,18 +1,32 @@
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
 use axum::{Extension, Json};
 use axum_extra::extract::CookieJar;
-use password_auth::verify_password;
+use password_auth::{generate_hash, verify_password};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::{http_server::HttpError, jwt::JWTConfig, reloadable_sqlite::ReloadableSqlite};
+use crate::{
+    http_server::{ClientError, HttpError, extractor::client_ip::ClientIp},
+    jwt::JWTConfig,
+    login_throttle::{Action, LoginThrottle, ThrottleKey},
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+    totp::SecondFactor,
+};
+
+/// Verified instead of a password hash for unknown emails, so they take as
+/// long as wrong passwords.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| generate_hash(Uuid::new_v4().to_string()));
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     user_id: Uuid,
+    /// Unless satisfied the session only allows `verify_totp`, or the TOTP
+    /// enrollment for admins without one.
+    second_factor: SecondFactor,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -31,41 +45,53 @@ pub struct LoginBody {
     request_body=LoginBody,
     responses(
         (status=200, content_type="application/json", body=LoginResponse),
-        (status=401, content_type="application/json", body=String),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=429, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, throttle))]
 #[axum::debug_handler]
 pub async fn login(
     Extension(db): Extension<ReloadableSqlite>,
     cookies: CookieJar,
     Extension(jwt_config): Extension<Arc<JWTConfig>>,
+    Extension(throttle): Extension<LoginThrottle>,
+    ClientIp(ip): ClientIp,
     Json(body): Json<LoginBody>,
 ) -> Result<(CookieJar, Json<LoginResponse>), HttpError> {
     let jwt_config = jwt_config.as_ref().clone();
     let db = db.get().await.clone();
+    let keys = ThrottleKey::for_request(ip, &body.email);
+    throttle.check(&db, Action::Login, &keys).await?;
     let user = sqlx::query!(
         r#"
-        SELECT id as "id!: Uuid", password FROM users WHERE email = ?
+        SELECT id as "id!: Uuid", password, is_admin, totp_secret IS NOT NULL as "totp_enabled!: bool"
+        FROM users WHERE email = ?
         "#,
         body.email,
     )
-    .fetch_one(&db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Failed to find user: {:?}", e);
-        HttpError::InvalidCredentials
-    })?;
+    .fetch_optional(&db)
+    .await?;
 
-    if verify_password(body.password, &user.password).is_ok() {
+    let password_hash = user
+        .as_ref()
+        .map_or(DUMMY_HASH.as_str(), |user| &user.password);
+    let verified = verify_password(body.password, password_hash).is_ok();
+    if let Some(user) = user.filter(|_| verified) {
+        throttle.reset(Action::Login, &keys);
+        let session = session::create(&db, user.id).await?;
         Ok((
-            jwt_config.add_jwt_cookie(cookies, user.id).map_err(|e| {
+            jwt_config.add_jwt_cookie(cookies, &session).map_err(|e| {
                 tracing::error!("Failed to add JWT cookie: {:?}", e);
                 HttpError::InvalidCredentials
             })?,
-            Json(LoginResponse { user_id: user.id }),
+            Json(LoginResponse {
+                user_id: user.id,
+                second_factor: SecondFactor::new(user.is_admin, user.totp_enabled, false),
+            }),
         ))
     } else {
+        throttle.record(&db, Action::Login, &keys).await;
         Err(HttpError::InvalidCredentials)
     }
 }
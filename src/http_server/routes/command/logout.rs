@@ -4,9 +4,20 @@ use axum::{Extension, Json};
 use axum_extra::extract::CookieJar;
 use tracing::instrument;
 
-use crate::{http_server::HttpError, jwt::JWTConfig};
+use crate::{
+    http_server::{
+        HttpError,
+        extractor::auth::{self, PartialAuth},
+    },
+    jwt::JWTConfig,
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+};
 
 /// Log out the currently authenticated user.
+///
+/// Revokes the session, so its tokens can't be used anymore. Clears the
+/// cookies even if the session is revoked already.
 #[utoipa::path(
     post,
     tags=["command", "auth"],
@@ -15,11 +26,16 @@ use crate::{http_server::HttpError, jwt::JWTConfig};
         (status=200, content_type="application/json", body=String),
     ),
 )]
-#[instrument(skip(cookies))]
+#[instrument(skip(db, cookies))]
 pub async fn logout(
+    Extension(db): Extension<ReloadableSqlite>,
     cookies: CookieJar,
     Extension(jwt_config): Extension<Arc<JWTConfig>>,
+    auth: Result<PartialAuth, auth::Error>,
 ) -> Result<(CookieJar, Json<String>), HttpError> {
+    if let Ok(PartialAuth(auth)) = auth {
+        session::revoke(&db.get().await.clone(), auth.session_id).await?;
+    }
     Ok((
         jwt_config.remove_jwt_cookie(cookies).map_err(|e| {
             tracing::error!("Failed to remove JWT cookie: {:?}", e);
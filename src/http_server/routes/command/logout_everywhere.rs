@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use axum_extra::extract::CookieJar;
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    jwt::JWTConfig,
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LogoutEverywhereResponse {
+    /// Number of sessions that were logged out, including this one.
+    revoked_sessions: u64,
+}
+
+/// Log out the current user on every device.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/logout_everywhere",
+    responses(
+        (status=200, content_type="application/json", body=LogoutEverywhereResponse),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, cookies))]
+pub async fn logout_everywhere(
+    Extension(db): Extension<ReloadableSqlite>,
+    cookies: CookieJar,
+    Extension(jwt_config): Extension<Arc<JWTConfig>>,
+    auth: Auth,
+) -> Result<(CookieJar, Json<LogoutEverywhereResponse>), HttpError> {
+    let revoked_sessions = session::revoke_all(&db.get().await.clone(), auth.user_id).await?;
+    info!(
+        "Revoked {revoked_sessions} sessions of user {}",
+        auth.user_id
+    );
+    Ok((
+        jwt_config.remove_jwt_cookie(cookies).map_err(|e| {
+            tracing::error!("Failed to remove JWT cookie: {:?}", e);
+            HttpError::InvalidCredentials
+        })?,
+        Json(LogoutEverywhereResponse { revoked_sessions }),
+    ))
+}
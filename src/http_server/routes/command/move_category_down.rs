@@ -1,10 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -30,10 +34,13 @@ pub struct MoveCategoryDownBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit, live))]
 #[axum::debug_handler]
 pub async fn move_category_down(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<MoveCategoryDownBody>,
 ) -> Result<Json<MoveCategoryDownResponse>, HttpError> {
@@ -49,15 +56,16 @@ pub async fn move_category_down(
     let current = sqlx::query!(
         r#"
         SELECT "order" FROM categories 
-        WHERE name = $1
+        WHERE name = $1 AND competition_id = $2
         "#,
-        body.name
+        body.name,
+        competition.id
     )
     .fetch_optional(&mut *tx)
     .await?;
 
     let current_record = current.ok_or(HttpError::StatusCode(StatusCode::NOT_FOUND))?;
-    
+
     // If category has no order, assign it to the end
     let current_order = if let Some(order) = current_record.order {
         order
@@ -65,23 +73,25 @@ pub async fn move_category_down(
         // Find the maximum order and assign current_order to max + 1
         let max_order = sqlx::query!(
             r#"
-            SELECT MAX("order") as max_order FROM categories
-            "#
+            SELECT MAX("order") as max_order FROM categories WHERE competition_id = $1
+            "#,
+            competition.id
         )
         .fetch_one(&mut *tx)
         .await?
         .max_order
         .unwrap_or(0);
-        
+
         let new_order = max_order + 1;
         sqlx::query!(
-            r#"UPDATE categories SET "order" = $1 WHERE name = $2"#,
+            r#"UPDATE categories SET "order" = $1 WHERE name = $2 AND competition_id = $3"#,
             new_order,
-            body.name
+            body.name,
+            competition.id
         )
         .execute(&mut *tx)
         .await?;
-        
+
         new_order
     };
 
@@ -89,54 +99,70 @@ pub async fn move_category_down(
     let next_entry = sqlx::query!(
         r#"
         SELECT name, "order" FROM categories 
-        WHERE "order" > $1 
+        WHERE "order" > $1 AND competition_id = $2
         ORDER BY "order" ASC 
         LIMIT 1
         "#,
-        current_order
+        current_order,
+        competition.id
     )
     .fetch_optional(&mut *tx)
     .await?;
 
     if let Some(next) = next_entry {
         let next_name = next.name;
-        let next_order = next.order.ok_or(HttpError::StatusCode(StatusCode::BAD_REQUEST))?;
-        
+        let next_order = next
+            .order
+            .ok_or(HttpError::StatusCode(StatusCode::BAD_REQUEST))?;
+
         // Use a temporary negative order to avoid constraint violations
         let temp_order = -999999;
 
         // Move current to temp
         sqlx::query!(
-            r#"UPDATE categories SET "order" = $1 WHERE name = $2"#,
+            r#"UPDATE categories SET "order" = $1 WHERE name = $2 AND competition_id = $3"#,
             temp_order,
-            body.name
+            body.name,
+            competition.id
         )
         .execute(&mut *tx)
         .await?;
 
         // Move next to current position
         sqlx::query!(
-            r#"UPDATE categories SET "order" = $1 WHERE name = $2"#,
+            r#"UPDATE categories SET "order" = $1 WHERE name = $2 AND competition_id = $3"#,
             current_order,
-            next_name
+            next_name,
+            competition.id
         )
         .execute(&mut *tx)
         .await?;
 
         // Move temp to next position
         sqlx::query!(
-            r#"UPDATE categories SET "order" = $1 WHERE name = $2"#,
+            r#"UPDATE categories SET "order" = $1 WHERE name = $2 AND competition_id = $3"#,
             next_order,
-            body.name
+            body.name,
+            competition.id
         )
         .execute(&mut *tx)
         .await?;
 
         tx.commit().await?;
+        audit.before(json!([
+            { "name": body.name, "order": current_order },
+            { "name": next_name, "order": next_order },
+        ]));
+        audit.after(json!([
+            { "name": body.name, "order": next_order },
+            { "name": next_name, "order": current_order },
+        ]));
     } else {
         // No next entry, can't move down
         return Err(HttpError::StatusCode(StatusCode::BAD_REQUEST));
     }
 
+    live.publish(competition.id, LiveChange::TimeplanChanged);
+
     Ok(Json(MoveCategoryDownResponse {}))
 }
@@ -4,7 +4,13 @@ use tracing::instrument;
 use utoipa::ToSchema;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{
+        ClientError, HttpError, extractor::auth::Auth,
+        routes::query::list_timeplan::get_timeplan_entry,
+    },
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -30,10 +36,13 @@ pub struct MoveTimeplanUpBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn move_timeplan_up(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    Extension(live): Extension<LiveHub>,
+    competition: ActiveCompetition,
     auth: Auth,
     Json(body): Json<MoveTimeplanUpBody>,
 ) -> Result<Json<MoveTimeplanUpResponse>, HttpError> {
@@ -49,11 +58,12 @@ pub async fn move_timeplan_up(
     let prev_entry = sqlx::query!(
         r#"
         SELECT id FROM timeplan 
-        WHERE id < $1 
+        WHERE id < $1 AND competition_id = $2
         ORDER BY id DESC 
         LIMIT 1
         "#,
-        body.id
+        body.id,
+        competition.id
     )
     .fetch_optional(&mut *tx)
     .await?;
@@ -61,18 +71,26 @@ pub async fn move_timeplan_up(
     if let Some(prev) = prev_entry {
         let prev_id = prev.id;
         let current_id = body.id;
-        
+        let before = [
+            get_timeplan_entry(&mut *tx, competition.id, current_id).await?,
+            get_timeplan_entry(&mut *tx, competition.id, prev_id).await?,
+        ];
+
         // Use a temporary negative ID to avoid constraint violations
         let temp_id = -999999;
 
         // Move current to temp
-        sqlx::query!(
-            "UPDATE timeplan SET id = $1 WHERE id = $2",
+        let moved = sqlx::query!(
+            "UPDATE timeplan SET id = $1 WHERE id = $2 AND competition_id = $3",
             temp_id,
-            current_id
+            current_id,
+            competition.id
         )
         .execute(&mut *tx)
         .await?;
+        if moved.rows_affected() == 0 {
+            return Err(HttpError::StatusCode(StatusCode::NOT_FOUND));
+        }
 
         // Move previous to current position
         sqlx::query!(
@@ -92,11 +110,19 @@ pub async fn move_timeplan_up(
         .execute(&mut *tx)
         .await?;
 
+        let after = [
+            get_timeplan_entry(&mut *tx, competition.id, prev_id).await?,
+            get_timeplan_entry(&mut *tx, competition.id, current_id).await?,
+        ];
         tx.commit().await?;
+        audit.before(before);
+        audit.after(after);
     } else {
         // No previous entry, can't move up
         return Err(HttpError::StatusCode(StatusCode::BAD_REQUEST));
     }
 
+    live.publish(competition.id, LiveChange::TimeplanChanged);
+
     Ok(Json(MoveTimeplanUpResponse {}))
 }
@@ -16,6 +16,7 @@ use crate::{
     jwt::JWTConfig,
     mailer::Mailer,
     reloadable_sqlite::ReloadableSqlite,
+    session,
     system_status::Capabilities,
     templates::VerifyMail,
     utils::check_password,
@@ -53,7 +54,7 @@ pub async fn register(
     cookies: CookieJar,
     capabilities: Capabilities,
     Extension(db): Extension<ReloadableSqlite>,
-    Extension(mailer): Extension<Arc<Mailer>>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
     Extension(jwt_config): Extension<Arc<JWTConfig>>,
     Extension(http_options): Extension<Arc<HttpServerOptions>>,
     Json(body): Json<RegisterBody>,
@@ -66,24 +67,25 @@ pub async fn register(
     check_email_exists(&db, &body.email).await?;
     let user_id = Uuid::now_v7();
     let hashed_password = generate_hash(body.password);
+    let now = time::OffsetDateTime::now_utc();
     sqlx::query!(
         r#"
-        INSERT INTO users (id, name, email, email_verified, password, is_admin)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO users (id, name, email, email_verified, password, is_admin, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
         user_id,
         body.name,
         body.email,
         false,
         hashed_password,
-        false
+        false,
+        now
     )
     .execute(&db)
     .await?;
 
     // Create a token for email verification
     let email_token = Uuid::new_v4();
-    let now = time::OffsetDateTime::now_utc();
     sqlx::query!(
         r#"
         INSERT INTO mail_verification (id, user_id, created_at)
@@ -114,8 +116,9 @@ pub async fn register(
         )
         .await?;
 
+    let session = session::create(&db, user_id).await?;
     Ok((
-        jwt_config.add_jwt_cookie(cookies, user_id).map_err(|e| {
+        jwt_config.add_jwt_cookie(cookies, &session).map_err(|e| {
             tracing::error!("Failed to add JWT cookie: {:?}", e);
             HttpError::InvalidCredentials
         })?,
@@ -0,0 +1,90 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    club_membership::{self, ClubRole, MembershipError},
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RemoveClubMemberResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RemoveClubMemberBody {
+    club_id: Uuid,
+    user_id: Uuid,
+}
+
+/// Remove a member from a club.
+///
+/// Owners remove other members, everybody can leave a club. The last owner
+/// can't leave.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/remove_club_member",
+    request_body=RemoveClubMemberBody,
+    responses(
+        (status=200, content_type="application/json", body=RemoveClubMemberResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn remove_club_member(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
+    Json(body): Json<RemoveClubMemberBody>,
+) -> Result<Json<RemoveClubMemberResponse>, HttpError> {
+    if access.auth.user_id == body.user_id {
+        access.check_club(body.club_id, ClubRole::ReadOnly)?;
+    } else {
+        access.check_club(body.club_id, ClubRole::Owner)?;
+    }
+    let db = db.get().await.clone();
+
+    let mut tx = db.begin().await?;
+    let role = sqlx::query_scalar!(
+        r#"
+        DELETE FROM club_memberships WHERE club_id = ? AND user_id = ?
+        RETURNING role as "role!: ClubRole"
+        "#,
+        body.club_id,
+        body.user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+    if club_membership::count_owners(&mut tx, body.club_id).await? == 0 {
+        return Err(MembershipError::LastOwner.into());
+    }
+    // Show one of the remaining clubs instead
+    sqlx::query!(
+        r#"
+        UPDATE users SET club_id = (
+            SELECT club_id FROM club_memberships WHERE user_id = $1 ORDER BY created_at LIMIT 1
+        )
+        WHERE id = $1 AND club_id = $2
+        "#,
+        body.user_id,
+        body.club_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    info!("Removed user {} from club {}", body.user_id, body.club_id);
+    audit.before(json!({ "club_id": body.club_id, "user_id": body.user_id, "role": role }));
+
+    Ok(Json(RemoveClubMemberResponse {}))
+}
@@ -1,11 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{HttpError, extractor::auth::Auth},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
     system_status::Capabilities,
 };
@@ -33,42 +36,39 @@ pub struct RenameClubBody {
         (status=200, content_type="application/json", body=RenameClubResponse),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn rename_club(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Json(body): Json<RenameClubBody>,
 ) -> Result<Json<RenameClubResponse>, HttpError> {
     if !capabilities.can_register_starter {
         return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
     }
+    access.check_club(body.club_id, ClubRole::Owner)?;
     let db = db.get().await.clone();
-    if auth.is_admin {
-        sqlx::query!(
-            r#"
-            UPDATE clubs SET name = ? WHERE id = ?
-            "#,
-            body.name,
-            body.club_id,
-        )
-    } else {
-        sqlx::query!(
-            r#"
-            UPDATE clubs SET name = ? WHERE id = ? AND owner_id = ?
-            "#,
-            body.name,
-            body.club_id,
-            auth.user_id,
-        )
-    }
+    let before = sqlx::query_scalar!("SELECT name FROM clubs WHERE id = ?", body.club_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+    sqlx::query!(
+        r#"
+        UPDATE clubs SET name = ? WHERE id = ?
+        "#,
+        body.name,
+        body.club_id,
+    )
     .execute(&db)
     .await
     .map_err(|e| {
         tracing::error!("Failed to rename club: {:?}", e);
         HttpError::InternalServerError
     })?;
+    audit.before(json!({ "club_id": body.club_id, "name": before }));
+    audit.after(json!({ "club_id": body.club_id, "name": body.name }));
 
     Ok(Json(RenameClubResponse {
         club_id: body.club_id,
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
 use crate::{
-    http_server::{ClientError, HttpError, HttpServerOptions}, mailer::Mailer, reloadable_sqlite::ReloadableSqlite, templates::PasswordResetMail
+    http_server::{ClientError, HttpError, HttpServerOptions, extractor::client_ip::ClientIp},
+    login_throttle::{Action, LoginThrottle, ThrottleKey},
+    mailer::Mailer,
+    reloadable_sqlite::ReloadableSqlite,
+    templates::PasswordResetMail,
 };
 use askama::Template;
 use axum::{Extension, Json};
@@ -29,18 +33,24 @@ pub struct RequestPasswordResetBody {
     request_body=RequestPasswordResetBody,
     responses(
         (status=200, content_type="application/json", body=RequestPasswordResetResponse),
+        (status=429, content_type="application/json", body=ClientError),
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(mailer))]
+#[instrument(skip(mailer, throttle))]
 #[axum::debug_handler]
 pub async fn request_password_reset(
-    Extension(mailer): Extension<Arc<Mailer>>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
     Extension(http_options): Extension<Arc<HttpServerOptions>>,
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(throttle): Extension<LoginThrottle>,
+    ClientIp(ip): ClientIp,
     Json(body): Json<RequestPasswordResetBody>,
 ) -> Result<Json<RequestPasswordResetResponse>, HttpError> {
     let db = db.get().await.clone();
+    let keys = ThrottleKey::for_request(ip, &body.email);
+    throttle.check(&db, Action::PasswordReset, &keys).await?;
+    throttle.record(&db, Action::PasswordReset, &keys).await;
     let user = sqlx::query!(
         r#"
         SELECT id as "id!: Uuid", name FROM users WHERE email = ?
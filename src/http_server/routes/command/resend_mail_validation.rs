@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use crate::{
-    http_server::{ClientError, HttpError, HttpServerOptions, extractor::auth::Auth}, mailer::Mailer, reloadable_sqlite::ReloadableSqlite, templates::VerifyMail
+    http_server::{ClientError, HttpError, HttpServerOptions, extractor::auth::Auth},
+    mailer::Mailer,
+    reloadable_sqlite::ReloadableSqlite,
+    templates::VerifyMail,
 };
 use askama::Template;
 use axum::{Extension, Json};
@@ -33,7 +36,7 @@ pub struct ResendMailValidationBody {}
 #[instrument(skip(mailer))]
 #[axum::debug_handler]
 pub async fn resend_mail_validation(
-    Extension(mailer): Extension<Arc<Mailer>>,
+    Extension(mailer): Extension<Arc<dyn Mailer>>,
     Extension(http_options): Extension<Arc<HttpServerOptions>>,
     Extension(db): Extension<ReloadableSqlite>,
     auth: Auth,
@@ -1,10 +1,14 @@
 use crate::{
-    http_server::{ClientError, HttpError}, reloadable_sqlite::ReloadableSqlite, utils::check_password
+    http_server::{ClientError, HttpError, HttpServerOptions},
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+    utils::check_password,
 };
 use axum::{Extension, Json};
 use password_auth::generate_hash;
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use std::sync::Arc;
+use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -27,26 +31,32 @@ pub struct PasswordResetBody {
     request_body=PasswordResetBody,
     responses(
         (status=200, content_type="application/json", body=PasswordResetResponse),
+        (status=404, content_type="application/json", body=ClientError),
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, http_options))]
 #[axum::debug_handler]
 pub async fn reset_password(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(http_options): Extension<Arc<HttpServerOptions>>,
     Json(body): Json<PasswordResetBody>,
 ) -> Result<Json<PasswordResetResponse>, HttpError> {
     let db = db.get().await.clone();
+    check_password(&body.new_password).map_err(HttpError::ErrorMessages)?;
+    // Use the token up right away, so it works only once
+    let valid_since = time::OffsetDateTime::now_utc() - http_options.lifetimes.password_reset;
     let user = sqlx::query!(
         r#"
-        SELECT user_id as "user_id!: Uuid" FROM password_resets WHERE id = ?
+        DELETE FROM password_resets WHERE id = ? AND created_at > ?
+        RETURNING user_id as "user_id!: Uuid"
         "#,
-        body.token
+        body.token,
+        valid_since
     )
     .fetch_optional(&db)
     .await?;
     if let Some(user) = user {
-        check_password(&body.new_password).map_err(HttpError::ErrorMessages)?;
         // Update the password
         let password_hash = generate_hash(body.new_password);
         sqlx::query!(
@@ -67,6 +77,9 @@ pub async fn reset_password(
         )
         .execute(&db)
         .await?;
+        // Log out everywhere, the old password might have been stolen
+        let revoked = session::revoke_all(&db, user.user_id).await?;
+        info!("Revoked {revoked} sessions of user {}", user.user_id);
         Ok(Json(PasswordResetResponse {}))
     } else {
         Err(HttpError::NotFound)
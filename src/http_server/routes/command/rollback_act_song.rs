@@ -0,0 +1,72 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+    song_versions,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RollbackActSongResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RollbackActSongBody {
+    act_id: Uuid,
+    /// The version as listed by `list_act_song_versions`.
+    version: i64,
+}
+
+/// Make an earlier upload the song of an act again.
+///
+/// The song is checked if this version was checked.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/rollback_act_song",
+    request_body=RollbackActSongBody,
+    responses(
+        (status=200, content_type="application/json", body=RollbackActSongResponse),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn rollback_act_song(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<RollbackActSongBody>,
+) -> Result<Json<RollbackActSongResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let version_id = song_versions::find(&db, body.act_id, body.version)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+    let before = sqlx::query_scalar!(
+        r#"
+        SELECT v.version
+        FROM acts LEFT JOIN act_song_versions v ON v.id = acts.song_version_id
+        WHERE acts.id = ?
+        "#,
+        body.act_id
+    )
+    .fetch_one(&db)
+    .await?;
+
+    song_versions::activate(&mut *db.acquire().await?, version_id).await?;
+    audit.before(json!({ "act_id": body.act_id, "version": before }));
+    audit.after(json!({ "act_id": body.act_id, "version": body.version }));
+
+    Ok(Json(RollbackActSongResponse {}))
+}
@@ -6,22 +6,30 @@ use axum::{
     http::StatusCode,
 };
 use serde::{Deserialize, Serialize};
-use tokio::{
-    fs::{DirBuilder, File},
-    io::AsyncWriteExt,
-};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, HttpServerOptions, extractor::auth::Auth},
+    audio::{self, AudioInfo},
+    audit::Audit,
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
+    song_storage::{self, SongStorage},
+    song_versions::{self, NewSongVersion},
     system_status::Capabilities,
 };
 
 #[derive(Debug, Serialize, ToSchema)]
-pub struct SaveActSongResponse {}
+pub struct SaveActSongResponse {
+    song: AudioInfo,
+    /// The song is longer than the acts of the category may be.
+    too_long: bool,
+    /// Number of the upload, earlier versions can be restored.
+    version: i64,
+}
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct SaveActSongQuery {
@@ -53,16 +61,21 @@ pub struct Upload {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, storage, audit))]
 #[axum::debug_handler]
 pub async fn save_act_song(
     Extension(db): Extension<ReloadableSqlite>,
-    http_options: Extension<Arc<HttpServerOptions>>,
-    auth: Auth,
+    Extension(audit): Extension<Audit>,
+    Extension(storage): Extension<Arc<dyn SongStorage>>,
+    access: ClubAccess,
     capabilities: Capabilities,
     Query(query): Query<SaveActSongQuery>,
     mut body: Multipart,
 ) -> Result<Json<SaveActSongResponse>, HttpError> {
+    if !capabilities.can_upload_music {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    access.check_act(query.act_id, ClubRole::Trainer).await?;
     let db = db.get().await.clone();
     let entry = body
         .next_field()
@@ -79,36 +92,57 @@ pub async fn save_act_song(
         .extension()
         .ok_or(HttpError::StatusCode(StatusCode::BAD_REQUEST))?;
 
-    let save_file_name = format!("{}.{}", query.act_id, extension.to_string_lossy());
-
-    let path = http_options.data_path.join(&save_file_name);
+    let extension = extension.to_string_lossy().to_lowercase();
     let data = entry
         .bytes()
         .await
         .map_err(|_e| HttpError::InternalServerError)?;
-    DirBuilder::new()
-        .recursive(true)
-        .create(path.parent().unwrap())
-        .await
-        .map_err(|e| HttpError::ErrorMessages(e.to_string()))?;
-    File::create(&path)
-        .await
-        .map_err(|e| HttpError::ErrorMessages(e.to_string()))?
-        .write_all(&data)
-        .await
-        .map_err(|e| HttpError::ErrorMessages(e.to_string()))?;
-    sqlx::query!(
+    let song = {
+        let data = data.clone();
+        let extension = extension.clone();
+        tokio::task::spawn_blocking(move || audio::probe(&data, Some(&extension)))
+            .await
+            .map_err(|_e| HttpError::InternalServerError)??
+    };
+    let sha256 = song_storage::sha256(&data);
+    let save_file_name = song_storage::store(storage.as_ref(), &sha256, data, &extension).await?;
+    let (_, version) = song_versions::create(
+        &db,
+        NewSongVersion {
+            act_id: query.act_id,
+            file: &save_file_name,
+            original_file_name: &file_name,
+            sha256: &sha256,
+            song: &song,
+            uploaded_by: access.auth.user_id,
+        },
+    )
+    .await?;
+    audit.after(json!({
+        "act_id": query.act_id,
+        "version": version,
+        "file": save_file_name,
+        "original_file_name": file_name,
+        "sha256": sha256,
+    }));
+
+    let act_duration_seconds = sqlx::query_scalar!(
         r#"
-        UPDATE acts
-        SET song_file_name = ?, song_file = ?
-        WHERE id = ?
+        SELECT categories.act_duration_seconds
+        FROM view_act
+          JOIN categories ON view_act.category = categories.name
+            AND view_act.competition_id = categories.competition_id
+        WHERE view_act.id = ?
         "#,
-        file_name,
-        save_file_name,
-        query.act_id,
+        query.act_id
     )
-    .execute(&db)
+    .fetch_optional(&db)
     .await?;
+    let too_long = act_duration_seconds.is_some_and(|max| song.duration_seconds > max as f64);
 
-    Ok(Json(SaveActSongResponse {}))
+    Ok(Json(SaveActSongResponse {
+        song,
+        too_long,
+        version,
+    }))
 }
@@ -1,11 +1,14 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::instrument;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -32,10 +35,12 @@ pub struct ActOrderBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn set_act_order(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(live): Extension<LiveHub>,
+    Extension(audit): Extension<Audit>,
     auth: Auth,
     Json(body): Json<ActOrderBody>,
 ) -> Result<Json<SetActOrderResponse>, HttpError> {
@@ -44,17 +49,29 @@ pub async fn set_act_order(
     }
     let db = db.get().await.clone();
 
-    sqlx::query!(
+    let before = sqlx::query_scalar!(r#"SELECT "order" FROM acts WHERE id = ?"#, body.act_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+
+    let act = sqlx::query!(
         r#"
         UPDATE acts
         SET "order" = $1
         WHERE id = $2
+        RETURNING competition_id as "competition_id!: Uuid"
         "#,
         body.order,
         body.act_id,
     )
-    .execute(&db)
+    .fetch_optional(&db)
     .await?;
 
+    if let Some(act) = act {
+        live.publish(act.competition_id, LiveChange::TimeplanChanged);
+    }
+    audit.before(json!({ "act_id": body.act_id, "order": before }));
+    audit.after(json!({ "act_id": body.act_id, "order": body.order }));
+
     Ok(Json(SetActOrderResponse {}))
 }
@@ -0,0 +1,77 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    club_membership::{self, ClubRole, MembershipError},
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetClubMemberRoleResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetClubMemberRoleBody {
+    club_id: Uuid,
+    user_id: Uuid,
+    role: ClubRole,
+}
+
+/// Change the role of a member of a club.
+///
+/// The last owner of a club can't give up the role.
+#[utoipa::path(
+    post,
+    tags=["command", "club"],
+    path="/set_club_member_role",
+    request_body=SetClubMemberRoleBody,
+    responses(
+        (status=200, content_type="application/json", body=SetClubMemberRoleResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn set_club_member_role(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    access: ClubAccess,
+    Json(body): Json<SetClubMemberRoleBody>,
+) -> Result<Json<SetClubMemberRoleResponse>, HttpError> {
+    access.check_club(body.club_id, ClubRole::Owner)?;
+    let db = db.get().await.clone();
+
+    let mut tx = db.begin().await?;
+    let before = sqlx::query_scalar!(
+        r#"
+        SELECT role as "role!: ClubRole" FROM club_memberships WHERE club_id = ? AND user_id = ?
+        "#,
+        body.club_id,
+        body.user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
+    info!(
+        "Setting role of user {} in club {} to {:?}",
+        body.user_id, body.club_id, body.role
+    );
+    club_membership::set(&mut tx, body.club_id, body.user_id, body.role).await?;
+    if club_membership::count_owners(&mut tx, body.club_id).await? == 0 {
+        return Err(MembershipError::LastOwner.into());
+    }
+    tx.commit().await?;
+    audit.before(json!({ "club_id": body.club_id, "user_id": body.user_id, "role": before }));
+    audit.after(json!({ "club_id": body.club_id, "user_id": body.user_id, "role": body.role }));
+
+    Ok(Json(SetClubMemberRoleResponse {}))
+}
@@ -0,0 +1,125 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge::JudgeRole,
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetJudgePanelAssignmentResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetJudgePanelAssignmentBody {
+    timeplan_id: i64,
+    judge_id: Uuid,
+    role: JudgeRole,
+    #[serde(default)]
+    hospitation: bool,
+}
+
+/// Put a judge into the panel of a timeplan entry by hand.
+///
+/// Manual assignments are kept when the panels are assigned again.
+#[utoipa::path(
+    post,
+    tags=["command", "judge"],
+    path="/set_judge_panel_assignment",
+    request_body=SetJudgePanelAssignmentBody,
+    responses(
+        (status=200, content_type="application/json", body=SetJudgePanelAssignmentResponse),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn set_judge_panel_assignment(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+    Json(body): Json<SetJudgePanelAssignmentBody>,
+) -> Result<Json<SetJudgePanelAssignmentResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let valid = sqlx::query_scalar!(
+        r#"
+        SELECT
+            EXISTS (
+                SELECT 1 FROM timeplan
+                WHERE id = ? AND competition_id = ? AND category IS NOT NULL
+            )
+            AND EXISTS (
+                SELECT 1 FROM judge JOIN clubs ON clubs.id = judge.club_id
+                WHERE judge.id = ? AND clubs.competition_id = ?
+            ) as "valid!: bool"
+        "#,
+        body.timeplan_id,
+        competition.id,
+        body.judge_id,
+        competition.id,
+    )
+    .fetch_one(&db)
+    .await?;
+    if !valid {
+        return Err(HttpError::NotFound);
+    }
+
+    let before = sqlx::query!(
+        r#"
+        SELECT role as "role: JudgeRole", hospitation, is_manual
+        FROM judge_panel WHERE timeplan_id = ? AND judge_id = ?
+        "#,
+        body.timeplan_id,
+        body.judge_id,
+    )
+    .fetch_optional(&db)
+    .await?
+    .map(|assignment| {
+        json!({
+            "timeplan_id": body.timeplan_id,
+            "judge_id": body.judge_id,
+            "role": assignment.role,
+            "hospitation": assignment.hospitation,
+            "is_manual": assignment.is_manual,
+        })
+    });
+
+    sqlx::query!(
+        r#"
+        INSERT INTO judge_panel (timeplan_id, judge_id, role, hospitation, is_manual)
+        VALUES (?, ?, ?, ?, TRUE)
+        ON CONFLICT (timeplan_id, judge_id) DO UPDATE SET
+            role = excluded.role,
+            hospitation = excluded.hospitation,
+            is_manual = TRUE
+        "#,
+        body.timeplan_id,
+        body.judge_id,
+        body.role,
+        body.hospitation,
+    )
+    .execute(&db)
+    .await?;
+    audit.before(before);
+    audit.after(json!({
+        "timeplan_id": body.timeplan_id,
+        "judge_id": body.judge_id,
+        "role": body.role,
+        "hospitation": body.hospitation,
+        "is_manual": true,
+    }));
+
+    Ok(Json(SetJudgePanelAssignmentResponse {}))
+}
@@ -0,0 +1,75 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetMusicOperatorResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MusicOperatorBody {
+    user_id: Uuid,
+    is_music_operator: bool,
+}
+
+/// Grant or revoke the music operator role of a user.
+///
+/// Music operators run the music control and can hear every song.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/set_music_operator",
+    request_body=MusicOperatorBody,
+    responses(
+        (status=200, content_type="application/json", body=SetMusicOperatorResponse),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn set_music_operator(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    auth: Auth,
+    Json(body): Json<MusicOperatorBody>,
+) -> Result<Json<SetMusicOperatorResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    info!(
+        "Setting music operator to {} for user {}",
+        body.is_music_operator, body.user_id
+    );
+
+    let before = sqlx::query_scalar!(
+        "SELECT is_music_operator FROM users WHERE id = ?",
+        body.user_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
+    sqlx::query!(
+        "UPDATE users SET is_music_operator = ? WHERE id = ?",
+        body.is_music_operator,
+        body.user_id,
+    )
+    .execute(&db)
+    .await?;
+    audit.before(json!({ "user_id": body.user_id, "is_music_operator": before }));
+    audit.after(json!({ "user_id": body.user_id, "is_music_operator": body.is_music_operator }));
+
+    Ok(Json(SetMusicOperatorResponse {}))
+}
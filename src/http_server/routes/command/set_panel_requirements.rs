@@ -0,0 +1,107 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge_panel::PanelRequirements,
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetPanelRequirementsResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetPanelRequirementsBody {
+    category: String,
+    #[serde(flatten)]
+    requirements: PanelRequirements,
+}
+
+/// Set how many judges the panel of a category needs.
+#[utoipa::path(
+    post,
+    tags=["command", "category"],
+    path="/set_panel_requirements",
+    request_body=SetPanelRequirementsBody,
+    responses(
+        (status=200, content_type="application/json", body=SetPanelRequirementsResponse),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn set_panel_requirements(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+    Json(body): Json<SetPanelRequirementsBody>,
+) -> Result<Json<SetPanelRequirementsResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let requirements = body.requirements;
+    if [
+        requirements.performance,
+        requirements.technique,
+        requirements.dismount,
+        requirements.hospitation,
+    ]
+    .iter()
+    .any(|count| *count < 0)
+    {
+        return Err(HttpError::ErrorMessages(
+            "Die Anzahl der Wertungsrichter darf nicht negativ sein.".to_string(),
+        ));
+    }
+    let db = db.get().await.clone();
+    let before = sqlx::query_as!(
+        PanelRequirements,
+        r#"
+        SELECT
+            panel_performance as performance,
+            panel_technique as technique,
+            panel_dismount as dismount,
+            panel_hospitation as hospitation
+        FROM categories WHERE name = ? AND competition_id = ?
+        "#,
+        body.category,
+        competition.id,
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE categories
+        SET
+            panel_performance = ?,
+            panel_technique = ?,
+            panel_dismount = ?,
+            panel_hospitation = ?
+        WHERE name = ? AND competition_id = ?
+        "#,
+        requirements.performance,
+        requirements.technique,
+        requirements.dismount,
+        requirements.hospitation,
+        body.category,
+        competition.id,
+    )
+    .execute(&db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(HttpError::NotFound);
+    }
+    audit.before(json!({ "category": body.category, "requirements": before }));
+    audit.after(json!({ "category": body.category, "requirements": requirements }));
+
+    Ok(Json(SetPanelRequirementsResponse {}))
+}
@@ -1,10 +1,12 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -32,10 +34,11 @@ pub struct ClubPaymentBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn set_payment(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
     auth: Auth,
     Json(body): Json<ClubPaymentBody>,
 ) -> Result<Json<SetClubPaymentResponse>, HttpError> {
@@ -49,6 +52,11 @@ pub async fn set_payment(
         body.amount, body.club_id
     );
 
+    let before = sqlx::query_scalar!("SELECT payment FROM clubs WHERE id = ?", body.club_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+
     sqlx::query!(
         r#"
         UPDATE clubs
@@ -60,6 +68,8 @@ pub async fn set_payment(
     )
     .execute(&db)
     .await?;
+    audit.before(json!({ "club_id": body.club_id, "payment": before }));
+    audit.after(json!({ "club_id": body.club_id, "payment": body.amount }));
 
     Ok(Json(SetClubPaymentResponse {}))
 }
@@ -1,10 +1,12 @@
 use axum::{Extension, Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use tracing::{info, instrument};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -32,10 +34,11 @@ pub struct SongCheckedBody {
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, audit))]
 #[axum::debug_handler]
 pub async fn set_song_checked(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
     auth: Auth,
     Json(body): Json<SongCheckedBody>,
 ) -> Result<Json<SetSongCheckedResponse>, HttpError> {
@@ -49,6 +52,11 @@ pub async fn set_song_checked(
         body.checked, body.act_id
     );
 
+    let before = sqlx::query_scalar!("SELECT song_checked FROM acts WHERE id = ?", body.act_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::NotFound)?;
+
     sqlx::query!(
         r#"
         UPDATE acts
@@ -60,6 +68,20 @@ pub async fn set_song_checked(
     )
     .execute(&db)
     .await?;
+    // Uploading another song resets the check, rolling back restores it
+    sqlx::query!(
+        r#"
+        UPDATE act_song_versions
+        SET checked = $1
+        WHERE id = (SELECT song_version_id FROM acts WHERE id = $2)
+        "#,
+        body.checked,
+        body.act_id,
+    )
+    .execute(&db)
+    .await?;
+    audit.before(json!({ "act_id": body.act_id, "song_checked": before }));
+    audit.after(json!({ "act_id": body.act_id, "song_checked": body.checked }));
 
     Ok(Json(SetSongCheckedResponse {}))
 }
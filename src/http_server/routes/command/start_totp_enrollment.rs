@@ -0,0 +1,59 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::Serialize;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{ClientError, HttpError, extractor::auth::PartialAuth},
+    reloadable_sqlite::ReloadableSqlite,
+    totp,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StartTotpEnrollmentResponse {
+    /// Base32 secret for manual entry in the authenticator app.
+    secret: String,
+    /// The same secret as `otpauth://` URL for a QR code.
+    otpauth_url: String,
+}
+
+/// Create a new TOTP secret for the current user.
+///
+/// The secret is only used after it was confirmed with a code by
+/// `confirm_totp_enrollment`. Admins without TOTP may call this right after
+/// the login.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/start_totp_enrollment",
+    responses(
+        (status=200, content_type="application/json", body=StartTotpEnrollmentResponse),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+#[axum::debug_handler]
+pub async fn start_totp_enrollment(
+    Extension(db): Extension<ReloadableSqlite>,
+    PartialAuth(auth): PartialAuth,
+) -> Result<Json<StartTotpEnrollmentResponse>, HttpError> {
+    if !auth.second_factor.may_enroll() {
+        return Err(HttpError::StatusCode(StatusCode::UNAUTHORIZED));
+    }
+    let db = db.get().await.clone();
+    let secret = totp::generate_secret();
+    let otpauth_url = totp::otpauth_url(&secret, &auth.email)?;
+    sqlx::query!(
+        "UPDATE users SET totp_pending_secret = ? WHERE id = ?",
+        secret,
+        auth.user_id
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(StartTotpEnrollmentResponse {
+        secret,
+        otpauth_url,
+    }))
+}
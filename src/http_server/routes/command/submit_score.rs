@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use axum::{Extension, Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::instrument;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge::JudgeRole,
+    reloadable_sqlite::ReloadableSqlite,
+    scoring::ScoreCriterion,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubmitScoreResponse {
+    act_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScoreValue {
+    criterion: ScoreCriterion,
+    value: f64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SubmitScoreBody {
+    judge_id: Uuid,
+    /// The act to score, defaults to the act that started last.
+    act_id: Option<Uuid>,
+    role: JudgeRole,
+    criteria: Vec<ScoreValue>,
+}
+
+/// Submit the score of a judge for an act.
+///
+/// Judges enter their own scores for the act on stage, an account counts as
+/// the judge if its verified email is the judge's mail. Admins can enter the
+/// scores of every judge at the scoring desk. The judge has to be in the
+/// panel of the act's category with the given role. A new score of the same
+/// judge replaces the previous one.
+#[utoipa::path(
+    post,
+    tags=["command", "scoring"],
+    path="/submit_score",
+    request_body=SubmitScoreBody,
+    responses(
+        (status=200, content_type="application/json", body=SubmitScoreResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, audit))]
+#[axum::debug_handler]
+pub async fn submit_score(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+    Json(body): Json<SubmitScoreBody>,
+) -> Result<Json<SubmitScoreResponse>, HttpError> {
+    let expected: HashSet<ScoreCriterion> = ScoreCriterion::for_role(body.role)
+        .iter()
+        .copied()
+        .collect();
+    let given: HashSet<ScoreCriterion> = body.criteria.iter().map(|c| c.criterion).collect();
+    if given != expected || body.criteria.len() != expected.len() {
+        return Err(HttpError::ErrorMessages(
+            "Die Wertung muss jedes Kriterium der Rolle genau einmal enthalten.".to_string(),
+        ));
+    }
+    if let Some(invalid) = body
+        .criteria
+        .iter()
+        .find(|c| !c.criterion.is_valid(c.value))
+    {
+        return Err(HttpError::ErrorMessages(format!(
+            "Der Wert {} ist für {:?} nicht erlaubt.",
+            invalid.value, invalid.criterion
+        )));
+    }
+
+    let db = db.get().await.clone();
+    if !auth.is_admin() {
+        let is_own_judge = auth.email_verified
+            && sqlx::query_scalar!(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM judge WHERE id = ? AND lower(mail) = lower(?)
+                ) as "is_own_judge!: bool"
+                "#,
+                body.judge_id,
+                auth.email
+            )
+            .fetch_one(&db)
+            .await?;
+        if !is_own_judge {
+            return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+        }
+    }
+
+    let act_id = match body.act_id {
+        Some(act_id) => act_id,
+        None => sqlx::query_scalar!(
+            r#"
+            SELECT id as "id!: Uuid" FROM acts
+            WHERE competition_id = ? AND started_at IS NOT NULL
+            ORDER BY started_at DESC, "order" DESC
+            LIMIT 1
+            "#,
+            competition.id
+        )
+        .fetch_optional(&db)
+        .await?
+        .ok_or(HttpError::ErrorMessages(
+            "Es hat noch kein Auftritt begonnen.".to_string(),
+        ))?,
+    };
+
+    let category = sqlx::query_scalar!(
+        "SELECT category FROM view_act WHERE id = ? AND competition_id = ?",
+        act_id,
+        competition.id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
+    let in_panel = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM judge_panel
+              JOIN timeplan ON timeplan.id = judge_panel.timeplan_id
+            WHERE timeplan.competition_id = ?
+              AND timeplan.category = ?
+              AND judge_panel.judge_id = ?
+              AND judge_panel.role = ?
+              AND NOT judge_panel.hospitation
+        ) as "in_panel!: bool"
+        "#,
+        competition.id,
+        category,
+        body.judge_id,
+        body.role,
+    )
+    .fetch_one(&db)
+    .await?;
+    if !in_panel {
+        return Err(HttpError::ErrorMessages(
+            "Der Wertungsrichter ist in dieser Rolle nicht im Panel der Kategorie.".to_string(),
+        ));
+    }
+
+    let mut tx = db.begin().await?;
+    let before = sqlx::query_as!(
+        ScoreValue,
+        r#"
+        SELECT criterion as "criterion: ScoreCriterion", value
+        FROM scores WHERE act_id = ? AND judge_id = ?
+        "#,
+        act_id,
+        body.judge_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "DELETE FROM scores WHERE act_id = ? AND judge_id = ?",
+        act_id,
+        body.judge_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    for score in &body.criteria {
+        sqlx::query!(
+            r#"
+            INSERT INTO scores (act_id, judge_id, role, criterion, value)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            act_id,
+            body.judge_id,
+            body.role,
+            score.criterion,
+            score.value,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    audit.before(json!({ "act_id": act_id, "judge_id": body.judge_id, "criteria": before }));
+    audit.after(json!({
+        "act_id": act_id,
+        "judge_id": body.judge_id,
+        "role": body.role,
+        "criteria": body.criteria,
+    }));
+
+    Ok(Json(SubmitScoreResponse { act_id }))
+}
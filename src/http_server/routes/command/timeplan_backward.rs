@@ -2,19 +2,23 @@ use axum::{Extension, Json, http::StatusCode};
 use serde::Serialize;
 use tracing::instrument;
 use utoipa::ToSchema;
-use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
+    timeplan_events,
 };
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SetTimeplanBackwardResponse {}
 
-/// EditClubAct a new user.
+/// Undo the last step of the timeplan progress.
 ///
-/// This endpoint is used to create a new user.
+/// The step stays in the history and can be restored with timeplan_redo
+/// until the timeplan is forwarded again.
 #[utoipa::path(
     post,
     tags=["command", "timeplan"],
@@ -25,10 +29,13 @@ pub struct SetTimeplanBackwardResponse {}
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn timeplan_backward(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(live): Extension<LiveHub>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
     auth: Auth,
 ) -> Result<Json<SetTimeplanBackwardResponse>, HttpError> {
     if !auth.is_admin() {
@@ -36,60 +43,10 @@ pub async fn timeplan_backward(
     }
     let db = db.get().await.clone();
 
-    let running_timeplan_entry = sqlx::query!(
-        "SELECT id, category FROM timeplan WHERE started_at IS NOT NULL AND ended_at IS NULL ORDER BY id LIMIT 1"
-    ).fetch_optional(&db).await?.map(|row| (row.id, row.category));
+    let step = timeplan_events::undo(&db, competition.id).await?;
+    audit.after(step);
 
-    match running_timeplan_entry {
-        Some((id, Some(category))) => {
-            let running_act = sqlx::query!(
-                "SELECT id as 'id!: Uuid' FROM view_act WHERE category = ? AND started_at IS NOT NULL AND ended_at IS NULL ORDER BY `order` LIMIT 1",
-                category
-            ).fetch_optional(&db).await?.map(|row| row.id);
-            if let Some(running_act_id) = running_act {
-                sqlx::query!(
-                    "UPDATE acts SET started_at = NULL WHERE id = ?",
-                    running_act_id
-                )
-                .execute(&db)
-                .await?;
-            } else {
-                let finished_acts = sqlx::query!(
-                    "SELECT id as 'id!: Uuid' FROM view_act WHERE category = ? AND ended_at IS NOT NULL ORDER BY `order` DESC",
-                    category
-                ).fetch_all(&db).await?;
-                if finished_acts.is_empty() {
-                    sqlx::query!("UPDATE timeplan SET started_at = NULL WHERE id = ?", id)
-                        .execute(&db)
-                        .await?;
-                } else {
-                    let last_act_id = finished_acts[0].id;
-                    sqlx::query!("UPDATE acts SET ended_at = NULL WHERE id = ?", last_act_id)
-                        .execute(&db)
-                        .await?;
-                }
-            }
-        }
-        Some((id, None)) => {
-            sqlx::query!("UPDATE timeplan SET started_at = NULL WHERE id = ?", id)
-                .execute(&db)
-                .await?;
-        }
-        None => {
-            let category = sqlx::query!(
-                "UPDATE timeplan SET ended_at = NULL WHERE id = (SELECT id FROM timeplan WHERE ended_at IS NOT NULL ORDER BY id DESC LIMIT 1) RETURNING category"
-            ).fetch_optional(&db).await?.map(|row| row.category);
-
-            if let Some(category) = category {
-                sqlx::query!(
-                    "UPDATE acts SET ended_at = NULL WHERE id = (SELECT id FROM view_act WHERE category = ? ORDER BY `order` DESC LIMIT 1)",
-                    category
-                )
-                .execute(&db)
-                .await?;
-            }
-        }
-    }
+    live.publish(competition.id, LiveChange::TimeplanChanged);
 
     Ok(Json(SetTimeplanBackwardResponse {}))
 }
@@ -5,16 +5,22 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
+    live::{LiveChange, LiveHub},
     reloadable_sqlite::ReloadableSqlite,
+    timeplan_events,
 };
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SetTimeplanForwardResponse {}
 
-/// EditClubAct a new user.
+/// Advance the timeplan progress by one step.
 ///
-/// This endpoint is used to create a new user.
+/// Ends the running act or starts the next act of the running category, and
+/// ends or starts the timeplan entry when its acts are done. The step is
+/// recorded as event and discards the steps that could be redone.
 #[utoipa::path(
     post,
     tags=["command", "timeplan"],
@@ -25,10 +31,13 @@ pub struct SetTimeplanForwardResponse {}
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db))]
+#[instrument(skip(db, live, audit))]
 #[axum::debug_handler]
 pub async fn timeplan_forward(
     Extension(db): Extension<ReloadableSqlite>,
+    Extension(live): Extension<LiveHub>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
     auth: Auth,
 ) -> Result<Json<SetTimeplanForwardResponse>, HttpError> {
     if !auth.is_admin() {
@@ -37,84 +46,94 @@ pub async fn timeplan_forward(
 
     info!("Forwarding timeplan");
     let db = db.get().await.clone();
+    let mut changes = vec![];
 
     let running_timeplan_entry = sqlx::query!(
-        "SELECT id, category FROM timeplan WHERE started_at IS NOT NULL AND ended_at IS NULL ORDER BY id LIMIT 1"
+        "SELECT id, category FROM timeplan WHERE competition_id = ? AND started_at IS NOT NULL AND ended_at IS NULL ORDER BY id LIMIT 1",
+        competition.id
     ).fetch_optional(&db).await?.map(|row| (row.id, row.category));
 
     match running_timeplan_entry {
         Some((id, Some(category))) => {
             let running_act = sqlx::query!(
-                "SELECT id as 'id!: Uuid' FROM view_act WHERE category = ? AND started_at IS NOT NULL AND ended_at IS NULL ORDER BY `order` LIMIT 1",
+                "SELECT id as 'id!: Uuid' FROM view_act WHERE competition_id = ? AND category = ? AND started_at IS NOT NULL AND ended_at IS NULL ORDER BY `order` LIMIT 1",
+                competition.id,
                 category
             ).fetch_optional(&db).await?.map(|row| row.id);
             if let Some(running_act_id) = running_act {
-                sqlx::query!(
-                    "UPDATE acts SET ended_at = datetime('now') WHERE id = ?",
-                    running_act_id
-                )
-                .execute(&db)
-                .await?;
+                changes.push(LiveChange::ActEnded {
+                    act_id: running_act_id,
+                });
 
                 // Ende category if all acts are done
                 let open_cat_acts = sqlx::query!(
-                    "SELECT id FROM view_act WHERE category = ? AND started_at IS NULL",
+                    "SELECT id FROM view_act WHERE competition_id = ? AND category = ? AND started_at IS NULL",
+                    competition.id,
                     category
                 )
                 .fetch_all(&db)
                 .await?;
                 if open_cat_acts.is_empty() {
-                    sqlx::query!(
-                        "UPDATE timeplan SET ended_at = datetime('now') WHERE id = ?",
-                        id
-                    )
-                    .execute(&db)
-                    .await?;
+                    changes.push(LiveChange::EntryEnded { timeplan_id: id });
                 }
             } else {
                 info!("Starting next act");
-                let upcoming_starts = sqlx::query!(
-                    "SELECT id as 'id!: Uuid' FROM view_act WHERE category = ? AND started_at IS NULL ORDER BY `order`",
-                    category
-                ).fetch_all(&db).await?;
-                info!("Upcoming starts: {upcoming_starts:?}");
-                sqlx::query!(
-                    "UPDATE acts SET started_at = datetime('now') WHERE id = (SELECT id FROM view_act WHERE category = ? AND started_at IS NULL ORDER BY `order` LIMIT 1)",
+                let next_act = sqlx::query!(
+                    "SELECT id as 'id!: Uuid' FROM view_act WHERE competition_id = ? AND category = ? AND started_at IS NULL ORDER BY `order` LIMIT 1",
+                    competition.id,
                     category
-                ).execute(&db).await?;
+                ).fetch_optional(&db).await?;
+                match next_act {
+                    Some(act) => changes.push(LiveChange::ActStarted { act_id: act.id }),
+                    // A category without acts would never end otherwise
+                    None => changes.push(LiveChange::EntryEnded { timeplan_id: id }),
+                }
             }
         }
         Some((id, None)) => {
-            sqlx::query!(
-                "UPDATE timeplan SET ended_at = datetime('now') WHERE id = ?",
-                id
-            )
-            .execute(&db)
-            .await?;
+            changes.push(LiveChange::EntryEnded { timeplan_id: id });
         }
         None => {
             info!("Starting Next timeplan entry");
-            start_next_timeplan_entry(&db).await?;
+            changes.extend(start_next_timeplan_entry(&db, competition.id).await?);
         }
     }
 
+    if !changes.is_empty() {
+        timeplan_events::record(&db, competition.id, &changes).await?;
+    }
+    audit.after(&changes);
+    for change in changes {
+        live.publish(competition.id, change);
+    }
+
     Ok(Json(SetTimeplanForwardResponse {}))
 }
 
-async fn start_next_timeplan_entry(db: &sqlx::SqlitePool) -> Result<(), sqlx::Error> {
-    sqlx::query!(
-        "UPDATE timeplan SET started_at = datetime('now') WHERE id = (SELECT id FROM timeplan WHERE started_at IS NULL ORDER BY id LIMIT 1)"
-    ).execute(db).await?;
-
-    let category =
-        sqlx::query!("SELECT id, category FROM timeplan WHERE started_at IS NOT NULL AND ended_at IS NULL ORDER BY id LIMIT 1")
-            .fetch_optional(db)
-            .await?
-            .map(|row| row.category);
+/// The changes to start the next timeplan entry and its first act if it has
+/// no einfahrzeit.
+async fn start_next_timeplan_entry(
+    db: &sqlx::SqlitePool,
+    competition_id: Uuid,
+) -> Result<Vec<LiveChange>, sqlx::Error> {
+    let mut changes = vec![];
+    let next = sqlx::query!(
+        "SELECT id, category FROM timeplan WHERE competition_id = ? AND started_at IS NULL ORDER BY id LIMIT 1",
+        competition_id
+    )
+    .fetch_optional(db)
+    .await?;
+    let category = next.and_then(|row| {
+        changes.push(LiveChange::EntryStarted {
+            timeplan_id: row.id,
+        });
+        row.category
+    });
 
     if let Some(category) = category {
         let einfahrzeit_seconds = sqlx::query!(
-            "SELECT einfahrzeit_seconds FROM categories WHERE name = ?",
+            "SELECT einfahrzeit_seconds FROM categories WHERE competition_id = ? AND name = ?",
+            competition_id,
             category
         )
         .fetch_one(db)
@@ -122,12 +141,16 @@ async fn start_next_timeplan_entry(db: &sqlx::SqlitePool) -> Result<(), sqlx::Er
         .einfahrzeit_seconds;
 
         if einfahrzeit_seconds == 0 {
-            sqlx::query!(
-                "UPDATE acts SET started_at = datetime('now') WHERE id = (SELECT id FROM view_act WHERE category = ? AND started_at IS NULL ORDER BY `order` LIMIT 1)",
+            let first_act = sqlx::query!(
+                "SELECT id as 'id!: Uuid' FROM view_act WHERE competition_id = ? AND category = ? AND started_at IS NULL ORDER BY `order` LIMIT 1",
+                competition_id,
                 category
-            ).execute(db).await?;
+            ).fetch_optional(db).await?;
+            if let Some(act) = first_act {
+                changes.push(LiveChange::ActStarted { act_id: act.id });
+            }
         }
     }
 
-    Ok(())
+    Ok(changes)
 }
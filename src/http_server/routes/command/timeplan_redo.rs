@@ -0,0 +1,49 @@
+use axum::{Extension, Json, http::StatusCode};
+use serde::Serialize;
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    audit::Audit,
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    live::{LiveChange, LiveHub},
+    reloadable_sqlite::ReloadableSqlite,
+    timeplan_events,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetTimeplanRedoResponse {}
+
+/// Restore the last undone step of the timeplan progress.
+#[utoipa::path(
+    post,
+    tags=["command", "timeplan"],
+    path="/timeplan_redo",
+    responses(
+        (status=200, content_type="application/json", body=SetTimeplanRedoResponse),
+        (status=400, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, live, audit))]
+#[axum::debug_handler]
+pub async fn timeplan_redo(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(live): Extension<LiveHub>,
+    Extension(audit): Extension<Audit>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<Json<SetTimeplanRedoResponse>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let step = timeplan_events::redo(&db, competition.id).await?;
+    audit.after(step);
+
+    live.publish(competition.id, LiveChange::TimeplanChanged);
+
+    Ok(Json(SetTimeplanRedoResponse {}))
+}
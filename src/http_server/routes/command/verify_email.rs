@@ -8,9 +8,10 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError},
+    http_server::{ClientError, HttpError, HttpServerOptions},
     jwt::JWTConfig,
     reloadable_sqlite::ReloadableSqlite,
+    session,
 };
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -32,24 +33,30 @@ pub struct VerifyMailBody {
     path="/verify_email",
     responses(
         (status=200, content_type="application/json", body=VerifyMailResponse),
+        (status=404, content_type="application/json", body=ClientError),
         (status=500, content_type="application/json", body=ClientError),
     ),
 )]
-#[instrument(skip(db, jwt_config))]
+#[instrument(skip(db, jwt_config, http_options))]
 #[axum::debug_handler]
 pub async fn verify_email(
     Extension(db): Extension<ReloadableSqlite>,
     Extension(jwt_config): Extension<Arc<JWTConfig>>,
+    Extension(http_options): Extension<Arc<HttpServerOptions>>,
     cookies: CookieJar,
     Json(body): Json<VerifyMailBody>,
 ) -> Result<(CookieJar, Json<VerifyMailResponse>), HttpError> {
     info!("Verifying email");
     let db = db.get().await.clone();
+    // Use the token up right away, so it works only once
+    let valid_since = time::OffsetDateTime::now_utc() - http_options.lifetimes.email_verification;
     let user = sqlx::query!(
         r#"
-        SELECT user_id as "user_id!: Uuid" FROM mail_verification WHERE id = ?
+        DELETE FROM mail_verification WHERE id = ? AND created_at > ?
+        RETURNING user_id as "user_id!: Uuid"
         "#,
-        body.token
+        body.token,
+        valid_since
     )
     .fetch_optional(&db)
     .await?;
@@ -68,13 +75,12 @@ pub async fn verify_email(
         .execute(&db)
         .await?;
         info!("Found user: {:?}", user);
+        let session = session::create(&db, user.user_id).await?;
         Ok((
-            jwt_config
-                .add_jwt_cookie(cookies, user.user_id)
-                .map_err(|e| {
-                    tracing::error!("Failed to add JWT cookie: {:?}", e);
-                    HttpError::InvalidCredentials
-                })?,
+            jwt_config.add_jwt_cookie(cookies, &session).map_err(|e| {
+                tracing::error!("Failed to add JWT cookie: {:?}", e);
+                HttpError::InvalidCredentials
+            })?,
             Json(VerifyMailResponse {
                 user_id: user.user_id,
             }),
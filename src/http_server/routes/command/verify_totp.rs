@@ -0,0 +1,60 @@
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+use crate::{
+    http_server::{
+        ClientError, HttpError,
+        extractor::{auth::PartialAuth, client_ip::ClientIp},
+    },
+    login_throttle::{Action, LoginThrottle, ThrottleKey},
+    reloadable_sqlite::ReloadableSqlite,
+    session, totp,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerifyTotpResponse {}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyTotpBody {
+    /// A code of the authenticator app or a recovery code.
+    code: String,
+}
+
+/// Complete the login with the second factor.
+///
+/// A recovery code can only be used once.
+#[utoipa::path(
+    post,
+    tags=["command", "auth"],
+    path="/verify_totp",
+    request_body=VerifyTotpBody,
+    responses(
+        (status=200, content_type="application/json", body=VerifyTotpResponse),
+        (status=401, content_type="application/json", body=ClientError),
+        (status=429, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, throttle, body))]
+#[axum::debug_handler]
+pub async fn verify_totp(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(throttle): Extension<LoginThrottle>,
+    ClientIp(ip): ClientIp,
+    PartialAuth(auth): PartialAuth,
+    Json(body): Json<VerifyTotpBody>,
+) -> Result<Json<VerifyTotpResponse>, HttpError> {
+    let db = db.get().await.clone();
+    let keys = ThrottleKey::for_request(ip, &auth.email);
+    throttle.check(&db, Action::SecondFactor, &keys).await?;
+    if let Err(e) = totp::verify(&db, auth.user_id, &body.code).await {
+        throttle.record(&db, Action::SecondFactor, &keys).await;
+        return Err(e);
+    }
+    throttle.reset(Action::SecondFactor, &keys);
+    session::verify_second_factor(&db, auth.session_id).await?;
+
+    Ok(Json(VerifyTotpResponse {}))
+}
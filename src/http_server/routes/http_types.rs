@@ -1,5 +1,7 @@
 use uuid::Uuid;
 
+use crate::club_membership::ClubMembership;
+
 #[derive(Debug, serde::Serialize, utoipa::ToSchema)]
 pub struct User {
     pub id: Uuid,
@@ -7,7 +9,9 @@ pub struct User {
     pub email: String,
     pub email_verified: bool,
     pub is_admin: bool,
+    pub is_music_operator: bool,
     pub club_id: Option<Uuid>,
+    pub memberships: Vec<ClubMembership>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema, Clone)]
@@ -18,6 +22,33 @@ pub struct ActParticipant {
     pub club_name: String,
 }
 
+/// The names of all participants, e.g. `Anna Muster & Berta Beispiel`.
+pub fn participant_names(participants: &[ActParticipant]) -> String {
+    participants
+        .iter()
+        .map(|p| format!("{} {}", p.firstname, p.lastname))
+        .collect::<Vec<String>>()
+        .join(" & ")
+}
+
+/// The clubs of all participants, each club only once.
+pub fn participant_clubs(participants: &[ActParticipant]) -> String {
+    let mut clubs: Vec<&str> = vec![];
+    for participant in participants {
+        if !clubs.contains(&participant.club_name.as_str()) {
+            clubs.push(&participant.club_name);
+        }
+    }
+    clubs.join(" & ")
+}
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct StartlistExportQuery {
+    /// Comma separated columns, e.g. `start_number,time,names`. All columns
+    /// are exported by default.
+    pub columns: Option<String>,
+}
+
 #[derive(Debug, serde::Serialize, utoipa::ToSchema)]
 pub struct Act {
     pub id: Uuid,
@@ -26,6 +57,16 @@ pub struct Act {
     pub description: Option<String>,
     pub song_file_name: Option<String>,
     pub song_checked: bool,
+    pub song_duration_seconds: Option<f64>,
+    pub song_codec: Option<String>,
+    /// Average bits per second.
+    pub song_bitrate: Option<i64>,
+    /// The song is longer than the acts of the category may be.
+    pub song_too_long: bool,
+    /// EBU R128 integrated loudness.
+    pub song_loudness_lufs: Option<f64>,
+    /// Copy of the song at the target loudness, served like `song_file`.
+    pub song_normalized_file: Option<String>,
     pub is_pair: Option<bool>,
     pub max_age: Option<f64>,
     pub is_sonderpokal: Option<bool>,
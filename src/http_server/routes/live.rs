@@ -0,0 +1,49 @@
+use axum::{
+    Extension,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+use tracing::instrument;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::ClientError,
+    live::{LiveChange, LiveEvent, LiveHub},
+};
+
+/// Subscribe to the timeplan progress of the active competition.
+///
+/// Every change is sent as server-sent event of type `timeplan`. Subscribers
+/// that fall behind get a `timeplan_changed` event and should reload the
+/// timeplan.
+#[utoipa::path(
+    get,
+    tags=["live", "timeplan"],
+    path="/live",
+    responses(
+        (status=200, content_type="text/event-stream", body=LiveEvent),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(hub))]
+pub async fn live(
+    Extension(hub): Extension<LiveHub>,
+    competition: ActiveCompetition,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let stream = BroadcastStream::new(hub.subscribe()).filter_map(move |event| {
+        let event = match event {
+            Ok(event) if event.competition_id == competition.id => event,
+            Ok(_) => return None,
+            Err(BroadcastStreamRecvError::Lagged(_)) => LiveEvent {
+                competition_id: competition.id,
+                change: LiveChange::TimeplanChanged,
+            },
+        };
+        Some(Event::default().event("timeplan").json_data(event))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -1,38 +1,64 @@
 use utoipa_axum::{router::OpenApiRouter, routes};
 
+mod export_music_package;
 mod get_act;
+mod get_active_competition;
 mod get_club;
+pub(crate) mod get_duration_statistics;
+mod get_results_csv;
+mod get_results_pdf;
+mod get_song_url;
 mod get_startlist_csv;
+mod get_startlist_xlsx;
 mod get_system_status;
+mod list_act_song_versions;
 mod list_acts;
-mod list_categories;
+mod list_audit_log;
+pub(crate) mod list_categories;
 mod list_club_acts;
-mod list_club_judges;
-mod list_club_starters;
+pub(crate) mod list_club_judges;
+mod list_club_members;
+pub(crate) mod list_club_starters;
+mod list_competitions;
+mod list_judge_panels;
 mod list_judges;
 mod list_starters;
-mod list_timeplan;
+pub(crate) mod list_timeplan;
 mod list_users;
 mod predict_timeplan;
+mod results;
 mod startlist;
 mod whoami;
 
 pub fn get_query_router() -> OpenApiRouter {
     OpenApiRouter::new()
         .routes(routes!(get_act::get_act))
+        .routes(routes!(get_song_url::get_song_url))
         .routes(routes!(get_club::get_club))
         .routes(routes!(list_club_starters::list_club_starters))
         .routes(routes!(list_users::list_users))
+        .routes(routes!(list_audit_log::list_audit_log))
         .routes(routes!(whoami::whoami))
         .routes(routes!(get_system_status::get_system_status))
+        .routes(routes!(get_active_competition::get_active_competition))
+        .routes(routes!(list_competitions::list_competitions))
         .routes(routes!(list_club_judges::list_club_judges))
+        .routes(routes!(list_club_members::list_club_members))
         .routes(routes!(list_starters::list_starters))
         .routes(routes!(list_club_acts::list_club_acts))
         .routes(routes!(list_acts::list_acts))
+        .routes(routes!(list_act_song_versions::list_act_song_versions))
         .routes(routes!(list_judges::list_judges))
+        .routes(routes!(list_judge_panels::list_judge_panels))
         .routes(routes!(list_categories::list_categories))
         .routes(routes!(list_timeplan::list_timeplan))
         .routes(routes!(startlist::startlist))
         .routes(routes!(get_startlist_csv::get_startlist_csv))
+        .routes(routes!(get_startlist_xlsx::get_startlist_xlsx))
+        .routes(routes!(export_music_package::export_music_package))
         .routes(routes!(predict_timeplan::predict_timeplan))
+        .routes(routes!(get_duration_statistics::get_duration_statistics))
+        .routes(routes!(results::results))
+        .routes(routes!(get_results_csv::get_results_csv))
+        .routes(routes!(get_results_pdf::get_results_pdf))
 }
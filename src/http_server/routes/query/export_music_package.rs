@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    io::{Seek, SeekFrom, Write},
+    sync::Arc,
+};
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::Query,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use futures_util::StreamExt;
+use tokio::runtime::Handle;
+use tokio_util::io::ReaderStream;
+use tracing::instrument;
+use uuid::Uuid;
+use zip::result::ZipError;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    music_package::{StoredSong, package_songs, render_zip},
+    reloadable_sqlite::ReloadableSqlite,
+    song_storage::{SongStorage, StorageError},
+    timeplan::predict_competition,
+};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct MusicPackageQuery {
+    /// Pack the normalized copies of the songs where they exist.
+    #[serde(default)]
+    normalized: bool,
+}
+
+/// Get all checked songs as ZIP for playing them without the server.
+///
+/// The songs are named `{start_no}_{category}_{names}` in the order of the
+/// timeplan and come with an M3U playlist per day and a `manifest.json`.
+#[utoipa::path(
+    get,
+    tags=["query", "timeplan"],
+    path="/export_music_package",
+    params(MusicPackageQuery),
+    responses(
+        (status=200, content_type="application/zip", body=Vec<u8>),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, storage))]
+pub async fn export_music_package(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(storage): Extension<Arc<dyn SongStorage>>,
+    competition: ActiveCompetition,
+    auth: Auth,
+    Query(query): Query<MusicPackageQuery>,
+) -> Result<impl IntoResponse, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    let timeplan = predict_competition(&db, competition.id).await?;
+
+    let songs: HashMap<Uuid, StoredSong> = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: Uuid",
+            song_file as "song_file!",
+            song_file_name,
+            song_normalized_file,
+            song_duration_seconds,
+            song_loudness_lufs
+        FROM acts
+        WHERE competition_id = ? AND song_checked AND song_file IS NOT NULL
+        "#,
+        competition.id
+    )
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|song| {
+        let file = match song.song_normalized_file {
+            Some(normalized_file) if query.normalized => normalized_file,
+            _ => song.song_file,
+        };
+        let stored = StoredSong {
+            file,
+            original_file_name: song.song_file_name,
+            duration_seconds: song.song_duration_seconds,
+            loudness_lufs: song.song_loudness_lufs,
+        };
+        (song.id, stored)
+    })
+    .collect();
+
+    let songs = package_songs(&timeplan, &songs);
+    let name = competition.name.clone();
+    let runtime = Handle::current();
+    // The package is written to a temporary file one song at a time, so
+    // neither the songs nor the package have to fit into memory
+    let zip = tokio::task::spawn_blocking(move || {
+        let mut file = temp_file()?;
+        render_zip(&mut file, &name, &songs, |song, zip| {
+            runtime
+                .block_on(copy_song(storage.as_ref(), &song.stored_file, zip))
+                .map_err(std::io::Error::other)
+        })?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok::<_, ZipError>(file)
+    })
+    .await
+    .map_err(|_e| HttpError::InternalServerError)?
+    .map_err(|e| HttpError::ErrorMessages(e.to_string()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"musik.zip\"",
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(zip))),
+    ))
+}
+
+/// A file that is removed from the file system right away and is gone once
+/// it is closed.
+fn temp_file() -> std::io::Result<std::fs::File> {
+    let path = std::env::temp_dir().join(format!("music-package-{}.zip", Uuid::now_v7()));
+    let file = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(file)
+}
+
+async fn copy_song(
+    storage: &dyn SongStorage,
+    name: &str,
+    out: &mut impl Write,
+) -> Result<(), StorageError> {
+    let size = storage
+        .head(name)
+        .await?
+        .ok_or_else(|| StorageError::NotFound(name.to_string()))?
+        .size;
+    let mut stream = storage.stream(name, 0..size).await?;
+    while let Some(chunk) = stream.next().await {
+        out.write_all(&chunk?)?;
+    }
+    Ok(())
+}
@@ -3,9 +3,10 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    club_membership::ClubRole,
     http_server::{
         ClientError, HttpError,
-        extractor::auth::Auth,
+        extractor::club_access::ClubAccess,
         routes::http_types::{Act, ActParticipant},
     },
     reloadable_sqlite::ReloadableSqlite,
@@ -32,7 +33,7 @@ pub struct GetActQuery {
 pub async fn get_act(
     Extension(db): Extension<ReloadableSqlite>,
     Query(query): Query<GetActQuery>,
-    auth: Option<Auth>,
+    access: ClubAccess,
 ) -> Result<Json<Act>, HttpError> {
     pub struct DBAct {
         id: Uuid,
@@ -46,6 +47,12 @@ pub async fn get_act(
         participants: sqlx::types::Json<Vec<ActParticipant>>,
         category: Option<String>,
         song_checked: bool,
+        song_duration_seconds: Option<f64>,
+        song_codec: Option<String>,
+        song_bitrate: Option<i64>,
+        song_too_long: bool,
+        song_loudness_lufs: Option<f64>,
+        song_normalized_file: Option<String>,
         act_order: Option<i64>,
         category_order: Option<i64>,
     }
@@ -63,11 +70,21 @@ pub async fn get_act(
                 participants: db_act.participants.0,
                 category: db_act.category,
                 song_checked: db_act.song_checked,
+                song_duration_seconds: db_act.song_duration_seconds,
+                song_codec: db_act.song_codec,
+                song_bitrate: db_act.song_bitrate,
+                song_too_long: db_act.song_too_long,
+                song_loudness_lufs: db_act.song_loudness_lufs,
+                song_normalized_file: db_act.song_normalized_file,
                 act_order: db_act.act_order,
                 category_order: db_act.category_order,
             }
         }
     }
+    // The music control shows the starters of every act
+    if !access.auth.may_control_music() {
+        access.check_act(query.act_id, ClubRole::ReadOnly).await?;
+    }
     let db = db.get().await.clone();
     let act = sqlx::query_as!(
         DBAct,
@@ -85,8 +102,16 @@ pub async fn get_act(
             view_act.is_sonderpokal as "is_sonderpokal: bool",
             participants as "participants!: sqlx::types::Json<Vec<ActParticipant>>",
             category,
-            song_checked
-        FROM view_act JOIN categories ON view_act.category = categories.name
+            song_checked,
+            song_duration_seconds,
+            song_codec,
+            song_bitrate,
+            COALESCE(song_duration_seconds > categories.act_duration_seconds, FALSE) as "song_too_long!: bool",
+            song_loudness_lufs,
+            song_normalized_file
+        FROM view_act
+          JOIN categories ON view_act.category = categories.name
+            AND view_act.competition_id = categories.competition_id
         WHERE id = ?
         "#,
         query.act_id
@@ -0,0 +1,24 @@
+use axum::Json;
+use tracing::instrument;
+
+use crate::{
+    competition::{ActiveCompetition, Competition},
+    http_server::{ClientError, HttpError},
+};
+
+/// Get the competition registrations currently go to.
+#[utoipa::path(
+    get,
+    tags=["query", "competition"],
+    path="/get_active_competition",
+    responses(
+        (status=200, content_type="application/json", body=Competition),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument()]
+pub async fn get_active_competition(
+    competition: ActiveCompetition,
+) -> Result<Json<Competition>, HttpError> {
+    Ok(Json(competition.0))
+}
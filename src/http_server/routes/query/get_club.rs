@@ -3,7 +3,8 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -36,16 +37,11 @@ pub struct GetClubQuery {
 pub async fn get_club(
     Extension(db): Extension<ReloadableSqlite>,
     Query(query): Query<GetClubQuery>,
-    auth: Option<Auth>,
+    access: ClubAccess,
 ) -> Result<Json<Club>, HttpError> {
     let db = db.get().await.clone();
-    let mut club_id = query.club_id;
-    if let Some(auth) = auth
-        && club_id.is_none()
-    {
-        club_id = auth.club_id;
-    }
-    if let Some(club_id) = club_id {
+    if let Some(club_id) = query.club_id.or(access.auth.club_id) {
+        access.check_club(club_id, ClubRole::ReadOnly)?;
         let club = sqlx::query_as!(
             Club,
             r#"
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use axum::{Extension, Json, extract::Query, http::StatusCode};
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    duration_stats::{CategoryDurations, CategoryRun, category_durations},
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct DurationStatisticsQuery {
+    /// Only measure this competition instead of all competitions.
+    competition_id: Option<Uuid>,
+}
+
+/// Measured durations per category, from the recorded timeplan progress.
+#[utoipa::path(
+    get,
+    tags=["query", "timeplan"],
+    path="/get_duration_statistics",
+    params(DurationStatisticsQuery),
+    responses(
+        (status=200, content_type="application/json", body=Vec<CategoryDurations>),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn get_duration_statistics(
+    Extension(db): Extension<ReloadableSqlite>,
+    auth: Auth,
+    Query(query): Query<DurationStatisticsQuery>,
+) -> Result<Json<Vec<CategoryDurations>>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let runs: Vec<_> = load_runs(&db)
+        .await?
+        .into_iter()
+        .filter(|(competition_id, _)| {
+            query
+                .competition_id
+                .is_none_or(|filter| filter == *competition_id)
+        })
+        .map(|(_, run)| run)
+        .collect();
+
+    Ok(Json(category_durations(&runs)))
+}
+
+/// Load the recorded progress of every category of every competition.
+pub(crate) async fn load_runs(db: &sqlx::SqlitePool) -> sqlx::Result<Vec<(Uuid, CategoryRun)>> {
+    let mut runs = HashMap::<(Uuid, String), CategoryRun>::new();
+
+    let entries = sqlx::query!(
+        r#"
+        SELECT
+            competition_id as "competition_id!: Uuid",
+            category as "category!",
+            MIN(started_at) as "started_at!: OffsetDateTime"
+        FROM timeplan
+        WHERE category IS NOT NULL AND started_at IS NOT NULL
+        GROUP BY competition_id, category
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+    for entry in entries {
+        runs.insert(
+            (entry.competition_id, entry.category.clone()),
+            CategoryRun {
+                category: entry.category,
+                entry_started_at: Some(entry.started_at),
+                acts: vec![],
+            },
+        );
+    }
+
+    let acts = sqlx::query!(
+        r#"
+        SELECT
+            competition_id as "competition_id!: Uuid",
+            category as "category!: String",
+            started_at as "started_at!: OffsetDateTime",
+            ended_at as "ended_at: OffsetDateTime"
+        FROM view_act
+        WHERE started_at IS NOT NULL AND category IS NOT NULL
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+    for act in acts {
+        runs.entry((act.competition_id, act.category.clone()))
+            .or_insert_with(|| CategoryRun {
+                category: act.category,
+                ..Default::default()
+            })
+            .acts
+            .push((act.started_at, act.ended_at));
+    }
+
+    Ok(runs
+        .into_iter()
+        .map(|((competition_id, _), run)| (competition_id, run))
+        .collect())
+}
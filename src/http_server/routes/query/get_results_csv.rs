@@ -0,0 +1,51 @@
+use axum::{
+    Extension,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use tracing::instrument;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+    results_csv::render_csv,
+    scoring::get_results,
+};
+
+/// Get the results of all categories as CSV.
+#[utoipa::path(
+    get,
+    tags=["query", "scoring"],
+    path="/get_results_csv",
+    responses(
+        (status=200, content_type="text/csv", body=String),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn get_results_csv(
+    Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<impl IntoResponse, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    let results = get_results(&db, competition.id).await?;
+
+    let csv = render_csv(&results).map_err(|e| HttpError::ErrorMessages(e.to_string()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"ergebnisse.csv\"",
+            ),
+        ],
+        csv,
+    ))
+}
@@ -0,0 +1,50 @@
+use axum::{
+    Extension,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use tracing::instrument;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+    results_pdf::render_results,
+    scoring::get_results,
+};
+
+/// Get the printable result lists with one page per category.
+#[utoipa::path(
+    get,
+    tags=["query", "scoring"],
+    path="/get_results_pdf",
+    responses(
+        (status=200, content_type="application/pdf", body=Vec<u8>),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn get_results_pdf(
+    Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<impl IntoResponse, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    let results = get_results(&db, competition.id).await?;
+    let pdf = render_results(&format!("Ergebnisliste {}", competition.name), &results);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"ergebnisse.pdf\"",
+            ),
+        ],
+        pdf,
+    ))
+}
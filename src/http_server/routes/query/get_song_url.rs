@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json, extract::Query};
+use time::OffsetDateTime;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    jwt::JWTConfig,
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct GetSongUrlQuery {
+    act_id: Uuid,
+    /// Prefer the normalized copy if there is one.
+    #[serde(default)]
+    normalized: bool,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct SongUrl {
+    url: String,
+    /// Whether the URL points to the normalized copy.
+    normalized: bool,
+    #[serde(with = "time::serde::iso8601")]
+    expires_at: OffsetDateTime,
+}
+
+/// Get a signed URL of the current song of an act.
+///
+/// The URL works without the login cookie until it expires, so it can be
+/// handed to players.
+#[utoipa::path(
+    get,
+    tags=["query", "act"],
+    path="/get_song_url",
+    params(GetSongUrlQuery),
+    responses(
+        (status=200, content_type="application/json", body=SongUrl),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db, jwt))]
+pub async fn get_song_url(
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(jwt): Extension<Arc<JWTConfig>>,
+    Query(query): Query<GetSongUrlQuery>,
+    access: ClubAccess,
+) -> Result<Json<SongUrl>, HttpError> {
+    if !access.auth.may_control_music() {
+        access.check_act(query.act_id, ClubRole::ReadOnly).await?;
+    }
+    let db = db.get().await.clone();
+
+    let act = sqlx::query!(
+        "SELECT song_file, song_normalized_file FROM acts WHERE id = ?",
+        query.act_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+    let (file, normalized) = match (act.song_normalized_file, act.song_file) {
+        (Some(file), _) if query.normalized => (file, true),
+        (_, Some(file)) => (file, false),
+        _ => return Err(HttpError::NotFound),
+    };
+
+    let (token, claims) = jwt
+        .create_song_token(&file)
+        .map_err(|_e| HttpError::InternalServerError)?;
+    let expires_at = OffsetDateTime::from_unix_timestamp(claims.exp() as i64)
+        .map_err(|_e| HttpError::InternalServerError)?;
+    Ok(Json(SongUrl {
+        url: format!("/songs/{file}?token={token}"),
+        normalized,
+        expires_at,
+    }))
+}
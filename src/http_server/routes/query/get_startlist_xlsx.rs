@@ -0,0 +1,49 @@
+use axum::{Extension, extract::Query, http::header, response::IntoResponse};
+use tracing::instrument;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, routes::http_types::StartlistExportQuery},
+    reloadable_sqlite::ReloadableSqlite,
+    startlist_export::{parse_columns, render_xlsx},
+    timeplan::predict_competition,
+};
+
+/// Get the startlist as Excel workbook with one sheet per day.
+#[utoipa::path(
+    get,
+    tags=["query", "timeplan"],
+    path="/get_startlist_xlsx",
+    params(StartlistExportQuery),
+    responses(
+        (status=200, content_type="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", body=Vec<u8>),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn get_startlist_xlsx(
+    Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
+    Query(query): Query<StartlistExportQuery>,
+) -> Result<impl IntoResponse, HttpError> {
+    let columns = parse_columns(query.columns.as_deref()).map_err(HttpError::ErrorMessages)?;
+    let db = db.get().await.clone();
+    let timeplan = predict_competition(&db, competition.id).await?;
+    let xlsx =
+        render_xlsx(&timeplan, &columns).map_err(|e| HttpError::ErrorMessages(e.to_string()))?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"startliste.xlsx\"",
+            ),
+        ],
+        xlsx,
+    ))
+}
@@ -0,0 +1,40 @@
+use axum::{Extension, Json, extract::Query};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    reloadable_sqlite::ReloadableSqlite,
+    song_versions::{self, SongVersion},
+};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct ListActSongVersionsQuery {
+    act_id: Uuid,
+}
+
+/// List all uploaded songs of an act, the newest first.
+#[utoipa::path(
+    get,
+    tags=["query", "act"],
+    path="/list_act_song_versions",
+    params(ListActSongVersionsQuery),
+    responses(
+        (status=200, content_type="application/json", body=Vec<SongVersion>),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn list_act_song_versions(
+    Extension(db): Extension<ReloadableSqlite>,
+    Query(query): Query<ListActSongVersionsQuery>,
+    access: ClubAccess,
+) -> Result<Json<Vec<SongVersion>>, HttpError> {
+    access.check_act(query.act_id, ClubRole::ReadOnly).await?;
+    let db = db.get().await.clone();
+
+    Ok(Json(song_versions::list(&db, query.act_id).await?))
+}
@@ -3,6 +3,7 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{
         ClientError, HttpError,
         routes::http_types::{Act, ActParticipant},
@@ -24,6 +25,7 @@ use crate::{
 #[instrument(skip(db))]
 pub async fn list_acts(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
 ) -> Result<Json<Vec<Act>>, HttpError> {
     pub struct DBAct {
         id: Uuid,
@@ -37,6 +39,12 @@ pub async fn list_acts(
         participants: sqlx::types::Json<Vec<ActParticipant>>,
         category: Option<String>,
         song_checked: bool,
+        song_duration_seconds: Option<f64>,
+        song_codec: Option<String>,
+        song_bitrate: Option<i64>,
+        song_too_long: bool,
+        song_loudness_lufs: Option<f64>,
+        song_normalized_file: Option<String>,
         act_order: Option<i64>,
         category_order: Option<i64>,
     }
@@ -54,6 +62,12 @@ pub async fn list_acts(
                 participants: db_act.participants.0,
                 category: db_act.category,
                 song_checked: db_act.song_checked,
+                song_duration_seconds: db_act.song_duration_seconds,
+                song_codec: db_act.song_codec,
+                song_bitrate: db_act.song_bitrate,
+                song_too_long: db_act.song_too_long,
+                song_loudness_lufs: db_act.song_loudness_lufs,
+                song_normalized_file: db_act.song_normalized_file,
                 act_order: db_act.act_order,
                 category_order: db_act.category_order,
             }
@@ -72,14 +86,24 @@ pub async fn list_acts(
             view_act.description,
             song_file_name,
             view_act.is_pair as "is_pair: bool",
-            max_age,
+            max_age as "max_age: f64",
             view_act.is_sonderpokal as "is_sonderpokal: bool",
             participants as "participants!: sqlx::types::Json<Vec<ActParticipant>>",
             category,
-            song_checked
-        FROM view_act JOIN categories ON view_act.category = categories.name
+            song_checked,
+            song_duration_seconds,
+            song_codec,
+            song_bitrate,
+            COALESCE(song_duration_seconds > categories.act_duration_seconds, FALSE) as "song_too_long!: bool",
+            song_loudness_lufs,
+            song_normalized_file
+        FROM view_act
+          JOIN categories ON view_act.category = categories.name
+            AND view_act.competition_id = categories.competition_id
+        WHERE view_act.competition_id = ?
         ORDER BY categories."order", view_act."order" ASC
-        "#
+        "#,
+        competition.id
     )
     .fetch_all(&db)
     .await?;
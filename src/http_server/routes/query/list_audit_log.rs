@@ -0,0 +1,120 @@
+use axum::{Extension, Json, extract::Query, http::StatusCode};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+const DEFAULT_LIMIT: i64 = 100;
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct ListAuditLogQuery {
+    user_id: Option<Uuid>,
+    command: Option<String>,
+    request_id: Option<String>,
+    /// Only entries at or after this time (ISO 8601).
+    #[serde(default, with = "time::serde::iso8601::option")]
+    #[param(value_type = Option<String>)]
+    from: Option<time::OffsetDateTime>,
+    /// Only entries before this time (ISO 8601).
+    #[serde(default, with = "time::serde::iso8601::option")]
+    #[param(value_type = Option<String>)]
+    to: Option<time::OffsetDateTime>,
+    /// Maximum number of entries, 100 by default.
+    limit: Option<i64>,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct AuditLogEntry {
+    id: i64,
+    #[serde(with = "time::serde::iso8601")]
+    created_at: time::OffsetDateTime,
+    user_id: Option<Uuid>,
+    user_name: Option<String>,
+    request_id: Option<String>,
+    command: String,
+    #[schema(value_type = Option<Object>)]
+    payload: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    before: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
+    after: Option<serde_json::Value>,
+}
+
+fn parse_json(value: Option<String>) -> Option<serde_json::Value> {
+    value.and_then(|value| serde_json::from_str(&value).ok())
+}
+
+/// List the audit log, newest entries first.
+#[utoipa::path(
+    get,
+    tags=["query", "audit"],
+    path="/list_audit_log",
+    params(ListAuditLogQuery),
+    responses(
+        (status=200, content_type="application/json", body=Vec<AuditLogEntry>),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn list_audit_log(
+    Extension(db): Extension<ReloadableSqlite>,
+    auth: Auth,
+    Query(query): Query<ListAuditLogQuery>,
+) -> Result<Json<Vec<AuditLogEntry>>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+
+    let entries = sqlx::query!(
+        r#"
+        SELECT
+            audit_log.id as "id!",
+            audit_log.created_at as "created_at!: time::OffsetDateTime",
+            audit_log.user_id as "user_id: Uuid",
+            users.name as "user_name?",
+            audit_log.request_id,
+            audit_log.command,
+            audit_log.payload,
+            audit_log."before",
+            audit_log."after"
+        FROM audit_log
+          LEFT JOIN users ON users.id = audit_log.user_id
+        WHERE ($1 IS NULL OR audit_log.user_id = $1)
+          AND ($2 IS NULL OR audit_log.command = $2)
+          AND ($3 IS NULL OR audit_log.request_id = $3)
+          AND ($4 IS NULL OR audit_log.created_at >= datetime($4))
+          AND ($5 IS NULL OR audit_log.created_at < datetime($5))
+        ORDER BY audit_log.id DESC
+        LIMIT $6
+        "#,
+        query.user_id,
+        query.command,
+        query.request_id,
+        query.from,
+        query.to,
+        limit,
+    )
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|entry| AuditLogEntry {
+        id: entry.id,
+        created_at: entry.created_at,
+        user_id: entry.user_id,
+        user_name: entry.user_name,
+        request_id: entry.request_id,
+        command: entry.command,
+        payload: parse_json(entry.payload),
+        before: parse_json(entry.before),
+        after: parse_json(entry.after),
+    })
+    .collect();
+
+    Ok(Json(entries))
+}
@@ -1,7 +1,10 @@
 use axum::{Extension, Json};
+use sqlx::SqliteExecutor;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -37,6 +40,7 @@ pub struct Category {
 #[instrument(skip(db))]
 pub async fn list_categories(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
     auth: Option<Auth>,
 ) -> Result<Json<Vec<Category>>, HttpError> {
     let db = db.get().await.clone();
@@ -44,10 +48,30 @@ pub async fn list_categories(
         Category,
         r#"
         SELECT name as "name!", description, from_birthday, to_birthday, is_pair, is_sonderpokal, is_single_male, "order", einfahrzeit_seconds, act_duration_seconds, judge_duration_seconds
-        FROM categories ORDER BY "order" ASC
-        "#
+        FROM categories WHERE competition_id = ? ORDER BY "order" ASC
+        "#,
+        competition.id
     )
     .fetch_all(&db)
     .await?;
     Ok(Json(club_categories))
 }
+
+/// Load one category, e.g. to record it in the audit log.
+pub(crate) async fn get_category(
+    executor: impl SqliteExecutor<'_>,
+    competition_id: Uuid,
+    name: &str,
+) -> sqlx::Result<Option<Category>> {
+    sqlx::query_as!(
+        Category,
+        r#"
+        SELECT name as "name!", description, from_birthday, to_birthday, is_pair, is_sonderpokal, is_single_male, "order", einfahrzeit_seconds, act_duration_seconds, judge_duration_seconds
+        FROM categories WHERE competition_id = ? AND name = ?
+        "#,
+        competition_id,
+        name
+    )
+    .fetch_optional(executor)
+    .await
+}
@@ -3,7 +3,8 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError},
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -52,7 +53,9 @@ pub struct ListClubActsQuery {
 pub async fn list_club_acts(
     Extension(db): Extension<ReloadableSqlite>,
     Query(query): Query<ListClubActsQuery>,
+    access: ClubAccess,
 ) -> Result<Json<Vec<ClubAct>>, HttpError> {
+    access.check_club(query.club_id, ClubRole::ReadOnly)?;
     let db = db.get().await.clone();
     let acts_with_club_participation = sqlx::query!(
         r#"
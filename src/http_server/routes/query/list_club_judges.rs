@@ -1,10 +1,14 @@
 use axum::{Extension, Json, extract::Query};
+use sqlx::SqlitePool;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    judge::{JudgeQualification, get_qualifications},
     reloadable_sqlite::ReloadableSqlite,
+    utils::get_competition_id_for_club_id,
 };
 
 #[derive(Debug, serde::Serialize, utoipa::ToSchema)]
@@ -16,66 +20,7 @@ pub struct ClubJudge {
     mail: String,
     #[serde(with = "time::serde::iso8601")]
     birthdate: time::OffsetDateTime,
-    n_ew_u15_p: bool,
-    n_ew_u15_p_hosp: bool,
-    n_ew_u15_t: bool,
-    n_ew_u15_t_hosp: bool,
-    n_ew_u15_a: bool,
-    n_ew_u15_a_hosp: bool,
-    n_ew_o15_p: bool,
-    n_ew_o15_p_hosp: bool,
-    n_ew_o15_t: bool,
-    n_ew_o15_t_hosp: bool,
-    n_ew_o15_a: bool,
-    n_ew_o15_a_hosp: bool,
-    n_em_u15_p: bool,
-    n_em_u15_p_hosp: bool,
-    n_em_u15_t: bool,
-    n_em_u15_t_hosp: bool,
-    n_em_u15_a: bool,
-    n_em_u15_a_hosp: bool,
-    n_em_o15_p: bool,
-    n_em_o15_p_hosp: bool,
-    n_em_o15_t: bool,
-    n_em_o15_t_hosp: bool,
-    n_em_o15_a: bool,
-    n_em_o15_a_hosp: bool,
-    n_p_u15_p: bool,
-    n_p_u15_p_hosp: bool,
-    n_p_u15_t: bool,
-    n_p_u15_t_hosp: bool,
-    n_p_u15_a: bool,
-    n_p_u15_a_hosp: bool,
-    n_p_o15_p: bool,
-    n_p_o15_p_hosp: bool,
-    n_p_o15_t: bool,
-    n_p_o15_t_hosp: bool,
-    n_p_o15_a: bool,
-    n_p_o15_a_hosp: bool,
-    s_e_u15_p: bool,
-    s_e_u15_p_hosp: bool,
-    s_e_u15_t: bool,
-    s_e_u15_t_hosp: bool,
-    s_e_u15_a: bool,
-    s_e_u15_a_hosp: bool,
-    s_e_o15_p: bool,
-    s_e_o15_p_hosp: bool,
-    s_e_o15_t: bool,
-    s_e_o15_t_hosp: bool,
-    s_e_o15_a: bool,
-    s_e_o15_a_hosp: bool,
-    s_p_u15_p: bool,
-    s_p_u15_p_hosp: bool,
-    s_p_u15_t: bool,
-    s_p_u15_t_hosp: bool,
-    s_p_u15_a: bool,
-    s_p_u15_a_hosp: bool,
-    s_p_o15_p: bool,
-    s_p_o15_p_hosp: bool,
-    s_p_o15_t: bool,
-    s_p_o15_t_hosp: bool,
-    s_p_o15_a: bool,
-    s_p_o15_a_hosp: bool,
+    qualifications: Vec<JudgeQualification>,
 }
 
 #[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
@@ -99,12 +44,16 @@ pub struct ListClubJudgesQuery {
 pub async fn list_club_judges(
     Extension(db): Extension<ReloadableSqlite>,
     Query(query): Query<ListClubJudgesQuery>,
-    auth: Option<Auth>,
+    access: ClubAccess,
 ) -> Result<Json<Vec<ClubJudge>>, HttpError> {
+    access.check_club(query.club_id, ClubRole::ReadOnly)?;
     let db = db.get().await.clone();
     let club_id = query.club_id;
-    let club_judges = sqlx::query_as!(
-        ClubJudge,
+    let competition_id = get_competition_id_for_club_id(&db, club_id)
+        .await
+        .map_err(HttpError::ErrorMessages)?;
+    let mut qualifications = get_qualifications(&db, competition_id, Some(club_id)).await?;
+    let club_judges = sqlx::query!(
         r#"
         SELECT
             id as "id!: Uuid",
@@ -112,72 +61,62 @@ pub async fn list_club_judges(
             firstname,
             lastname,
             mail,
-            birthdate,
-            n_ew_u15_p,
-            n_ew_u15_p_hosp,
-            n_ew_u15_t,
-            n_ew_u15_t_hosp,
-            n_ew_u15_a,
-            n_ew_u15_a_hosp,
-            n_ew_o15_p,
-            n_ew_o15_p_hosp,
-            n_ew_o15_t,
-            n_ew_o15_t_hosp,
-            n_ew_o15_a,
-            n_ew_o15_a_hosp,
-            n_em_u15_p,
-            n_em_u15_p_hosp,
-            n_em_u15_t,
-            n_em_u15_t_hosp,
-            n_em_u15_a,
-            n_em_u15_a_hosp,
-            n_em_o15_p,
-            n_em_o15_p_hosp,
-            n_em_o15_t,
-            n_em_o15_t_hosp,
-            n_em_o15_a,
-            n_em_o15_a_hosp,
-            n_p_u15_p,
-            n_p_u15_p_hosp,
-            n_p_u15_t,
-            n_p_u15_t_hosp,
-            n_p_u15_a,
-            n_p_u15_a_hosp,
-            n_p_o15_p,
-            n_p_o15_p_hosp,
-            n_p_o15_t,
-            n_p_o15_t_hosp,
-            n_p_o15_a,
-            n_p_o15_a_hosp,
-            s_e_u15_p,
-            s_e_u15_p_hosp,
-            s_e_u15_t,
-            s_e_u15_t_hosp,
-            s_e_u15_a,
-            s_e_u15_a_hosp,
-            s_e_o15_p,
-            s_e_o15_p_hosp,
-            s_e_o15_t,
-            s_e_o15_t_hosp,
-            s_e_o15_a,
-            s_e_o15_a_hosp,
-            s_p_u15_p,
-            s_p_u15_p_hosp,
-            s_p_u15_t,
-            s_p_u15_t_hosp,
-            s_p_u15_a,
-            s_p_u15_a_hosp,
-            s_p_o15_p,
-            s_p_o15_p_hosp,
-            s_p_o15_t,
-            s_p_o15_t_hosp,
-            s_p_o15_a,
-            s_p_o15_a_hosp
+            birthdate
         FROM judge WHERE club_id = ?
         "#,
         club_id
     )
     .fetch_all(&db)
-    .await?;
+    .await?
+    .into_iter()
+    .map(|judge| ClubJudge {
+        qualifications: qualifications.remove(&judge.id).unwrap_or_default(),
+        id: judge.id,
+        club_id: judge.club_id,
+        firstname: judge.firstname,
+        lastname: judge.lastname,
+        mail: judge.mail,
+        birthdate: judge.birthdate,
+    })
+    .collect();
     Ok(Json(club_judges))
 }
+
+/// Load one judge with the qualifications, e.g. to record it in the audit
+/// log.
+pub(crate) async fn get_club_judge(
+    db: &SqlitePool,
+    judge_id: Uuid,
+) -> sqlx::Result<Option<ClubJudge>> {
+    let Some(judge) = sqlx::query!(
+        r#"
+        SELECT
+            judge.id as "id!: Uuid",
+            judge.club_id as "club_id!: Uuid",
+            clubs.competition_id as "competition_id!: Uuid",
+            judge.firstname,
+            judge.lastname,
+            judge.mail,
+            judge.birthdate
+        FROM judge JOIN clubs ON clubs.id = judge.club_id
+        WHERE judge.id = ?
+        "#,
+        judge_id
+    )
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let mut qualifications =
+        get_qualifications(db, judge.competition_id, Some(judge.club_id)).await?;
+    Ok(Some(ClubJudge {
+        qualifications: qualifications.remove(&judge.id).unwrap_or_default(),
+        id: judge.id,
+        club_id: judge.club_id,
+        firstname: judge.firstname,
+        lastname: judge.lastname,
+        mail: judge.mail,
+        birthdate: judge.birthdate,
+    }))
+}
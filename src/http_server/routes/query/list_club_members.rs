@@ -0,0 +1,98 @@
+use axum::{Extension, Json, extract::Query};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct ListClubMembersQuery {
+    club_id: Uuid,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct ClubMember {
+    user_id: Uuid,
+    name: String,
+    email: String,
+    role: ClubRole,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct PendingInvitation {
+    email: String,
+    role: ClubRole,
+    invited_by: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    created_at: time::OffsetDateTime,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct ClubMembers {
+    members: Vec<ClubMember>,
+    /// Only shown to owners.
+    invitations: Vec<PendingInvitation>,
+}
+
+/// List the members of a club and its pending invitations.
+#[utoipa::path(
+    get,
+    tags=["query", "club"],
+    path="/list_club_members",
+    params(ListClubMembersQuery),
+    responses(
+        (status=200, content_type="application/json", body=ClubMembers),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn list_club_members(
+    Extension(db): Extension<ReloadableSqlite>,
+    Query(query): Query<ListClubMembersQuery>,
+    access: ClubAccess,
+) -> Result<Json<ClubMembers>, HttpError> {
+    access.check_club(query.club_id, ClubRole::ReadOnly)?;
+    let db = db.get().await.clone();
+
+    let members = sqlx::query_as!(
+        ClubMember,
+        r#"
+        SELECT user_id as "user_id!: Uuid", users.name, users.email, role as "role!: ClubRole"
+        FROM club_memberships JOIN users ON club_memberships.user_id = users.id
+        WHERE club_memberships.club_id = ?
+        ORDER BY users.name
+        "#,
+        query.club_id
+    )
+    .fetch_all(&db)
+    .await?;
+    let invitations = if access.check_club(query.club_id, ClubRole::Owner).is_ok() {
+        sqlx::query_as!(
+            PendingInvitation,
+            r#"
+            SELECT
+                club_invitations.email,
+                role as "role!: ClubRole",
+                users.name as "invited_by?",
+                club_invitations.created_at as "created_at!: time::OffsetDateTime"
+            FROM club_invitations LEFT JOIN users ON club_invitations.invited_by = users.id
+            WHERE club_invitations.club_id = ?
+            ORDER BY club_invitations.created_at
+            "#,
+            query.club_id
+        )
+        .fetch_all(&db)
+        .await?
+    } else {
+        vec![]
+    };
+
+    Ok(Json(ClubMembers {
+        members,
+        invitations,
+    }))
+}
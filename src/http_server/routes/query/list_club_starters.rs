@@ -1,9 +1,11 @@
 use axum::{Extension, Json, extract::Query};
+use sqlx::SqliteExecutor;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    club_membership::ClubRole,
+    http_server::{ClientError, HttpError, extractor::club_access::ClubAccess},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -47,8 +49,9 @@ pub struct ListClubStartersQuery {
 pub async fn list_club_starters(
     Extension(db): Extension<ReloadableSqlite>,
     Query(query): Query<ListClubStartersQuery>,
-    auth: Option<Auth>,
+    access: ClubAccess,
 ) -> Result<Json<Vec<ClubStarter>>, HttpError> {
+    access.check_club(query.club_id, ClubRole::ReadOnly)?;
     let db = db.get().await.clone();
     let club_id = query.club_id;
     let club_starters = sqlx::query_as!(
@@ -78,3 +81,35 @@ pub async fn list_club_starters(
     .await?;
     Ok(Json(club_starters))
 }
+
+/// Load one starter, e.g. to record it in the audit log.
+pub(crate) async fn get_club_starter(
+    executor: impl SqliteExecutor<'_>,
+    starter_id: Uuid,
+) -> sqlx::Result<Option<ClubStarter>> {
+    sqlx::query_as!(
+        ClubStarter,
+        r#"
+        SELECT
+            starter.id as "id!: Uuid",
+            starter.club_id as "club_id!: Uuid",
+            starter.firstname,
+            starter.lastname,
+            starter.birthdate,
+            starter.single_sonderpokal,
+            starter.single_male,
+            starter.single_female,
+            starter.pair_sonderpokal,
+            starter.pair,
+            starter.partner_id as "partner_id: Uuid",
+            starter.partner_name,
+            NULLIF(concat_ws(" ", partner.firstname, partner.lastname), '') as "resolved_partner_name: String",
+            partner_club.name as resolved_partner_club
+        FROM starter LEFT JOIN starter as partner ON partner.id = starter.partner_id LEFT JOIN clubs as partner_club ON partner_club.id = partner.club_id
+        WHERE starter.id = ?
+        "#,
+        starter_id
+    )
+    .fetch_optional(executor)
+    .await
+}
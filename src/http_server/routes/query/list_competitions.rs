@@ -0,0 +1,31 @@
+use axum::{Extension, Json, http::StatusCode};
+use tracing::instrument;
+
+use crate::{
+    competition::Competition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+/// List all competitions, newest first.
+#[utoipa::path(
+    get,
+    tags=["query", "competition"],
+    path="/list_competitions",
+    responses(
+        (status=200, content_type="application/json", body=Vec<Competition>),
+        (status=403, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn list_competitions(
+    Extension(db): Extension<ReloadableSqlite>,
+    auth: Auth,
+) -> Result<Json<Vec<Competition>>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    Ok(Json(Competition::list(&db).await?))
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use axum::{Extension, Json, http::StatusCode};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge::JudgeRole,
+    judge_panel::{PanelRequirements, get_assignments, get_slots},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct JudgePanelMember {
+    judge_id: Uuid,
+    firstname: String,
+    lastname: String,
+    club_name: String,
+    role: JudgeRole,
+    hospitation: bool,
+    is_manual: bool,
+    /// The club of the judge has starters in the category.
+    own_club: bool,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct MissingJudges {
+    role: JudgeRole,
+    count: i64,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct JudgePanel {
+    timeplan_id: i64,
+    category: String,
+    #[serde(with = "time::serde::iso8601")]
+    predicted_start: time::OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    predicted_end: time::OffsetDateTime,
+    requirements: PanelRequirements,
+    judges: Vec<JudgePanelMember>,
+    /// Roles that have fewer judges than required.
+    missing: Vec<MissingJudges>,
+}
+
+/// List the judge panels of all categories in the timeplan.
+#[utoipa::path(
+    get,
+    tags=["query", "judge"],
+    path="/list_judge_panels",
+    responses(
+        (status=200, content_type="application/json", body=Vec<JudgePanel>),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn list_judge_panels(
+    Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<Json<Vec<JudgePanel>>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+
+    let judges: HashMap<Uuid, _> = sqlx::query!(
+        r#"
+        SELECT
+            judge.id as "id!: Uuid",
+            judge.club_id as "club_id!: Uuid",
+            firstname,
+            lastname,
+            clubs.name as "club_name"
+        FROM judge JOIN clubs ON clubs.id = judge.club_id
+        WHERE clubs.competition_id = ?
+        "#,
+        competition.id
+    )
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|judge| (judge.id, judge))
+    .collect();
+    let assignments = get_assignments(&db, competition.id).await?;
+
+    let panels = get_slots(&db, competition.id)
+        .await?
+        .into_iter()
+        .map(|slot| {
+            let members: Vec<JudgePanelMember> = assignments
+                .iter()
+                .filter(|assignment| assignment.timeplan_id == slot.timeplan_id)
+                .filter_map(|assignment| {
+                    let judge = judges.get(&assignment.judge_id)?;
+                    Some(JudgePanelMember {
+                        judge_id: judge.id,
+                        firstname: judge.firstname.clone(),
+                        lastname: judge.lastname.clone(),
+                        club_name: judge.club_name.clone(),
+                        role: assignment.role,
+                        hospitation: assignment.hospitation,
+                        is_manual: assignment.is_manual,
+                        own_club: slot.starter_clubs.contains(&judge.club_id),
+                    })
+                })
+                .collect();
+            let missing = [
+                JudgeRole::Performance,
+                JudgeRole::Technique,
+                JudgeRole::Dismount,
+            ]
+            .into_iter()
+            .map(|role| MissingJudges {
+                role,
+                count: slot.requirements.judges(role)
+                    - members
+                        .iter()
+                        .filter(|member| member.role == role && !member.hospitation)
+                        .count() as i64,
+            })
+            .filter(|missing| missing.count > 0)
+            .collect();
+            JudgePanel {
+                timeplan_id: slot.timeplan_id,
+                category: slot.category,
+                predicted_start: slot.start,
+                predicted_end: slot.end,
+                requirements: slot.requirements,
+                judges: members,
+                missing,
+            }
+        })
+        .collect();
+
+    Ok(Json(panels))
+}
@@ -3,7 +3,9 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
+    judge::{JudgeQualification, get_qualifications},
     reloadable_sqlite::ReloadableSqlite,
 };
 
@@ -17,67 +19,7 @@ pub struct Judge {
     mail: String,
     #[serde(with = "time::serde::iso8601")]
     birthdate: time::OffsetDateTime,
-
-    n_ew_u15_p: bool,
-    n_ew_u15_p_hosp: bool,
-    n_ew_u15_t: bool,
-    n_ew_u15_t_hosp: bool,
-    n_ew_u15_a: bool,
-    n_ew_u15_a_hosp: bool,
-    n_ew_o15_p: bool,
-    n_ew_o15_p_hosp: bool,
-    n_ew_o15_t: bool,
-    n_ew_o15_t_hosp: bool,
-    n_ew_o15_a: bool,
-    n_ew_o15_a_hosp: bool,
-    n_em_u15_p: bool,
-    n_em_u15_p_hosp: bool,
-    n_em_u15_t: bool,
-    n_em_u15_t_hosp: bool,
-    n_em_u15_a: bool,
-    n_em_u15_a_hosp: bool,
-    n_em_o15_p: bool,
-    n_em_o15_p_hosp: bool,
-    n_em_o15_t: bool,
-    n_em_o15_t_hosp: bool,
-    n_em_o15_a: bool,
-    n_em_o15_a_hosp: bool,
-    n_p_u15_p: bool,
-    n_p_u15_p_hosp: bool,
-    n_p_u15_t: bool,
-    n_p_u15_t_hosp: bool,
-    n_p_u15_a: bool,
-    n_p_u15_a_hosp: bool,
-    n_p_o15_p: bool,
-    n_p_o15_p_hosp: bool,
-    n_p_o15_t: bool,
-    n_p_o15_t_hosp: bool,
-    n_p_o15_a: bool,
-    n_p_o15_a_hosp: bool,
-    s_e_u15_p: bool,
-    s_e_u15_p_hosp: bool,
-    s_e_u15_t: bool,
-    s_e_u15_t_hosp: bool,
-    s_e_u15_a: bool,
-    s_e_u15_a_hosp: bool,
-    s_e_o15_p: bool,
-    s_e_o15_p_hosp: bool,
-    s_e_o15_t: bool,
-    s_e_o15_t_hosp: bool,
-    s_e_o15_a: bool,
-    s_e_o15_a_hosp: bool,
-    s_p_u15_p: bool,
-    s_p_u15_p_hosp: bool,
-    s_p_u15_t: bool,
-    s_p_u15_t_hosp: bool,
-    s_p_u15_a: bool,
-    s_p_u15_a_hosp: bool,
-    s_p_o15_p: bool,
-    s_p_o15_p_hosp: bool,
-    s_p_o15_t: bool,
-    s_p_o15_t_hosp: bool,
-    s_p_o15_a: bool,
-    s_p_o15_a_hosp: bool,
+    qualifications: Vec<JudgeQualification>,
 }
 
 /// Get information about a club.
@@ -94,6 +36,7 @@ pub struct Judge {
 #[instrument(skip(db))]
 pub async fn list_judges(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
     auth: Option<Auth>,
 ) -> Result<Json<Vec<Judge>>, HttpError> {
     auth.ok_or(HttpError::InvalidCredentials).map(|auth| {
@@ -104,8 +47,8 @@ pub async fn list_judges(
         }
     })??;
     let db = db.get().await.clone();
-    let club_judges = sqlx::query_as!(
-        Judge,
+    let mut qualifications = get_qualifications(&db, competition.id, None).await?;
+    let club_judges = sqlx::query!(
         r#"
         SELECT
             judge.id as "id!: Uuid",
@@ -114,71 +57,25 @@ pub async fn list_judges(
             firstname,
             lastname,
             mail,
-            birthdate,
-            n_ew_u15_p,
-            n_ew_u15_p_hosp,
-            n_ew_u15_t,
-            n_ew_u15_t_hosp,
-            n_ew_u15_a,
-            n_ew_u15_a_hosp,
-            n_ew_o15_p,
-            n_ew_o15_p_hosp,
-            n_ew_o15_t,
-            n_ew_o15_t_hosp,
-            n_ew_o15_a,
-            n_ew_o15_a_hosp,
-            n_em_u15_p,
-            n_em_u15_p_hosp,
-            n_em_u15_t,
-            n_em_u15_t_hosp,
-            n_em_u15_a,
-            n_em_u15_a_hosp,
-            n_em_o15_p,
-            n_em_o15_p_hosp,
-            n_em_o15_t,
-            n_em_o15_t_hosp,
-            n_em_o15_a,
-            n_em_o15_a_hosp,
-            n_p_u15_p,
-            n_p_u15_p_hosp,
-            n_p_u15_t,
-            n_p_u15_t_hosp,
-            n_p_u15_a,
-            n_p_u15_a_hosp,
-            n_p_o15_p,
-            n_p_o15_p_hosp,
-            n_p_o15_t,
-            n_p_o15_t_hosp,
-            n_p_o15_a,
-            n_p_o15_a_hosp,
-            s_e_u15_p,
-            s_e_u15_p_hosp,
-            s_e_u15_t,
-            s_e_u15_t_hosp,
-            s_e_u15_a,
-            s_e_u15_a_hosp,
-            s_e_o15_p,
-            s_e_o15_p_hosp,
-            s_e_o15_t,
-            s_e_o15_t_hosp,
-            s_e_o15_a,
-            s_e_o15_a_hosp,
-            s_p_u15_p,
-            s_p_u15_p_hosp,
-            s_p_u15_t,
-            s_p_u15_t_hosp,
-            s_p_u15_a,
-            s_p_u15_a_hosp,
-            s_p_o15_p,
-            s_p_o15_p_hosp,
-            s_p_o15_t,
-            s_p_o15_t_hosp,
-            s_p_o15_a,
-            s_p_o15_a_hosp
+            birthdate
         FROM judge JOIN clubs as club ON club.id = judge.club_id
-        "#
+        WHERE club.competition_id = ?
+        "#,
+        competition.id
     )
     .fetch_all(&db)
-    .await?;
+    .await?
+    .into_iter()
+    .map(|judge| Judge {
+        qualifications: qualifications.remove(&judge.id).unwrap_or_default(),
+        id: judge.id,
+        club_id: judge.club_id,
+        club_name: judge.club_name,
+        firstname: judge.firstname,
+        lastname: judge.lastname,
+        mail: judge.mail,
+        birthdate: judge.birthdate,
+    })
+    .collect();
     Ok(Json(club_judges))
 }
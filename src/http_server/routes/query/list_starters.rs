@@ -3,6 +3,7 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -42,6 +43,7 @@ pub struct Starter {
 #[instrument(skip(db))]
 pub async fn list_starters(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
     auth: Option<Auth>,
 ) -> Result<Json<Vec<Starter>>, HttpError> {
     auth.ok_or(HttpError::InvalidCredentials).map(|auth| {
@@ -71,12 +73,14 @@ pub async fn list_starters(
             starter.partner_name,
             NULLIF(concat_ws(" ", partner.firstname, partner.lastname), '') as "resolved_partner_name: String",
             partner_club.name as resolved_partner_club,
-            starter.age_on_competition
-        FROM starter
+            starter.age_on_competition as "age_on_competition!: f64"
+        FROM view_starter as starter
           LEFT JOIN clubs as club ON club.id = starter.club_id
           LEFT JOIN starter as partner ON partner.id = starter.partner_id
           LEFT JOIN clubs as partner_club ON partner_club.id = partner.club_id
-        "#
+        WHERE starter.competition_id = ?
+        "#,
+        competition.id
     )
     .fetch_all(&db)
     .await?;
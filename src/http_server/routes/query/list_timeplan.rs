@@ -1,7 +1,10 @@
 use axum::{Extension, Json};
+use sqlx::SqliteExecutor;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError, extractor::auth::Auth},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -34,6 +37,7 @@ pub struct TimeplanListEntry {
 #[instrument(skip(db))]
 pub async fn list_timeplan(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
     auth: Option<Auth>,
 ) -> Result<Json<Vec<TimeplanListEntry>>, HttpError> {
     let db = db.get().await.clone();
@@ -41,11 +45,31 @@ pub async fn list_timeplan(
         TimeplanListEntry,
         r#"
         SELECT id, earliest_start_time, duration_seconds, label, category, started_at, ended_at
-        FROM timeplan ORDER BY id ASC
-        "#
+        FROM timeplan WHERE competition_id = ? ORDER BY id ASC
+        "#,
+        competition.id
     )
     .fetch_all(&db)
     .await?;
 
     Ok(Json(timeplan_entries))
 }
+
+/// Load one timeplan entry, e.g. to record it in the audit log.
+pub(crate) async fn get_timeplan_entry(
+    executor: impl SqliteExecutor<'_>,
+    competition_id: Uuid,
+    id: i64,
+) -> sqlx::Result<Option<TimeplanListEntry>> {
+    sqlx::query_as!(
+        TimeplanListEntry,
+        r#"
+        SELECT id, earliest_start_time, duration_seconds, label, category, started_at, ended_at
+        FROM timeplan WHERE competition_id = ? AND id = ?
+        "#,
+        competition_id,
+        id
+    )
+    .fetch_optional(executor)
+    .await
+}
@@ -1,8 +1,11 @@
+use std::collections::HashMap;
+
 use axum::{Extension, Json};
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    club_membership::{ClubMembership, ClubRole},
     http_server::{ClientError, HttpError, extractor::auth::Auth, routes::http_types::User},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -27,13 +30,42 @@ pub async fn list_users(
         return Err(HttpError::InvalidCredentials);
     }
     let db = db.get().await.clone();
-    let users = sqlx::query_as!(
-        User,
+    let mut memberships: HashMap<Uuid, Vec<ClubMembership>> = HashMap::new();
+    for row in sqlx::query!(
+        r#"
+        SELECT user_id as "user_id!: Uuid", club_id as "club_id!: Uuid", role as "role!: ClubRole"
+        FROM club_memberships ORDER BY created_at
+        "#
+    )
+    .fetch_all(&db)
+    .await?
+    {
+        memberships
+            .entry(row.user_id)
+            .or_default()
+            .push(ClubMembership {
+                club_id: row.club_id,
+                role: row.role,
+            });
+    }
+    let users = sqlx::query!(
         r#"
-        SELECT id as "id!: Uuid", club_id as "club_id: Uuid", name, email, email_verified, is_admin FROM users
+        SELECT id as "id!: Uuid", club_id as "club_id: Uuid", name, email, email_verified, is_admin, is_music_operator FROM users
         "#
     )
     .fetch_all(&db)
-    .await?;
+    .await?
+    .into_iter()
+    .map(|user| User {
+        memberships: memberships.remove(&user.id).unwrap_or_default(),
+        id: user.id,
+        name: user.name,
+        email: user.email,
+        email_verified: user.email_verified,
+        is_admin: user.is_admin,
+        is_music_operator: user.is_music_operator,
+        club_id: user.club_id,
+    })
+    .collect();
     Ok(Json(users))
 }
@@ -0,0 +1,36 @@
+use axum::{Extension, Json, http::StatusCode};
+use tracing::instrument;
+
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{ClientError, HttpError, extractor::auth::Auth},
+    reloadable_sqlite::ReloadableSqlite,
+    scoring::{ResultAct, get_results},
+};
+
+/// List the results of all acts grouped by category.
+///
+/// Acts are ordered by category and rank, acts without a complete score come
+/// last in their category.
+#[utoipa::path(
+    get,
+    tags=["query", "scoring"],
+    path="/results",
+    responses(
+        (status=200, content_type="application/json", body=Vec<ResultAct>),
+        (status=404, content_type="application/json", body=ClientError),
+        (status=500, content_type="application/json", body=ClientError),
+    ),
+)]
+#[instrument(skip(db))]
+pub async fn results(
+    Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
+    auth: Auth,
+) -> Result<Json<Vec<ResultAct>>, HttpError> {
+    if !auth.is_admin() {
+        return Err(HttpError::StatusCode(StatusCode::FORBIDDEN));
+    }
+    let db = db.get().await.clone();
+    Ok(Json(get_results(&db, competition.id).await?))
+}
@@ -3,6 +3,7 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
+    competition::ActiveCompetition,
     http_server::{ClientError, HttpError},
     reloadable_sqlite::ReloadableSqlite,
 };
@@ -42,6 +43,7 @@ pub struct StartlistAct {
 #[instrument(skip(db))]
 pub async fn startlist(
     Extension(db): Extension<ReloadableSqlite>,
+    competition: ActiveCompetition,
 ) -> Result<Json<Vec<StartlistAct>>, HttpError> {
     pub struct DBStartlistAct {
         id: Uuid,
@@ -79,13 +81,17 @@ pub async fn startlist(
             categories."order" as "category_order",
             view_act.name,
             view_act.is_pair as "is_pair: bool",
-            max_age,
+            max_age as "max_age: f64",
             view_act.is_sonderpokal as "is_sonderpokal: bool",
             participants as "participants!: sqlx::types::Json<Vec<StartlistActParticipant>>",
             category
-        FROM view_act JOIN categories ON view_act.category = categories.name
+        FROM view_act
+          JOIN categories ON view_act.category = categories.name
+            AND view_act.competition_id = categories.competition_id
+        WHERE view_act.competition_id = ?
         ORDER BY categories."order", view_act."order" ASC
-        "#
+        "#,
+        competition.id
     )
     .fetch_all(&db)
     .await?;
@@ -3,33 +3,57 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    http_server::{HttpError, extractor::auth::Auth, routes::http_types::User},
+    http_server::{HttpError, extractor::auth::PartialAuth, routes::http_types::User},
     reloadable_sqlite::ReloadableSqlite,
+    totp::SecondFactor,
 };
 
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct WhoAmI {
+    #[serde(flatten)]
+    user: User,
+    /// Everything but `whoami` and the second factor commands is rejected
+    /// until this is `not_required` or `verified`.
+    second_factor: SecondFactor,
+}
+
 /// Get information about the currently authenticated user.
+///
+/// Also answers sessions that still need the second factor, so the frontend
+/// can ask for the code or the enrollment.
 #[utoipa::path(
     get,
     tags=["query", "auth"],
     path="/whoami",
     responses(
-        (status=200, content_type="application/json", body=User),
+        (status=200, content_type="application/json", body=WhoAmI),
     ),
 )]
 #[instrument(skip(db))]
 pub async fn whoami(
     Extension(db): Extension<ReloadableSqlite>,
-    auth: Auth,
-) -> Result<Json<User>, HttpError> {
+    PartialAuth(auth): PartialAuth,
+) -> Result<Json<WhoAmI>, HttpError> {
     let db = db.get().await.clone();
-    let user = sqlx::query_as!(
-        User,
+    let user = sqlx::query!(
         r#"
-        SELECT id as "id!: Uuid", name, email, email_verified, is_admin, club_id as "club_id: Uuid" FROM users WHERE id = ?
+        SELECT id as "id!: Uuid", name, email, email_verified, is_admin, is_music_operator, club_id as "club_id: Uuid" FROM users WHERE id = ?
         "#,
         auth.user_id
     )
     .fetch_one(&db)
     .await?;
-    Ok(Json(user))
+    Ok(Json(WhoAmI {
+        user: User {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            email_verified: user.email_verified,
+            is_admin: user.is_admin,
+            is_music_operator: user.is_music_operator,
+            club_id: user.club_id,
+            memberships: auth.memberships,
+        },
+        second_factor: auth.second_factor,
+    }))
 }
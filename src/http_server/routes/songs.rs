@@ -0,0 +1,176 @@
+use std::{ops::Range, sync::Arc};
+
+use axum::{
+    Extension,
+    body::Body,
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use sqlx::SqlitePool;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    http_server::{HttpError, extractor::auth::Auth},
+    jwt::JWTConfig,
+    reloadable_sqlite::ReloadableSqlite,
+    song_storage::{SongStorage, is_valid_name},
+};
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SongQuery {
+    /// Signed by `get_song_url`.
+    token: Option<String>,
+}
+
+/// Whether a club of the user has an act that uses the file, as its
+/// current song, an older version or the normalized copy.
+async fn member_uses_song(db: &SqlitePool, user_id: Uuid, name: &str) -> Result<bool, HttpError> {
+    Ok(sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM acts
+              JOIN act_participants ON act_participants.act_id = acts.id
+              JOIN starter ON act_participants.starter_id = starter.id
+              JOIN club_memberships ON club_memberships.club_id = starter.club_id
+            WHERE club_memberships.user_id = $1
+              AND (acts.song_file = $2
+                OR acts.song_normalized_file = $2
+                OR acts.id IN (SELECT act_id FROM act_song_versions WHERE file = $2))
+        ) as "uses!: bool"
+        "#,
+        user_id,
+        name,
+    )
+    .fetch_one(db)
+    .await?)
+}
+
+/// Songs of minors are only sent to admins, music operators, the clubs of
+/// the act and to players with a signed URL.
+async fn authorize(
+    db: &SqlitePool,
+    jwt: &JWTConfig,
+    auth: Option<Auth>,
+    token: Option<&str>,
+    name: &str,
+) -> Result<(), HttpError> {
+    if let Some(token) = token {
+        return match jwt.decode_song_token(token) {
+            Some(claims) if claims.song() == name => Ok(()),
+            _ => Err(HttpError::StatusCode(StatusCode::FORBIDDEN)),
+        };
+    }
+    let auth = auth.ok_or(HttpError::InvalidCredentials)?;
+    if auth.may_control_music() {
+        return Ok(());
+    }
+    if member_uses_song(db, auth.user_id, name).await? {
+        Ok(())
+    } else {
+        Err(HttpError::StatusCode(StatusCode::FORBIDDEN))
+    }
+}
+
+/// The part of the file a `Range` header asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum RequestedRange {
+    Full,
+    Part(Range<u64>),
+    Unsatisfiable,
+}
+
+/// Parse a single byte range, e.g. `bytes=0-1023`, `bytes=1024-` or
+/// `bytes=-512`.
+///
+/// Headers that can't be parsed and multiple ranges are ignored and the
+/// whole file is sent, like the RFC allows.
+fn requested_range(header: Option<&HeaderValue>, size: u64) -> RequestedRange {
+    let Some(spec) = header
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("bytes="))
+        .filter(|spec| !spec.contains(','))
+    else {
+        return RequestedRange::Full;
+    };
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RequestedRange::Full;
+    };
+    let range = match (start.parse::<u64>(), end.parse::<u64>()) {
+        (Err(_), Ok(suffix)) if start.is_empty() => size.saturating_sub(suffix)..size,
+        (Ok(start), Err(_)) if end.is_empty() => start..size,
+        (Ok(start), Ok(end)) if start <= end => start..(end + 1).min(size),
+        _ => return RequestedRange::Full,
+    };
+    if range.start >= size || range.is_empty() {
+        RequestedRange::Unsatisfiable
+    } else {
+        RequestedRange::Part(range)
+    }
+}
+
+/// Stream a song or a normalized copy from the song storage.
+///
+/// Players seek with range requests, so they are answered with the
+/// requested part only.
+#[instrument(skip(storage, db, jwt, auth, query, headers))]
+pub async fn get_song(
+    Extension(storage): Extension<Arc<dyn SongStorage>>,
+    Extension(db): Extension<ReloadableSqlite>,
+    Extension(jwt): Extension<Arc<JWTConfig>>,
+    auth: Option<Auth>,
+    Path(name): Path<String>,
+    Query(query): Query<SongQuery>,
+    headers: HeaderMap,
+) -> Result<Response, HttpError> {
+    if !is_valid_name(&name) {
+        return Err(HttpError::NotFound);
+    }
+    let db = db.get().await.clone();
+    authorize(&db, &jwt, auth, query.token.as_deref(), &name).await?;
+    let size = storage.head(&name).await?.ok_or(HttpError::NotFound)?.size;
+    let content_type = mime_guess::from_path(&name)
+        .first_or_octet_stream()
+        .to_string();
+
+    let (status, range) = match requested_range(headers.get(header::RANGE), size) {
+        RequestedRange::Full => (StatusCode::OK, 0..size),
+        RequestedRange::Part(range) => (StatusCode::PARTIAL_CONTENT, range),
+        RequestedRange::Unsatisfiable => {
+            return Ok((
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(header::CONTENT_RANGE, format!("bytes */{size}"))],
+            )
+                .into_response());
+        }
+    };
+    let body = if range.is_empty() {
+        Body::empty()
+    } else {
+        Body::from_stream(storage.stream(&name, range.clone()).await?)
+    };
+
+    let mut response = (
+        status,
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (
+                header::CONTENT_LENGTH,
+                (range.end - range.start).to_string(),
+            ),
+        ],
+        body,
+    )
+        .into_response();
+    if status == StatusCode::PARTIAL_CONTENT {
+        response.headers_mut().insert(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{size}", range.start, range.end - 1)
+                .parse()
+                .map_err(|_e| HttpError::InternalServerError)?,
+        );
+    }
+    Ok(response)
+}
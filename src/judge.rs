@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::http_server::HttpError;
+
+/// The role a judge takes in the panel of a category.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT")]
+pub enum JudgeRole {
+    #[sqlx(rename = "P")]
+    Performance,
+    #[sqlx(rename = "T")]
+    Technique,
+    /// Counts the dismounts.
+    #[sqlx(rename = "A")]
+    Dismount,
+}
+
+/// A judge can judge a role in a category, or hospitate to learn it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct JudgeQualification {
+    pub category: String,
+    pub role: JudgeRole,
+    #[serde(default)]
+    pub hospitation: bool,
+}
+
+/// Get the qualifications of the judges of a competition, grouped by judge.
+///
+/// If a club is given, only its judges are returned.
+pub async fn get_qualifications(
+    db: &SqlitePool,
+    competition_id: Uuid,
+    club_id: Option<Uuid>,
+) -> sqlx::Result<HashMap<Uuid, Vec<JudgeQualification>>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            judge_id as "judge_id!: Uuid",
+            category,
+            role as "role: JudgeRole",
+            hospitation
+        FROM judge_qualifications
+          JOIN judge ON judge.id = judge_qualifications.judge_id
+          JOIN categories ON categories.competition_id = judge_qualifications.competition_id
+            AND categories.name = judge_qualifications.category
+        WHERE judge_qualifications.competition_id = $1
+          AND ($2 IS NULL OR judge.club_id = $2)
+        ORDER BY categories."order", role
+        "#,
+        competition_id,
+        club_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut qualifications: HashMap<Uuid, Vec<JudgeQualification>> = HashMap::new();
+    for row in rows {
+        qualifications
+            .entry(row.judge_id)
+            .or_default()
+            .push(JudgeQualification {
+                category: row.category,
+                role: row.role,
+                hospitation: row.hospitation,
+            });
+    }
+    Ok(qualifications)
+}
+
+/// Replace the qualifications of a judge.
+///
+/// All categories have to exist in the competition of the judge's club.
+pub async fn set_qualifications(
+    tx: &mut SqliteConnection,
+    judge_id: Uuid,
+    qualifications: &[JudgeQualification],
+) -> Result<(), HttpError> {
+    let competition_id = sqlx::query_scalar!(
+        r#"
+        SELECT clubs.competition_id as "competition_id!: Uuid"
+        FROM judge JOIN clubs ON clubs.id = judge.club_id
+        WHERE judge.id = ?
+        "#,
+        judge_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(HttpError::NotFound)?;
+
+    let categories: HashSet<String> = sqlx::query_scalar!(
+        "SELECT name FROM categories WHERE competition_id = ?",
+        competition_id
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut seen = HashSet::new();
+    for qualification in qualifications {
+        if !categories.contains(&qualification.category) {
+            return Err(HttpError::ErrorMessages(format!(
+                "Die Kategorie {} gibt es in diesem Wettbewerb nicht.",
+                qualification.category
+            )));
+        }
+        if !seen.insert((&qualification.category, qualification.role)) {
+            return Err(HttpError::ErrorMessages(format!(
+                "Die Qualifikation für {} ist doppelt angegeben.",
+                qualification.category
+            )));
+        }
+    }
+
+    sqlx::query!(
+        "DELETE FROM judge_qualifications WHERE judge_id = ?",
+        judge_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for qualification in qualifications {
+        sqlx::query!(
+            r#"
+            INSERT INTO judge_qualifications (
+                judge_id,
+                competition_id,
+                category,
+                role,
+                hospitation
+            ) VALUES (?, ?, ?, ?, ?)
+            "#,
+            judge_id,
+            competition_id,
+            qualification.category,
+            qualification.role,
+            qualification.hospitation,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    Ok(())
+}
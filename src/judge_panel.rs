@@ -0,0 +1,388 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{SqliteConnection, SqlitePool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    http_server::HttpError,
+    judge::{JudgeQualification, JudgeRole, get_qualifications},
+    timeplan::{TimeplanEntry, predict_competition},
+};
+
+const ROLES: [JudgeRole; 3] = [
+    JudgeRole::Performance,
+    JudgeRole::Technique,
+    JudgeRole::Dismount,
+];
+
+/// How many judges of each role and how many hospitants a category needs.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct PanelRequirements {
+    pub performance: i64,
+    pub technique: i64,
+    pub dismount: i64,
+    pub hospitation: i64,
+}
+
+impl PanelRequirements {
+    pub fn judges(&self, role: JudgeRole) -> i64 {
+        match role {
+            JudgeRole::Performance => self.performance,
+            JudgeRole::Technique => self.technique,
+            JudgeRole::Dismount => self.dismount,
+        }
+    }
+}
+
+/// A category entry of the timeplan that needs a panel.
+#[derive(Debug, Clone)]
+pub struct PanelSlot {
+    pub timeplan_id: i64,
+    pub category: String,
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+    pub requirements: PanelRequirements,
+    /// Clubs with starters in the category.
+    pub starter_clubs: HashSet<Uuid>,
+}
+
+/// A judge available for the panels.
+#[derive(Debug, Clone)]
+pub struct PanelJudge {
+    pub id: Uuid,
+    pub club_id: Uuid,
+    pub qualifications: Vec<JudgeQualification>,
+}
+
+impl PanelJudge {
+    fn can_judge(&self, category: &str, role: JudgeRole) -> bool {
+        self.qualifications
+            .iter()
+            .any(|q| q.category == category && q.role == role && !q.hospitation)
+    }
+
+    fn hospitation_roles(&self, category: &str) -> impl Iterator<Item = JudgeRole> {
+        self.qualifications
+            .iter()
+            .filter(move |q| q.category == category && q.hospitation)
+            .map(|q| q.role)
+    }
+}
+
+/// A judge in the panel of a timeplan entry.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PanelAssignment {
+    pub timeplan_id: i64,
+    pub judge_id: Uuid,
+    pub role: JudgeRole,
+    pub hospitation: bool,
+    pub is_manual: bool,
+}
+
+/// The assignments made so far with the time each judge is busy.
+#[derive(Default)]
+struct PanelState {
+    assignments: Vec<PanelAssignment>,
+    busy: HashMap<Uuid, Vec<(OffsetDateTime, OffsetDateTime)>>,
+    load: HashMap<Uuid, time::Duration>,
+}
+
+impl PanelState {
+    fn take(&mut self, assignment: PanelAssignment, slot: &PanelSlot) {
+        self.busy
+            .entry(assignment.judge_id)
+            .or_default()
+            .push((slot.start, slot.end));
+        *self.load.entry(assignment.judge_id).or_default() += slot.end - slot.start;
+        self.assignments.push(assignment);
+    }
+
+    /// Whether the judge sits in no panel overlapping the slot. Slots of zero
+    /// length overlap nothing, so the panel of the slot itself is checked too.
+    fn is_free(&self, judge: &PanelJudge, slot: &PanelSlot) -> bool {
+        let in_panel = self
+            .assignments
+            .iter()
+            .any(|a| a.timeplan_id == slot.timeplan_id && a.judge_id == judge.id);
+        !in_panel
+            && self.busy.get(&judge.id).is_none_or(|intervals| {
+                intervals
+                    .iter()
+                    .all(|(start, end)| *end <= slot.start || slot.end <= *start)
+            })
+    }
+
+    fn load(&self, judge: &PanelJudge) -> time::Duration {
+        self.load.get(&judge.id).copied().unwrap_or_default()
+    }
+
+    fn count(&self, slot: &PanelSlot, role: Option<JudgeRole>, hospitation: bool) -> i64 {
+        self.assignments
+            .iter()
+            .filter(|a| a.timeplan_id == slot.timeplan_id && a.hospitation == hospitation)
+            .filter(|a| role.is_none_or(|role| a.role == role))
+            .count() as i64
+    }
+}
+
+/// Assign judges to the panels of all slots.
+///
+/// The `manual` assignments are kept as they are and count towards the
+/// requirements. Slots are filled in chronological order. A judge is never
+/// assigned to overlapping slots, judges without starters in the category
+/// are preferred and among those the judge with the least assigned time is
+/// taken first. Hospitants are spread over the roles they want to learn.
+/// Slots that cannot be filled completely stay short.
+pub fn assign(
+    slots: &[PanelSlot],
+    judges: &[PanelJudge],
+    manual: &[PanelAssignment],
+) -> Vec<PanelAssignment> {
+    let mut state = PanelState::default();
+    for assignment in manual {
+        if let Some(slot) = slots
+            .iter()
+            .find(|s| s.timeplan_id == assignment.timeplan_id)
+        {
+            state.take(assignment.clone(), slot);
+        }
+    }
+
+    let mut slots: Vec<&PanelSlot> = slots.iter().collect();
+    slots.sort_by_key(|slot| (slot.start, slot.timeplan_id));
+
+    for slot in slots {
+        for role in ROLES {
+            let missing = slot.requirements.judges(role) - state.count(slot, Some(role), false);
+            let mut candidates: Vec<&PanelJudge> = judges
+                .iter()
+                .filter(|judge| judge.can_judge(&slot.category, role) && state.is_free(judge, slot))
+                .collect();
+            candidates.sort_by_key(|judge| {
+                (
+                    slot.starter_clubs.contains(&judge.club_id),
+                    state.load(judge),
+                    judge.id,
+                )
+            });
+            let chosen: Vec<Uuid> = candidates
+                .into_iter()
+                .take(missing.max(0) as usize)
+                .map(|judge| judge.id)
+                .collect();
+            for judge_id in chosen {
+                state.take(
+                    PanelAssignment {
+                        timeplan_id: slot.timeplan_id,
+                        judge_id,
+                        role,
+                        hospitation: false,
+                        is_manual: false,
+                    },
+                    slot,
+                );
+            }
+        }
+
+        let missing = slot.requirements.hospitation - state.count(slot, None, true);
+        let mut candidates: Vec<&PanelJudge> = judges
+            .iter()
+            .filter(|judge| judge.hospitation_roles(&slot.category).next().is_some())
+            .filter(|judge| state.is_free(judge, slot))
+            .collect();
+        candidates.sort_by_key(|judge| (state.load(judge), judge.id));
+        for judge in candidates.into_iter().take(missing.max(0) as usize) {
+            // Hospitants are spread over the roles they can learn
+            let Some(role) = judge
+                .hospitation_roles(&slot.category)
+                .min_by_key(|role| state.count(slot, Some(*role), true))
+            else {
+                continue;
+            };
+            state.take(
+                PanelAssignment {
+                    timeplan_id: slot.timeplan_id,
+                    judge_id: judge.id,
+                    role,
+                    hospitation: true,
+                    is_manual: false,
+                },
+                slot,
+            );
+        }
+    }
+
+    state.assignments
+}
+
+/// Load the category entries of the timeplan with their predicted times.
+pub async fn get_slots(db: &SqlitePool, competition_id: Uuid) -> Result<Vec<PanelSlot>, HttpError> {
+    let has_timeplan = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM timeplan WHERE competition_id = ?) as "exists!: bool""#,
+        competition_id
+    )
+    .fetch_one(db)
+    .await?;
+    if !has_timeplan {
+        return Ok(vec![]);
+    }
+
+    let requirements: HashMap<String, PanelRequirements> = sqlx::query!(
+        r#"
+        SELECT
+            name,
+            panel_performance,
+            panel_technique,
+            panel_dismount,
+            panel_hospitation
+        FROM categories WHERE competition_id = ?
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|category| {
+        (
+            category.name,
+            PanelRequirements {
+                performance: category.panel_performance,
+                technique: category.panel_technique,
+                dismount: category.panel_dismount,
+                hospitation: category.panel_hospitation,
+            },
+        )
+    })
+    .collect();
+
+    let mut starter_clubs: HashMap<String, HashSet<Uuid>> = HashMap::new();
+    for row in sqlx::query!(
+        r#"
+        SELECT DISTINCT
+            view_act.category as "category!",
+            starter.club_id as "club_id!: Uuid"
+        FROM view_act
+          JOIN act_participants ON act_participants.act_id = view_act.id
+          JOIN starter ON starter.id = act_participants.starter_id
+        WHERE view_act.competition_id = ? AND view_act.category IS NOT NULL
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    {
+        starter_clubs
+            .entry(row.category)
+            .or_default()
+            .insert(row.club_id);
+    }
+
+    let timeplan = predict_competition(db, competition_id).await?;
+    Ok(timeplan
+        .items
+        .into_iter()
+        .filter_map(|item| match item.timeplan_entry {
+            TimeplanEntry::Category { name, .. } => Some(PanelSlot {
+                timeplan_id: item.id,
+                start: item.predicted_start,
+                end: item.predicted_end,
+                requirements: requirements.get(&name).copied().unwrap_or_default(),
+                starter_clubs: starter_clubs.get(&name).cloned().unwrap_or_default(),
+                category: name,
+            }),
+            TimeplanEntry::Custom { .. } => None,
+        })
+        .collect())
+}
+
+/// Load all judges of a competition with their qualifications.
+pub async fn get_judges(db: &SqlitePool, competition_id: Uuid) -> sqlx::Result<Vec<PanelJudge>> {
+    let mut qualifications = get_qualifications(db, competition_id, None).await?;
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            judge.id as "id!: Uuid",
+            judge.club_id as "club_id!: Uuid"
+        FROM judge JOIN clubs ON clubs.id = judge.club_id
+        WHERE clubs.competition_id = ?
+        ORDER BY judge.id
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|judge| PanelJudge {
+        qualifications: qualifications.remove(&judge.id).unwrap_or_default(),
+        id: judge.id,
+        club_id: judge.club_id,
+    })
+    .collect())
+}
+
+/// Load the panel assignments of a competition.
+pub async fn get_assignments(
+    db: &SqlitePool,
+    competition_id: Uuid,
+) -> sqlx::Result<Vec<PanelAssignment>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            timeplan_id,
+            judge_id as "judge_id!: Uuid",
+            role as "role: JudgeRole",
+            hospitation,
+            is_manual
+        FROM judge_panel
+          JOIN timeplan ON timeplan.id = judge_panel.timeplan_id
+        WHERE timeplan.competition_id = ?
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| PanelAssignment {
+        timeplan_id: row.timeplan_id,
+        judge_id: row.judge_id,
+        role: row.role,
+        hospitation: row.hospitation,
+        is_manual: row.is_manual,
+    })
+    .collect())
+}
+
+/// Replace the automatic assignments of a competition.
+pub async fn set_automatic_assignments(
+    tx: &mut SqliteConnection,
+    competition_id: Uuid,
+    assignments: &[PanelAssignment],
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM judge_panel
+        WHERE NOT is_manual
+          AND timeplan_id IN (SELECT id FROM timeplan WHERE competition_id = ?)
+        "#,
+        competition_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for assignment in assignments.iter().filter(|a| !a.is_manual) {
+        sqlx::query!(
+            r#"
+            INSERT INTO judge_panel (timeplan_id, judge_id, role, hospitation, is_manual)
+            VALUES (?, ?, ?, ?, FALSE)
+            "#,
+            assignment.timeplan_id,
+            assignment.judge_id,
+            assignment.role,
+            assignment.hospitation,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    Ok(())
+}
@@ -7,16 +7,32 @@ use axum_extra::extract::{
 use jsonwebtoken::{DecodingKey, EncodingKey};
 use uuid::Uuid;
 
+use crate::session::Session;
+
+/// How long an access token is valid, it's refreshed with the refresh token
+/// of its session after that.
+pub const ACCESS_TOKEN_VALIDITY: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// How long a session lasts without logging in again.
+pub const SESSION_VALIDITY: std::time::Duration =
+    std::time::Duration::from_secs(60 * 60 * 24 * 31 * 6);
+
+/// Claims of an access token, `jti` is the id of its session.
+///
+/// Unknown fields are rejected so refresh tokens can't be used as access
+/// tokens.
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct JWTClaims {
     sub: Uuid,
     exp: u64,
+    jti: Uuid,
 }
 
 impl JWTClaims {
     #[must_use]
-    pub fn new(sub: Uuid, exp: u64) -> Self {
-        Self { sub, exp }
+    pub fn new(sub: Uuid, exp: u64, jti: Uuid) -> Self {
+        Self { sub, exp, jti }
     }
 
     #[must_use]
@@ -28,6 +44,66 @@ impl JWTClaims {
     pub fn exp(&self) -> u64 {
         self.exp
     }
+
+    #[must_use]
+    pub fn jti(&self) -> Uuid {
+        self.jti
+    }
+}
+
+/// Claims of a refresh token, `rt` is replaced on every refresh.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct RefreshClaims {
+    sub: Uuid,
+    exp: u64,
+    jti: Uuid,
+    rt: Uuid,
+}
+
+impl RefreshClaims {
+    #[must_use]
+    pub fn sub(&self) -> Uuid {
+        self.sub
+    }
+
+    #[must_use]
+    pub fn jti(&self) -> Uuid {
+        self.jti
+    }
+
+    #[must_use]
+    pub fn rt(&self) -> Uuid {
+        self.rt
+    }
+}
+
+fn expires_in(validity: std::time::Duration) -> u64 {
+    (std::time::SystemTime::now() + validity)
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// How long a signed song URL can be used.
+pub const SONG_URL_VALIDITY: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Claims of a signed song URL, they grant access to a single song only.
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
+pub struct SongClaims {
+    song: String,
+    exp: u64,
+}
+
+impl SongClaims {
+    #[must_use]
+    pub fn song(&self) -> &str {
+        &self.song
+    }
+
+    #[must_use]
+    pub fn exp(&self) -> u64 {
+        self.exp
+    }
 }
 
 #[derive(Clone)]
@@ -36,7 +112,6 @@ pub struct JWTConfig {
     decode_key: DecodingKey,
     algorithm: jsonwebtoken::Algorithm,
     validation: jsonwebtoken::Validation,
-    validity: std::time::Duration,
     insecure_cookies: bool,
 }
 
@@ -54,7 +129,6 @@ impl JWTConfig {
             decode_key,
             algorithm,
             validation,
-            validity: std::time::Duration::from_secs(60 * 60 * 24 * 31 * 6),
             insecure_cookies,
         }
     }
@@ -79,46 +153,113 @@ impl JWTConfig {
         &self.validation
     }
 
-    pub fn create_user_token(&self, user_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+    /// Create a short lived access token for a session.
+    pub fn create_access_token(
+        &self,
+        session: &Session,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let claims = JWTClaims::new(
-            user_id,
-            (std::time::SystemTime::now() + self.validity)
-                .duration_since(std::time::SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            session.user_id,
+            expires_in(ACCESS_TOKEN_VALIDITY),
+            session.id,
         );
         jsonwebtoken::encode(&self.get_header(), &claims, self.encoding_key())
     }
 
+    /// Create a refresh token for the current refresh token of a session.
+    pub fn create_refresh_token(
+        &self,
+        session: &Session,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let claims = RefreshClaims {
+            sub: session.user_id,
+            exp: expires_in(SESSION_VALIDITY),
+            jti: session.id,
+            rt: session.refresh_token,
+        };
+        jsonwebtoken::encode(&self.get_header(), &claims, self.encoding_key())
+    }
+
+    /// The claims of a valid access token.
+    #[must_use]
+    pub fn decode_access_token(&self, token: &str) -> Option<JWTClaims> {
+        jsonwebtoken::decode::<JWTClaims>(token, self.decode_key(), self.validation())
+            .ok()
+            .map(|token_data| token_data.claims)
+    }
+
+    /// The claims of a valid refresh token.
+    #[must_use]
+    pub fn decode_refresh_token(&self, token: &str) -> Option<RefreshClaims> {
+        jsonwebtoken::decode::<RefreshClaims>(token, self.decode_key(), self.validation())
+            .ok()
+            .map(|token_data| token_data.claims)
+    }
+
+    /// Sign a song URL for players that can't send the cookie, valid for
+    /// [`SONG_URL_VALIDITY`].
+    pub fn create_song_token(
+        &self,
+        song: &str,
+    ) -> Result<(String, SongClaims), jsonwebtoken::errors::Error> {
+        let claims = SongClaims {
+            song: song.to_string(),
+            exp: expires_in(SONG_URL_VALIDITY),
+        };
+        let token = jsonwebtoken::encode(&self.get_header(), &claims, self.encoding_key())?;
+        Ok((token, claims))
+    }
+
+    /// The song a signed URL grants access to, `None` if the token is
+    /// invalid or expired.
+    #[must_use]
+    pub fn decode_song_token(&self, token: &str) -> Option<SongClaims> {
+        jsonwebtoken::decode::<SongClaims>(token, self.decode_key(), self.validation())
+            .ok()
+            .map(|token_data| token_data.claims)
+    }
+
+    fn cookie(
+        &self,
+        name: &'static str,
+        value: String,
+        max_age: time::Duration,
+    ) -> Cookie<'static> {
+        Cookie::build((name, value))
+            .http_only(true)
+            .secure(!self.insecure_cookies)
+            .max_age(max_age)
+            .path("/")
+            .same_site(SameSite::Strict)
+            .build()
+    }
+
+    /// Add the access token and the refresh token of a session.
     pub fn add_jwt_cookie(
         &self,
         cookies: CookieJar,
-        user_id: Uuid,
+        session: &Session,
     ) -> Result<CookieJar, jsonwebtoken::errors::Error> {
-        Ok(cookies.add(
-            Cookie::build(("jwt", self.create_user_token(user_id)?))
-                .http_only(true)
-                .secure(!self.insecure_cookies)
-                .max_age(time::Duration::try_from(self.validity).unwrap())
-                .path("/")
-                .same_site(SameSite::Strict)
-                .build(),
-        ))
+        Ok(cookies
+            .add(self.cookie(
+                "jwt",
+                self.create_access_token(session)?,
+                time::Duration::try_from(ACCESS_TOKEN_VALIDITY).unwrap(),
+            ))
+            .add(self.cookie(
+                "refresh",
+                self.create_refresh_token(session)?,
+                time::Duration::try_from(SESSION_VALIDITY).unwrap(),
+            )))
     }
 
     pub fn remove_jwt_cookie(
         &self,
         cookies: CookieJar,
     ) -> Result<CookieJar, jsonwebtoken::errors::Error> {
-        Ok(cookies.add(
-            Cookie::build(("jwt", ""))
-                .http_only(true)
-                .secure(!self.insecure_cookies)
-                .max_age(time::Duration::seconds(0))
-                .path("/")
-                .same_site(SameSite::Strict)
-                .build(),
-        ))
+        Ok(cookies
+            .add(self.cookie("jwt", String::new(), time::Duration::seconds(0)))
+            .add(self.cookie("refresh", String::new(), time::Duration::seconds(0))))
     }
 }
 
@@ -129,7 +270,6 @@ impl Debug for JWTConfig {
             .field("decode_key", &"...")
             .field("algorithm", &self.algorithm)
             .field("validation", &self.validation)
-            .field("validity", &self.validity)
             .finish()
     }
 }
@@ -1,7 +1,30 @@
+pub mod account_cleanup;
+pub mod audio;
+pub mod audit;
+pub mod club_membership;
+pub mod competition;
+pub mod duration_stats;
 pub mod http_server;
+pub mod judge;
+pub mod judge_panel;
 pub mod jwt;
+pub mod live;
+pub mod login_throttle;
+pub mod loudness;
 pub mod mailer;
+pub mod music_package;
 pub mod reloadable_sqlite;
+pub mod results_csv;
+pub mod results_pdf;
+pub mod scoring;
+pub mod session;
+pub mod song_analysis;
+pub mod song_storage;
+pub mod song_versions;
+pub mod startlist_export;
 pub mod system_status;
 pub mod templates;
+pub mod timeplan;
+pub mod timeplan_events;
+pub mod totp;
 pub mod utils;
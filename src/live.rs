@@ -0,0 +1,67 @@
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events that are kept for slow subscribers before they miss some.
+const CAPACITY: usize = 64;
+
+/// A change of the timeplan progress.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveChange {
+    ActStarted {
+        act_id: Uuid,
+    },
+    ActEnded {
+        act_id: Uuid,
+    },
+    EntryStarted {
+        timeplan_id: i64,
+    },
+    EntryEnded {
+        timeplan_id: i64,
+    },
+    /// Entries or acts were added, edited, reordered or the progress was
+    /// reverted, the timeplan has to be reloaded.
+    TimeplanChanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct LiveEvent {
+    pub competition_id: Uuid,
+    #[serde(flatten)]
+    pub change: LiveChange,
+}
+
+/// Broadcasts the changes of the command handlers to all live subscribers.
+#[derive(Debug, Clone)]
+pub struct LiveHub {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl Default for LiveHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveHub {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+        }
+    }
+
+    /// Send a change to all current subscribers.
+    ///
+    /// Without subscribers the change is dropped.
+    pub fn publish(&self, competition_id: Uuid, change: LiveChange) {
+        let _ = self.sender.send(LiveEvent {
+            competition_id,
+            change,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use sqlx::SqlitePool;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::http_server::HttpError;
+
+/// Delay after the first attempt over the free ones, doubled for every
+/// further attempt.
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+const LOCKOUT: Duration = Duration::from_secs(15 * 60);
+/// Attempts are forgotten after this long without a new one.
+const FORGET_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// What is limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Every failed login counts.
+    Login,
+    /// Every request counts, each one sends a mail.
+    PasswordReset,
+    /// Every wrong TOTP or recovery code counts.
+    SecondFactor,
+}
+
+impl Action {
+    /// Command of the lockout entries in the audit log.
+    fn lockout_command(self) -> &'static str {
+        match self {
+            Action::Login => "login_lockout",
+            Action::PasswordReset => "password_reset_lockout",
+            Action::SecondFactor => "second_factor_lockout",
+        }
+    }
+}
+
+/// Who is limited.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ThrottleKey {
+    Ip(IpAddr),
+    Email(String),
+}
+
+impl ThrottleKey {
+    /// The keys of a request for the account with the given email.
+    #[must_use]
+    pub fn for_request(ip: Option<IpAddr>, email: &str) -> Vec<ThrottleKey> {
+        let mut keys = vec![ThrottleKey::Email(email.trim().to_lowercase())];
+        keys.extend(ip.map(ThrottleKey::Ip));
+        keys
+    }
+}
+
+/// Attempts that are always allowed and attempts that lock the key.
+struct Limits {
+    free: u32,
+    lockout: u32,
+}
+
+fn limits(action: Action, key: &ThrottleKey) -> Limits {
+    // Clubs share addresses, so addresses get more attempts than accounts.
+    match (action, key) {
+        (Action::Login | Action::SecondFactor, ThrottleKey::Email(_)) => Limits {
+            free: 5,
+            lockout: 10,
+        },
+        (Action::Login | Action::SecondFactor, ThrottleKey::Ip(_)) => Limits {
+            free: 20,
+            lockout: 50,
+        },
+        (Action::PasswordReset, ThrottleKey::Email(_)) => Limits {
+            free: 3,
+            lockout: 10,
+        },
+        (Action::PasswordReset, ThrottleKey::Ip(_)) => Limits {
+            free: 10,
+            lockout: 50,
+        },
+    }
+}
+
+#[derive(Debug)]
+struct Attempts {
+    count: u32,
+    last: Instant,
+    blocked_until: Instant,
+    locked: bool,
+}
+
+/// A key that was locked out by an attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lockout {
+    pub action: Action,
+    pub key: ThrottleKey,
+    pub attempts: u32,
+}
+
+/// Rate limits of logins, second factors and password resets by address and
+/// by account.
+///
+/// The first attempts are free, every further one doubles the delay until
+/// the next attempt is allowed. Attempts while blocked count too, and too
+/// many of them lock the key for a while. Lockouts are written to the audit
+/// log.
+#[derive(Debug, Clone, Default)]
+pub struct LoginThrottle {
+    attempts: Arc<Mutex<HashMap<(Action, ThrottleKey), Attempts>>>,
+}
+
+impl LoginThrottle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject the attempt if one of the keys is blocked.
+    ///
+    /// Rejected attempts are recorded like failed ones.
+    pub async fn check(
+        &self,
+        db: &SqlitePool,
+        action: Action,
+        keys: &[ThrottleKey],
+    ) -> Result<(), HttpError> {
+        let now = Instant::now();
+        let retry_after = {
+            let attempts = self.attempts.lock().unwrap();
+            keys.iter()
+                .filter_map(|key| attempts.get(&(action, key.clone())))
+                .map(|attempts| attempts.blocked_until.saturating_duration_since(now))
+                .max()
+                .filter(|retry_after| !retry_after.is_zero())
+        };
+        match retry_after {
+            Some(retry_after) => {
+                self.record(db, action, keys).await;
+                Err(HttpError::TooManyRequests(retry_after))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Count an attempt against the limits of all keys.
+    pub async fn record(&self, db: &SqlitePool, action: Action, keys: &[ThrottleKey]) {
+        let lockouts = self.count(action, keys, Instant::now());
+        for lockout in lockouts {
+            log_lockout(db, &lockout).await;
+        }
+    }
+
+    /// Forget the attempts of an account after it logged in.
+    ///
+    /// Addresses are not reset, a known password must not allow guessing
+    /// others from the same address.
+    pub fn reset(&self, action: Action, keys: &[ThrottleKey]) {
+        let mut attempts = self.attempts.lock().unwrap();
+        for key in keys {
+            if matches!(key, ThrottleKey::Email(_)) {
+                attempts.remove(&(action, key.clone()));
+            }
+        }
+    }
+
+    fn count(&self, action: Action, keys: &[ThrottleKey], now: Instant) -> Vec<Lockout> {
+        let mut attempts = self.attempts.lock().unwrap();
+        attempts.retain(|_, attempts| {
+            attempts.blocked_until > now || now.duration_since(attempts.last) < FORGET_AFTER
+        });
+
+        let mut lockouts = vec![];
+        for key in keys {
+            let limits = limits(action, key);
+            let attempts = attempts.entry((action, key.clone())).or_insert(Attempts {
+                count: 0,
+                last: now,
+                blocked_until: now,
+                locked: false,
+            });
+            attempts.count += 1;
+            attempts.last = now;
+            let blocked = attempts.blocked_until > now;
+            if attempts.count >= limits.lockout {
+                // Keep the lock while it lasts instead of extending it, so
+                // an attacker can't lock out an account forever.
+                if !(attempts.locked && blocked) {
+                    attempts.locked = true;
+                    attempts.blocked_until = now + LOCKOUT;
+                    lockouts.push(Lockout {
+                        action,
+                        key: key.clone(),
+                        attempts: attempts.count,
+                    });
+                }
+            } else if attempts.count > limits.free {
+                let backoff = FIRST_BACKOFF
+                    .saturating_mul(1 << (attempts.count - limits.free - 1).min(16))
+                    .min(MAX_BACKOFF);
+                attempts.blocked_until = now + backoff;
+            }
+        }
+        lockouts
+    }
+}
+
+async fn log_lockout(db: &SqlitePool, lockout: &Lockout) {
+    let (user_id, payload) = match &lockout.key {
+        ThrottleKey::Email(email) => {
+            let user_id = sqlx::query_scalar!(
+                r#"SELECT id as "id!: Uuid" FROM users WHERE lower(email) = ?"#,
+                email
+            )
+            .fetch_optional(db)
+            .await
+            .ok()
+            .flatten();
+            (
+                user_id,
+                json!({ "email": email, "attempts": lockout.attempts }),
+            )
+        }
+        ThrottleKey::Ip(ip) => (None, json!({ "ip": ip, "attempts": lockout.attempts })),
+    };
+    warn!(
+        "Locked out {:?} from {:?} after {} attempts",
+        lockout.key, lockout.action, lockout.attempts
+    );
+    let command = lockout.action.lockout_command();
+    let payload = payload.to_string();
+    if let Err(e) = sqlx::query!(
+        "INSERT INTO audit_log (user_id, command, payload) VALUES (?, ?, ?)",
+        user_id,
+        command,
+        payload
+    )
+    .execute(db)
+    .await
+    {
+        error!("Could not write the audit log for {command}: {e}");
+    }
+}
@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+
+/// Target of EBU R128.
+pub const TARGET_LUFS: f64 = -23.0;
+/// Normalized songs keep their sample peak below this level.
+pub const MAX_PEAK_DBFS: f64 = -1.0;
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+/// Blocks of 400 ms overlap by 75 %, so a block is four of these.
+const SUB_BLOCK_SECONDS: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct Loudness {
+    pub integrated_lufs: f64,
+    pub peak_dbfs: f64,
+}
+
+impl Loudness {
+    /// The gain that brings the song to the target loudness without raising
+    /// its peak above [`MAX_PEAK_DBFS`].
+    pub fn normalization_gain_db(&self) -> f64 {
+        (TARGET_LUFS - self.integrated_lufs).min(MAX_PEAK_DBFS - self.peak_dbfs)
+    }
+}
+
+/// A biquad filter in direct form I.
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[1] * self.y[0]
+            - self.a[2] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// The K-weighting of ITU-R BS.1770 for any sample rate: a high shelf for
+/// the head followed by a high pass.
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let rate = sample_rate as f64;
+
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [1.0, 2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    [shelf, high_pass]
+}
+
+/// Surround channels count more, the LFE channel of 5.1 not at all.
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels, channel) {
+        (6, 3) => 0.0,
+        (5, 3 | 4) | (6, 4 | 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+fn loudness(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+/// Measures the integrated loudness of EBU R128 and the sample peak.
+#[derive(Debug, Clone)]
+pub struct LoudnessMeter {
+    channels: usize,
+    filters: Vec<[Biquad; 2]>,
+    sub_block_frames: usize,
+    /// Weighted energy of the current sub block.
+    energy: f64,
+    frames: usize,
+    sub_blocks: Vec<f64>,
+    peak: f64,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        Self {
+            channels,
+            filters: vec![k_weighting(sample_rate); channels],
+            sub_block_frames: ((sample_rate as f64 * SUB_BLOCK_SECONDS).round() as usize).max(1),
+            energy: 0.0,
+            frames: 0,
+            sub_blocks: vec![],
+            peak: 0.0,
+        }
+    }
+
+    /// Add interleaved samples.
+    pub fn add(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(self.channels) {
+            for (channel, &sample) in frame.iter().enumerate() {
+                let sample = sample as f64;
+                self.peak = self.peak.max(sample.abs());
+                let [shelf, high_pass] = &mut self.filters[channel];
+                let weighted = high_pass.process(shelf.process(sample));
+                self.energy += channel_weight(channel, self.channels) * weighted * weighted;
+            }
+            self.frames += 1;
+            if self.frames == self.sub_block_frames {
+                self.sub_blocks.push(self.energy / self.frames as f64);
+                self.energy = 0.0;
+                self.frames = 0;
+            }
+        }
+    }
+
+    /// The gated loudness over all samples, `None` for silence or for less
+    /// than one block.
+    pub fn integrated_lufs(&self) -> Option<f64> {
+        let blocks: Vec<f64> = self
+            .sub_blocks
+            .windows(4)
+            .map(|block| block.iter().sum::<f64>() / 4.0)
+            .filter(|&energy| energy > 0.0 && loudness(energy) > ABSOLUTE_GATE_LUFS)
+            .collect();
+        if blocks.is_empty() {
+            return None;
+        }
+        let relative_gate =
+            loudness(blocks.iter().sum::<f64>() / blocks.len() as f64) + RELATIVE_GATE_LU;
+        let gated: Vec<f64> = blocks
+            .into_iter()
+            .filter(|&energy| loudness(energy) > relative_gate)
+            .collect();
+        Some(loudness(gated.iter().sum::<f64>() / gated.len() as f64))
+    }
+
+    /// The highest absolute sample in dBFS.
+    pub fn peak_dbfs(&self) -> f64 {
+        20.0 * self.peak.log10()
+    }
+}
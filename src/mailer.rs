@@ -1,14 +1,33 @@
+use std::{future::Future, pin::Pin, sync::Mutex};
+
 use lettre::{
     AsyncSmtpTransport, AsyncTransport, Tokio1Executor, message::header::ContentType,
     transport::smtp::authentication::Credentials,
 };
 
-pub struct Mailer {
+pub type MailFuture<'a> = Pin<Box<dyn Future<Output = Result<(), MailError>> + Send + 'a>>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("Invalid address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("Invalid message: {0}")]
+    Message(#[from] lettre::error::Error),
+}
+
+/// Sends mails to users.
+pub trait Mailer: Send + Sync {
+    fn send_text<'a>(&'a self, to: &'a str, subject: &'a str, body: &'a str) -> MailFuture<'a>;
+}
+
+pub struct SmtpMailer {
     transport: AsyncSmtpTransport<Tokio1Executor>,
     from_address: String,
 }
 
-impl Mailer {
+impl SmtpMailer {
     pub fn new(server: &str, username: &str, password: &str, from_address: &str) -> Self {
         let creds = Credentials::new(username.to_owned(), password.to_owned());
         let transport = lettre::AsyncSmtpTransport::<Tokio1Executor>::relay(server)
@@ -20,22 +39,57 @@ impl Mailer {
             from_address: from_address.to_owned(),
         }
     }
+}
+
+impl Mailer for SmtpMailer {
+    fn send_text<'a>(&'a self, to: &'a str, subject: &'a str, body: &'a str) -> MailFuture<'a> {
+        Box::pin(async move {
+            let email = lettre::Message::builder()
+                .from(self.from_address.parse()?)
+                .to(to.parse()?)
+                .subject(subject)
+                .header(ContentType::TEXT_PLAIN)
+                .body(body.to_string())?;
+
+            self.transport.send(email).await?;
+            Ok(())
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Keeps all mails in memory instead of sending them.
+///
+/// Used for tests and local development without an SMTP server.
+#[derive(Debug, Default)]
+pub struct MemoryMailer {
+    sent: Mutex<Vec<SentMail>>,
+}
+
+impl MemoryMailer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All mails sent so far, oldest first.
+    pub fn sent(&self) -> Vec<SentMail> {
+        self.sent.lock().unwrap().clone()
+    }
+}
 
-    pub async fn send_text(
-        &self,
-        to: &str,
-        subject: &str,
-        body: &str,
-    ) -> Result<(), lettre::transport::smtp::Error> {
-        let email = lettre::Message::builder()
-            .from(self.from_address.parse().unwrap())
-            .to(to.parse().unwrap())
-            .subject(subject)
-            .header(ContentType::TEXT_PLAIN)
-            .body(body.to_string())
-            .unwrap();
-
-        self.transport.send(email).await?;
-        Ok(())
+impl Mailer for MemoryMailer {
+    fn send_text<'a>(&'a self, to: &'a str, subject: &'a str, body: &'a str) -> MailFuture<'a> {
+        self.sent.lock().unwrap().push(SentMail {
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        });
+        Box::pin(async { Ok(()) })
     }
 }
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    io::{Seek, Write},
+    path::Path,
+};
+
+use time::{Date, OffsetDateTime, macros::format_description};
+use uuid::Uuid;
+use zip::{CompressionMethod, ZipWriter, result::ZipResult, write::SimpleFileOptions};
+
+use crate::{
+    http_server::routes::http_types::{participant_clubs, participant_names},
+    startlist_export::LOCAL_OFFSET,
+    timeplan::{Timeplan, TimeplanEntry},
+};
+
+/// A checked song as it is stored.
+#[derive(Debug, Clone, Default)]
+pub struct StoredSong {
+    /// File name in the data path.
+    pub file: String,
+    /// Name of the file when it was uploaded.
+    pub original_file_name: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub loudness_lufs: Option<f64>,
+}
+
+/// A song of the package in starting order.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PackageSong {
+    pub start_number: usize,
+    #[serde(with = "time::serde::iso8601")]
+    pub start: OffsetDateTime,
+    pub category: String,
+    pub act_id: Uuid,
+    pub act_name: String,
+    pub names: String,
+    pub clubs: String,
+    /// Name of the file in the package.
+    pub file: String,
+    #[serde(skip)]
+    pub stored_file: String,
+    pub original_file_name: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub loudness_lufs: Option<f64>,
+}
+
+impl PackageSong {
+    fn day(&self) -> Date {
+        self.start.date()
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Manifest<'a> {
+    competition: &'a str,
+    #[serde(with = "time::serde::iso8601")]
+    created_at: OffsetDateTime,
+    songs: &'a [PackageSong],
+}
+
+/// Keep letters and digits, everything else becomes a single dash.
+///
+/// The names are safe on every file system of a venue laptop.
+pub fn file_name_part(value: &str) -> String {
+    let mut part = String::new();
+    for c in value.chars() {
+        if c.is_alphanumeric() {
+            part.push(c);
+        } else if !part.is_empty() && !part.ends_with('-') {
+            part.push('-');
+        }
+    }
+    part.trim_end_matches('-').to_string()
+}
+
+/// The songs of the timeplan in starting order.
+///
+/// Start numbers count all acts, so acts without a checked song leave a gap.
+pub fn package_songs(timeplan: &Timeplan, songs: &HashMap<Uuid, StoredSong>) -> Vec<PackageSong> {
+    timeplan
+        .items
+        .iter()
+        .filter_map(|item| match &item.timeplan_entry {
+            TimeplanEntry::Category { name, acts, .. } => {
+                Some(acts.iter().map(move |act| (name, act)))
+            }
+            TimeplanEntry::Custom { .. } => None,
+        })
+        .flatten()
+        .enumerate()
+        .filter_map(|(index, (category, act))| {
+            let song = songs.get(&act.id)?;
+            let start_number = index + 1;
+            let names = participant_names(&act.participants);
+            let extension = Path::new(&song.file)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            Some(PackageSong {
+                start_number,
+                start: act.predicted_start.to_offset(LOCAL_OFFSET),
+                category: category.clone(),
+                act_id: act.id,
+                act_name: act.name.clone(),
+                file: format!(
+                    "{start_number:03}_{}_{}.{extension}",
+                    file_name_part(category),
+                    file_name_part(&names)
+                ),
+                names,
+                clubs: participant_clubs(&act.participants),
+                stored_file: song.file.clone(),
+                original_file_name: song.original_file_name.clone(),
+                duration_seconds: song.duration_seconds,
+                loudness_lufs: song.loudness_lufs,
+            })
+        })
+        .collect()
+}
+
+/// An extended M3U playlist of the songs of one day.
+pub fn render_playlist(songs: &[&PackageSong]) -> String {
+    let mut playlist = "#EXTM3U\n".to_string();
+    for song in songs {
+        let duration = song
+            .duration_seconds
+            .map(|duration| duration.round() as i64)
+            .unwrap_or(-1);
+        playlist.push_str(&format!(
+            "#EXTINF:{duration},{} {} - {}\n{}\n",
+            song.start_number, song.names, song.act_name, song.file
+        ));
+    }
+    playlist
+}
+
+/// Pack the songs with a playlist per day and a manifest into `out`.
+///
+/// `write_song` writes the content of a stored song, so the songs can be
+/// copied in chunks instead of being held in memory. Songs are stored
+/// without compression, they are compressed already.
+pub fn render_zip<W: Write + Seek>(
+    out: W,
+    competition: &str,
+    songs: &[PackageSong],
+    mut write_song: impl FnMut(&PackageSong, &mut ZipWriter<W>) -> std::io::Result<()>,
+) -> ZipResult<W> {
+    let mut zip = ZipWriter::new(out);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default();
+
+    for song in songs {
+        zip.start_file(&song.file, stored)?;
+        write_song(song, &mut zip)?;
+    }
+
+    let mut days: Vec<Date> = songs.iter().map(PackageSong::day).collect();
+    days.dedup();
+    for day in days {
+        let day_songs: Vec<_> = songs.iter().filter(|song| song.day() == day).collect();
+        let name = day
+            .format(format_description!("[year]-[month]-[day]"))
+            .unwrap_or_default();
+        zip.start_file(format!("playlist_{name}.m3u8"), deflated)?;
+        zip.write_all(render_playlist(&day_songs).as_bytes())?;
+    }
+
+    zip.start_file("manifest.json", deflated)?;
+    let manifest = Manifest {
+        competition,
+        created_at: OffsetDateTime::now_utc(),
+        songs,
+    };
+    zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::other)?)?;
+
+    zip.finish()
+}
@@ -0,0 +1,46 @@
+use crate::scoring::{ResultAct, format_points};
+
+const HEADER: [&str; 9] = [
+    "Wettbewerb",
+    "Platz",
+    "Kürtitel",
+    "Name",
+    "Verein",
+    "Technik",
+    "Präsentation",
+    "Abzüge",
+    "Punkte",
+];
+
+/// Render the results of all categories as CSV following RFC 4180.
+///
+/// Fields are separated by semicolons because the points use a decimal
+/// comma. Like the startlist the file starts with a byte order mark for
+/// Excel.
+pub fn render_csv(results: &[ResultAct]) -> csv::Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b';')
+        .terminator(csv::Terminator::CRLF)
+        .from_writer("\u{feff}".as_bytes().to_vec());
+    writer.write_record(HEADER)?;
+    for act in results {
+        let category = act
+            .category_description
+            .clone()
+            .unwrap_or_else(|| act.category.clone());
+        writer.write_record([
+            category,
+            act.rank.map_or(String::new(), |rank| rank.to_string()),
+            act.name.clone(),
+            act.names(),
+            act.clubs(),
+            format_points(act.score.technique),
+            format_points(act.score.performance),
+            format_points(Some(act.score.dismount_deduction)),
+            format_points(act.score.total),
+        ])?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
@@ -0,0 +1,158 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use crate::scoring::{ResultAct, format_points};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const LINE_HEIGHT: f32 = 16.0;
+/// Rows below the headings of a page.
+const ROWS_PER_PAGE: usize = 40;
+
+const FONT: Name = Name(b"F1");
+const BOLD_FONT: Name = Name(b"F2");
+
+/// Columns of the result table with their x position and width in characters.
+const COLUMNS: [(&str, f32, usize); 4] = [
+    ("Platz", MARGIN, 6),
+    ("Name", MARGIN + 45.0, 38),
+    ("Verein", MARGIN + 265.0, 30),
+    ("Punkte", MARGIN + 440.0, 8),
+];
+
+/// Encode text for the WinAnsi encoding of the standard PDF fonts.
+///
+/// Latin-1 characters like umlauts map directly, everything else that is
+/// not available becomes `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '…' => 0x85,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Shorten text to a maximum number of characters.
+fn fit(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut text: String = text.chars().take(max_chars - 1).collect();
+    text.push('…');
+    text
+}
+
+fn text(content: &mut Content, font: Name, size: f32, x: f32, y: f32, value: &str) {
+    content
+        .begin_text()
+        .set_font(font, size)
+        .next_line(x, y)
+        .show(Str(&encode(value)))
+        .end_text();
+}
+
+/// Render one page with the heading and a part of the acts of a category.
+fn render_page(title: &str, heading: &str, acts: &[&ResultAct]) -> Vec<u8> {
+    let mut content = Content::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    text(&mut content, FONT, 10.0, MARGIN, y, title);
+    y -= 2.0 * LINE_HEIGHT;
+    text(&mut content, BOLD_FONT, 16.0, MARGIN, y, heading);
+    y -= 2.0 * LINE_HEIGHT;
+
+    for (label, x, _) in COLUMNS {
+        text(&mut content, BOLD_FONT, 11.0, x, y, label);
+    }
+    content
+        .set_line_width(0.5)
+        .move_to(MARGIN, y - 4.0)
+        .line_to(PAGE_WIDTH - MARGIN, y - 4.0)
+        .stroke();
+    y -= LINE_HEIGHT + 2.0;
+
+    for act in acts {
+        let rank = act.rank.map_or("-".to_string(), |rank| format!("{rank}."));
+        let values = [
+            rank,
+            act.names(),
+            act.clubs(),
+            format_points(act.score.total),
+        ];
+        for ((_, x, width), value) in COLUMNS.iter().zip(values) {
+            text(&mut content, FONT, 10.0, *x, y, &fit(&value, *width));
+        }
+        y -= LINE_HEIGHT;
+    }
+    content.finish()
+}
+
+/// Render the results as a PDF with one page per category.
+///
+/// Only the standard Helvetica fonts are used, so no fonts have to be
+/// installed or embedded.
+pub fn render_results(title: &str, results: &[ResultAct]) -> Vec<u8> {
+    let mut pages: Vec<Vec<u8>> = vec![];
+    for category in results.chunk_by(|a, b| a.category == b.category) {
+        let heading = category[0]
+            .category_description
+            .clone()
+            .unwrap_or_else(|| category[0].category.clone());
+        let acts: Vec<&ResultAct> = category.iter().collect();
+        for (index, chunk) in acts.chunks(ROWS_PER_PAGE).enumerate() {
+            let heading = if index == 0 {
+                heading.clone()
+            } else {
+                format!("{heading} (Fortsetzung)")
+            };
+            pages.push(render_page(title, &heading, chunk));
+        }
+    }
+    if pages.is_empty() {
+        pages.push(render_page(title, "Noch keine Ergebnisse", &[]));
+    }
+
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let info_id = Ref::new(5);
+    let page_ids: Vec<Ref> = (0..pages.len() as i32)
+        .map(|i| Ref::new(6 + 2 * i))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages.len() as i32);
+    pdf.document_info(info_id).title(TextStr(title));
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for (page_id, content) in page_ids.iter().zip(pages) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut page = pdf.page(*page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        let mut resources = page.resources();
+        resources
+            .fonts()
+            .pair(FONT, font_id)
+            .pair(BOLD_FONT, bold_font_id);
+        resources.finish();
+        page.finish();
+        pdf.stream(content_id, &content);
+    }
+
+    pdf.finish()
+}
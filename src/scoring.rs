@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    http_server::routes::http_types::{ActParticipant, participant_clubs, participant_names},
+    judge::JudgeRole,
+};
+
+/// Points of the technique and of the performance score.
+pub const ROLE_POINTS: f64 = 50.0;
+/// Every sub-criterion of technique and performance is scored from 0 to 10.
+pub const MAX_CRITERION_VALUE: f64 = 10.0;
+pub const MINOR_DISMOUNT_DEDUCTION: f64 = 0.5;
+pub const MAJOR_DISMOUNT_DEDUCTION: f64 = 1.0;
+
+/// A sub-criterion of a judge's score.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum ScoreCriterion {
+    /// Technique: number of different skills and transitions.
+    Quantity,
+    /// Technique: mastery and quality of execution.
+    Mastery,
+    /// Technique: difficulty and duration of the skills.
+    Difficulty,
+    /// Performance: presence, carriage and execution.
+    Presence,
+    /// Performance: composition and choreography.
+    Composition,
+    /// Performance: interpretation of the music.
+    Interpretation,
+    /// Dismount: number of minor dismounts.
+    MinorDismounts,
+    /// Dismount: number of major dismounts.
+    MajorDismounts,
+}
+
+impl ScoreCriterion {
+    /// The sub-criteria a judge of the role has to score.
+    pub fn for_role(role: JudgeRole) -> &'static [ScoreCriterion] {
+        match role {
+            JudgeRole::Technique => &[Self::Quantity, Self::Mastery, Self::Difficulty],
+            JudgeRole::Performance => &[Self::Presence, Self::Composition, Self::Interpretation],
+            JudgeRole::Dismount => &[Self::MinorDismounts, Self::MajorDismounts],
+        }
+    }
+
+    /// Check that the value is allowed for the criterion.
+    pub fn is_valid(&self, value: f64) -> bool {
+        match self {
+            Self::MinorDismounts | Self::MajorDismounts => value >= 0.0 && value.fract() == 0.0,
+            _ => (0.0..=MAX_CRITERION_VALUE).contains(&value),
+        }
+    }
+}
+
+/// A single sub-criterion scored by a judge.
+#[derive(Debug, Clone)]
+pub struct ScoreEntry {
+    pub judge_id: Uuid,
+    pub role: JudgeRole,
+    pub criterion: ScoreCriterion,
+    pub value: f64,
+}
+
+/// The computed score of an act.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct ActScore {
+    /// Technique points out of [`ROLE_POINTS`].
+    pub technique: Option<f64>,
+    /// Performance points out of [`ROLE_POINTS`].
+    pub performance: Option<f64>,
+    pub dismount_deduction: f64,
+    /// Only set once technique and performance are scored.
+    pub total: Option<f64>,
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Mean of the judges' totals.
+///
+/// With three or more judges the highest and the lowest total are dropped.
+fn trimmed_mean(mut totals: Vec<f64>) -> Option<f64> {
+    if totals.is_empty() {
+        return None;
+    }
+    if totals.len() >= 3 {
+        totals.sort_by(f64::total_cmp);
+        totals.pop();
+        totals.remove(0);
+    }
+    Some(totals.iter().sum::<f64>() / totals.len() as f64)
+}
+
+/// The scores of all judges of a role that scored every sub-criterion.
+fn judge_scores(entries: &[ScoreEntry], role: JudgeRole) -> Vec<HashMap<ScoreCriterion, f64>> {
+    let mut by_judge: HashMap<Uuid, HashMap<ScoreCriterion, f64>> = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.role == role) {
+        by_judge
+            .entry(entry.judge_id)
+            .or_default()
+            .insert(entry.criterion, entry.value);
+    }
+    by_judge
+        .into_values()
+        .filter(|criteria| {
+            ScoreCriterion::for_role(role)
+                .iter()
+                .all(|criterion| criteria.contains_key(criterion))
+        })
+        .collect()
+}
+
+/// Compute the score of an act following the IUF freestyle rules.
+///
+/// Technique and performance judges score three sub-criteria each. The sum
+/// of a judge is scaled to [`ROLE_POINTS`]. With three or more judges the
+/// highest and the lowest judge are dropped before averaging. Dismount
+/// judges count minor and major dismounts, the mean of their deductions is
+/// subtracted from the sum of technique and performance.
+pub fn score_act(entries: &[ScoreEntry]) -> ActScore {
+    let role_score = |role: JudgeRole| {
+        let max = MAX_CRITERION_VALUE * ScoreCriterion::for_role(role).len() as f64;
+        let totals = judge_scores(entries, role)
+            .into_iter()
+            .map(|criteria| criteria.values().sum::<f64>() / max * ROLE_POINTS)
+            .collect();
+        trimmed_mean(totals).map(round)
+    };
+    let technique = role_score(JudgeRole::Technique);
+    let performance = role_score(JudgeRole::Performance);
+
+    let deductions: Vec<f64> = judge_scores(entries, JudgeRole::Dismount)
+        .into_iter()
+        .map(|criteria| {
+            criteria[&ScoreCriterion::MinorDismounts] * MINOR_DISMOUNT_DEDUCTION
+                + criteria[&ScoreCriterion::MajorDismounts] * MAJOR_DISMOUNT_DEDUCTION
+        })
+        .collect();
+    let dismount_deduction = if deductions.is_empty() {
+        0.0
+    } else {
+        round(deductions.iter().sum::<f64>() / deductions.len() as f64)
+    };
+
+    let total = match (technique, performance) {
+        (Some(technique), Some(performance)) => Some(round(
+            (technique + performance - dismount_deduction).max(0.0),
+        )),
+        _ => None,
+    };
+
+    ActScore {
+        technique,
+        performance,
+        dismount_deduction,
+        total,
+    }
+}
+
+/// Rank the totals, equal totals share a rank.
+///
+/// Acts without a total are not ranked.
+pub fn rank(totals: &[Option<f64>]) -> Vec<Option<i64>> {
+    totals
+        .iter()
+        .map(|total| {
+            let total = (*total)?;
+            let better = totals
+                .iter()
+                .flatten()
+                .filter(|other| **other > total)
+                .count();
+            Some(better as i64 + 1)
+        })
+        .collect()
+}
+
+/// The result of an act in its category.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct ResultAct {
+    pub id: Uuid,
+    pub name: String,
+    pub category: String,
+    pub category_description: Option<String>,
+    /// Categories added by an admin have no order and come last.
+    pub category_order: Option<i64>,
+    pub participants: Vec<ActParticipant>,
+    #[serde(flatten)]
+    pub score: ActScore,
+    /// Rank within the category, equal totals share a rank.
+    pub rank: Option<i64>,
+}
+
+impl ResultAct {
+    /// The names of all participants, e.g. `Anna Muster & Berta Beispiel`.
+    pub fn names(&self) -> String {
+        participant_names(&self.participants)
+    }
+
+    /// The clubs of all participants, each club only once.
+    pub fn clubs(&self) -> String {
+        participant_clubs(&self.participants)
+    }
+}
+
+/// Format points with a decimal comma.
+pub fn format_points(points: Option<f64>) -> String {
+    points.map_or("-".to_string(), |points| {
+        format!("{points:.2}").replace('.', ",")
+    })
+}
+
+/// Compute the results of all acts of a competition.
+///
+/// Acts are ordered by category and rank, acts without a complete score come
+/// last in their category.
+pub async fn get_results(db: &SqlitePool, competition_id: Uuid) -> sqlx::Result<Vec<ResultAct>> {
+    let mut entries: HashMap<Uuid, Vec<ScoreEntry>> = HashMap::new();
+    for row in sqlx::query!(
+        r#"
+        SELECT
+            act_id as "act_id!: Uuid",
+            judge_id as "judge_id!: Uuid",
+            role as "role: JudgeRole",
+            criterion as "criterion: ScoreCriterion",
+            value
+        FROM scores JOIN acts ON acts.id = scores.act_id
+        WHERE acts.competition_id = ?
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    {
+        entries.entry(row.act_id).or_default().push(ScoreEntry {
+            judge_id: row.judge_id,
+            role: row.role,
+            criterion: row.criterion,
+            value: row.value,
+        });
+    }
+
+    let acts = sqlx::query!(
+        r#"
+        SELECT
+            view_act.id as "id!: Uuid",
+            view_act.name,
+            categories.name as "category!",
+            categories.description as "category_description",
+            categories."order" as "category_order",
+            participants as "participants!: sqlx::types::Json<Vec<ActParticipant>>"
+        FROM view_act
+          JOIN categories ON view_act.category = categories.name
+            AND view_act.competition_id = categories.competition_id
+        WHERE view_act.competition_id = ?
+        ORDER BY categories."order" IS NULL, categories."order", categories.name, view_act."order" ASC
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut results: Vec<ResultAct> = vec![];
+    for category in acts.chunk_by(|a, b| a.category == b.category) {
+        let scores: Vec<ActScore> = category
+            .iter()
+            .map(|act| score_act(entries.get(&act.id).map_or(&[], Vec::as_slice)))
+            .collect();
+        let totals: Vec<Option<f64>> = scores.iter().map(|score| score.total).collect();
+        let mut ranked: Vec<ResultAct> = category
+            .iter()
+            .zip(scores)
+            .zip(rank(&totals))
+            .map(|((act, score), rank)| ResultAct {
+                id: act.id,
+                name: act.name.clone(),
+                category: act.category.clone(),
+                category_description: act.category_description.clone(),
+                category_order: act.category_order,
+                participants: act.participants.0.clone(),
+                score,
+                rank,
+            })
+            .collect();
+        ranked.sort_by_key(|act| act.rank.unwrap_or(i64::MAX));
+        results.extend(ranked);
+    }
+    Ok(results)
+}
@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use sqlx::SqlitePool;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    jwt::{JWTConfig, RefreshClaims, SESSION_VALIDITY},
+    reloadable_sqlite::ReloadableSqlite,
+};
+
+/// A login of a user on one device.
+///
+/// The id is the `jti` of its tokens. The refresh token is replaced on every
+/// refresh, so a stolen refresh token is noticed as soon as both the thief
+/// and the user use it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_token: Uuid,
+}
+
+/// Start a new session for a user.
+pub async fn create(db: &SqlitePool, user_id: Uuid) -> Result<Session, sqlx::Error> {
+    let session = Session {
+        id: Uuid::now_v7(),
+        user_id,
+        refresh_token: Uuid::new_v4(),
+    };
+    let validity = format!("+{} seconds", SESSION_VALIDITY.as_secs());
+    sqlx::query!(
+        "DELETE FROM sessions WHERE user_id = ? AND expires_at < datetime('now')",
+        user_id
+    )
+    .execute(db)
+    .await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO sessions (id, user_id, refresh_token, expires_at)
+        VALUES (?, ?, ?, datetime('now', ?))
+        "#,
+        session.id,
+        session.user_id,
+        session.refresh_token,
+        validity
+    )
+    .execute(db)
+    .await?;
+    Ok(session)
+}
+
+/// Whether the session of an access token passed the second factor, `None`
+/// if it is revoked or expired.
+pub async fn second_factor_verified(
+    db: &SqlitePool,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<bool>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT second_factor_verified FROM sessions
+        WHERE id = ? AND user_id = ? AND revoked_at IS NULL AND expires_at > datetime('now')
+        "#,
+        id,
+        user_id
+    )
+    .fetch_optional(db)
+    .await
+}
+
+/// Mark a session as fully authenticated after the second factor.
+pub async fn verify_second_factor(db: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE sessions SET second_factor_verified = TRUE WHERE id = ?",
+        id
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Replace the refresh token of a session.
+///
+/// Requests sent in parallel to a refresh still carry the previous refresh
+/// token, it's accepted for a few seconds and answered with the current one.
+/// Any older refresh token means that it was stolen and revokes the session.
+pub async fn refresh(
+    db: &SqlitePool,
+    claims: &RefreshClaims,
+) -> Result<Option<Session>, sqlx::Error> {
+    let (id, user_id, refresh_token) = (claims.jti(), claims.sub(), claims.rt());
+    let rotated = Uuid::new_v4();
+    let updated = sqlx::query!(
+        r#"
+        UPDATE sessions
+        SET previous_refresh_token = refresh_token, refresh_token = ?, refreshed_at = CURRENT_TIMESTAMP
+        WHERE id = ? AND user_id = ? AND refresh_token = ?
+          AND revoked_at IS NULL AND expires_at > datetime('now')
+        "#,
+        rotated,
+        id,
+        user_id,
+        refresh_token
+    )
+    .execute(db)
+    .await?
+    .rows_affected();
+    if updated == 1 {
+        return Ok(Some(Session {
+            id,
+            user_id,
+            refresh_token: rotated,
+        }));
+    }
+
+    let current = sqlx::query_scalar!(
+        r#"
+        SELECT refresh_token as "refresh_token!: Uuid" FROM sessions
+        WHERE id = ? AND user_id = ? AND previous_refresh_token = ?
+          AND refreshed_at > datetime('now', '-30 seconds')
+          AND revoked_at IS NULL AND expires_at > datetime('now')
+        "#,
+        id,
+        user_id,
+        refresh_token
+    )
+    .fetch_optional(db)
+    .await?;
+    if let Some(current) = current {
+        return Ok(Some(Session {
+            id,
+            user_id,
+            refresh_token: current,
+        }));
+    }
+
+    if revoke(db, id).await? {
+        warn!("Refresh token of session {id} was reused, revoked the session");
+    }
+    Ok(None)
+}
+
+/// Revoke a session, `false` if it was revoked already.
+pub async fn revoke(db: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+    Ok(sqlx::query!(
+        "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL",
+        id
+    )
+    .execute(db)
+    .await?
+    .rows_affected()
+        == 1)
+}
+
+/// Revoke every session of a user and return how many were active.
+pub async fn revoke_all(db: &SqlitePool, user_id: Uuid) -> Result<u64, sqlx::Error> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP
+        WHERE user_id = ? AND revoked_at IS NULL AND expires_at > datetime('now')
+        "#,
+        user_id
+    )
+    .execute(db)
+    .await?
+    .rows_affected())
+}
+
+/// Refresh expired access tokens with the refresh token of their session.
+///
+/// The new access token replaces the one in the request, so the handler is
+/// authenticated with it. The new cookies are sent back unless the handler
+/// sets cookies itself, like `login` and `logout` do.
+pub async fn refresh_session(mut request: Request, next: Next) -> Response {
+    let cookies = CookieJar::from_headers(request.headers());
+    let (Some(jwt_config), Some(db)) = (
+        request.extensions().get::<Arc<JWTConfig>>().cloned(),
+        request.extensions().get::<ReloadableSqlite>().cloned(),
+    ) else {
+        return next.run(request).await;
+    };
+    if cookies.get("jwt").is_some_and(|jwt| {
+        jwt_config
+            .decode_access_token(jwt.value_trimmed())
+            .is_some()
+    }) {
+        return next.run(request).await;
+    }
+    let Some(claims) = cookies
+        .get("refresh")
+        .and_then(|refresh| jwt_config.decode_refresh_token(refresh.value_trimmed()))
+    else {
+        return next.run(request).await;
+    };
+
+    let db = db.get().await.clone();
+    let session = match refresh(&db, &claims).await {
+        Ok(Some(session)) => session,
+        Ok(None) => return next.run(request).await,
+        Err(e) => {
+            error!("Could not refresh session {}: {e}", claims.jti());
+            return next.run(request).await;
+        }
+    };
+    let refreshed = match jwt_config.add_jwt_cookie(CookieJar::new(), &session) {
+        Ok(refreshed) => refreshed,
+        Err(e) => {
+            error!("Could not create tokens for session {}: {e}", session.id);
+            return next.run(request).await;
+        }
+    };
+
+    let mut cookies = cookies;
+    if let Some(jwt) = refreshed.get("jwt") {
+        cookies = cookies.add(jwt.clone());
+    }
+    let request_cookies = cookies
+        .iter()
+        .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+        .collect::<Vec<_>>()
+        .join("; ");
+    if let Ok(value) = HeaderValue::from_str(&request_cookies) {
+        request.headers_mut().insert(header::COOKIE, value);
+    }
+
+    let response = next.run(request).await;
+    if response.headers().contains_key(header::SET_COOKIE) {
+        response
+    } else {
+        (refreshed, response).into_response()
+    }
+}
@@ -0,0 +1,120 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::SqlitePool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{audio, reloadable_sqlite::ReloadableSqlite, song_storage::SongStorage};
+
+/// How long the analysis sleeps when there are no new songs.
+const INTERVAL: Duration = Duration::from_secs(60);
+
+/// The normalized copy of a song is stored next to it.
+pub fn normalized_file_name(act_id: Uuid) -> String {
+    format!("{act_id}.normalized.wav")
+}
+
+/// Analyze new songs in the background until the server stops.
+pub fn spawn(db: ReloadableSqlite, storage: Arc<dyn SongStorage>, normalize: bool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+            let pool = db.get().await.clone();
+            match analyze_pending(&pool, storage.as_ref(), normalize).await {
+                Ok(0) => {}
+                Ok(analyzed) => info!("Analyzed {analyzed} songs"),
+                Err(e) => error!("Song analysis failed: {e}"),
+            }
+        }
+    });
+}
+
+/// Measure the loudness of all songs that weren't analyzed yet and write
+/// their normalized copies if `normalize` is set.
+///
+/// Songs that can't be read are marked as analyzed without loudness, so
+/// they aren't retried until they are uploaded again.
+pub async fn analyze_pending(
+    db: &SqlitePool,
+    storage: &dyn SongStorage,
+    normalize: bool,
+) -> sqlx::Result<usize> {
+    let pending = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", song_file as "song_file!"
+        FROM acts
+        WHERE song_file IS NOT NULL
+          AND (
+            song_analyzed_at IS NULL
+            OR ($1 AND song_loudness_lufs IS NOT NULL AND song_normalized_file IS NULL)
+          )
+        "#,
+        normalize
+    )
+    .fetch_all(db)
+    .await?;
+
+    for act in &pending {
+        let normalized_file = normalized_file_name(act.id);
+        let extension = std::path::Path::new(&act.song_file)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string());
+
+        let analysis = match storage.read(&act.song_file).await {
+            Ok(data) => tokio::task::spawn_blocking(move || {
+                let loudness = audio::measure_loudness(&data, extension.as_deref())?;
+                let normalized = match loudness {
+                    Some(loudness) if normalize => Some(audio::normalized_wav(
+                        &data,
+                        extension.as_deref(),
+                        loudness.normalization_gain_db(),
+                    )?),
+                    _ => None,
+                };
+                Ok::<_, audio::AudioError>((loudness, normalized))
+            })
+            .await
+            .unwrap_or(Err(audio::AudioError::Broken)),
+            Err(e) => {
+                warn!("Could not read {}: {e}", act.song_file);
+                Err(audio::AudioError::Broken)
+            }
+        };
+        let (loudness, normalized) = analysis.unwrap_or_else(|e| {
+            warn!("Could not analyze {}: {e}", act.song_file);
+            (None, None)
+        });
+        let normalized = match normalized {
+            Some(wav) => match storage.put(&normalized_file, wav.into()).await {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Could not store {normalized_file}: {e}");
+                    false
+                }
+            },
+            None => false,
+        };
+
+        let loudness_lufs = loudness.map(|l| l.integrated_lufs);
+        let peak_dbfs = loudness.map(|l| l.peak_dbfs);
+        let normalized_file = normalized.then_some(normalized_file);
+        // The song may have been replaced by another format in the meantime
+        sqlx::query!(
+            r#"
+            UPDATE acts
+            SET song_analyzed_at = datetime('now'), song_loudness_lufs = ?, song_peak_dbfs = ?, song_normalized_file = ?
+            WHERE id = ? AND song_file = ?
+            "#,
+            loudness_lufs,
+            peak_dbfs,
+            normalized_file,
+            act.id,
+            act.song_file,
+        )
+        .execute(db)
+        .await?;
+    }
+
+    Ok(pending.len())
+}
@@ -0,0 +1,292 @@
+mod local;
+mod s3;
+
+use std::{
+    collections::HashSet,
+    future::Future,
+    ops::Range,
+    pin::Pin,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+pub use local::LocalSongStorage;
+pub use s3::{S3Config, S3SongStorage};
+
+use crate::http_server::HttpError;
+
+/// Files this young may belong to an upload that isn't recorded yet.
+pub const GARBAGE_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+/// Orphaned files are moved here instead of being removed.
+pub const ARCHIVE_DIR: &str = "archive";
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StorageError>> + Send + 'a>>;
+pub type ByteStream = BoxStream<'static, std::io::Result<Bytes>>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Die Musikdatei {0} wurde nicht gefunden.")]
+    NotFound(String),
+    #[error("Die Musikdatei konnte nicht gelesen oder geschrieben werden: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Der Speicher der Musikdateien ist nicht erreichbar: {0}")]
+    ObjectStore(#[from] object_store::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<StorageError> for HttpError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::NotFound(_) => HttpError::NotFound,
+            StorageError::Database(e) => HttpError::DBError(e),
+            e => HttpError::ErrorMessages(e.to_string()),
+        }
+    }
+}
+
+/// A file of the song storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub name: String,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// Keeps the songs and their normalized copies.
+///
+/// Names are flat like `{sha256}.mp3`. Archived files are kept in
+/// [`ARCHIVE_DIR`] and are not listed.
+pub trait SongStorage: Send + Sync {
+    /// Write a file at once, no reader ever sees a part of it.
+    fn put<'a>(&'a self, name: &'a str, data: Bytes) -> StorageFuture<'a, ()>;
+    /// `None` if there is no such file.
+    fn head<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Option<StoredObject>>;
+    fn read<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Bytes>;
+    /// Stream a part of a file, the range must lie within the file.
+    fn stream<'a>(&'a self, name: &'a str, range: Range<u64>) -> StorageFuture<'a, ByteStream>;
+    fn list(&self) -> StorageFuture<'_, Vec<StoredObject>>;
+    /// Deleting a missing file succeeds.
+    fn delete<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()>;
+    /// Move a file to [`ARCHIVE_DIR`].
+    fn archive<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()>;
+}
+
+/// Names are single path segments and never hidden, so a name from a
+/// request can't leave the storage.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// Hex SHA-256 of a song.
+pub fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Songs are named after their content, so the same song is stored once
+/// no matter how many acts or versions use it.
+pub fn file_name(sha256: &str, extension: &str) -> String {
+    format!("{sha256}.{extension}")
+}
+
+/// Store a song and return its file name.
+///
+/// A song that is stored already is written again. That makes an orphaned
+/// copy young again, so [`collect_garbage`] keeps it until the upload is
+/// recorded.
+pub async fn store(
+    storage: &dyn SongStorage,
+    sha256: &str,
+    data: Bytes,
+    extension: &str,
+) -> Result<String, StorageError> {
+    let file = file_name(sha256, extension);
+    storage.put(&file, data).await?;
+    Ok(file)
+}
+
+/// What happens to files no act references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanAction {
+    /// Only report them.
+    Report,
+    Remove,
+    /// Move them to [`ARCHIVE_DIR`] in the data path.
+    Archive,
+}
+
+/// Disk usage of the song storage.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct StorageReport {
+    /// Files in the storage, the archive not included.
+    pub files: usize,
+    pub bytes: u64,
+    pub referenced_files: usize,
+    pub referenced_bytes: u64,
+    /// Files no act references, sorted by name.
+    pub orphaned_files: Vec<String>,
+    pub orphaned_bytes: u64,
+    /// Songs from before the content addressed storage that were renamed.
+    pub adopted_files: usize,
+}
+
+/// Rename songs that are still named after their act to their content.
+///
+/// Versions uploaded before the content addressed storage have no hash.
+async fn adopt_legacy_files(
+    db: &SqlitePool,
+    storage: &dyn SongStorage,
+) -> Result<usize, StorageError> {
+    let legacy =
+        sqlx::query_scalar!("SELECT DISTINCT file FROM act_song_versions WHERE sha256 IS NULL")
+            .fetch_all(db)
+            .await?;
+
+    let mut adopted = 0;
+    for legacy_file in legacy {
+        let data = match storage.read(&legacy_file).await {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Could not adopt {legacy_file}: {e}");
+                continue;
+            }
+        };
+        let sha256 = sha256(&data);
+        let extension = std::path::Path::new(&legacy_file)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let file = store(storage, &sha256, data, &extension).await?;
+
+        let mut tx = db.begin().await?;
+        sqlx::query!(
+            "UPDATE act_song_versions SET file = ?, sha256 = ? WHERE file = ?",
+            file,
+            sha256,
+            legacy_file
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE acts SET song_file = ? WHERE song_file = ?",
+            file,
+            legacy_file
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        // The old name is an orphan now and collected below
+        adopted += 1;
+    }
+    Ok(adopted)
+}
+
+/// Whether a file is still unreferenced and old right before it is removed
+/// or archived, an upload may have stored it since the files were listed.
+async fn is_still_orphaned(
+    db: &SqlitePool,
+    storage: &dyn SongStorage,
+    file: &str,
+    min_age: Duration,
+) -> Result<bool, StorageError> {
+    let Some(object) = storage.head(file).await? else {
+        return Ok(false);
+    };
+    let age = SystemTime::now()
+        .duration_since(object.modified)
+        .unwrap_or_default();
+    if age < min_age {
+        return Ok(false);
+    }
+    let referenced = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+          SELECT 1 FROM act_song_versions WHERE file = ?1
+          UNION SELECT 1 FROM acts WHERE song_file = ?1 OR song_normalized_file = ?1
+        ) as "referenced!: bool"
+        "#,
+        file
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(!referenced)
+}
+
+/// Report the disk usage of the songs and handle the files that no act
+/// references anymore.
+///
+/// Acts reference the files of all their versions and their normalized
+/// copy. Files younger than `min_age` are never touched, and every file is
+/// checked again right before it is removed or archived.
+pub async fn collect_garbage(
+    db: &SqlitePool,
+    storage: &dyn SongStorage,
+    action: OrphanAction,
+    min_age: Duration,
+) -> Result<StorageReport, StorageError> {
+    let adopted_files = match action {
+        OrphanAction::Report => 0,
+        OrphanAction::Remove | OrphanAction::Archive => adopt_legacy_files(db, storage).await?,
+    };
+
+    let referenced: HashSet<String> = sqlx::query_scalar!(
+        r#"
+        SELECT file as "file!" FROM act_song_versions
+        UNION SELECT song_file FROM acts WHERE song_file IS NOT NULL
+        UNION SELECT song_normalized_file FROM acts WHERE song_normalized_file IS NOT NULL
+        "#
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut report = StorageReport {
+        adopted_files,
+        ..Default::default()
+    };
+    let now = SystemTime::now();
+    let mut orphans = Vec::new();
+    for object in storage.list().await? {
+        report.files += 1;
+        report.bytes += object.size;
+        if referenced.contains(&object.name) {
+            report.referenced_files += 1;
+            report.referenced_bytes += object.size;
+            continue;
+        }
+        let age = now.duration_since(object.modified).unwrap_or_default();
+        if age >= min_age {
+            orphans.push(object);
+        }
+    }
+    orphans.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for object in orphans {
+        match action {
+            OrphanAction::Report => {}
+            _ if !is_still_orphaned(db, storage, &object.name, min_age).await? => continue,
+            OrphanAction::Remove => storage.delete(&object.name).await?,
+            OrphanAction::Archive => storage.archive(&object.name).await?,
+        }
+        report.orphaned_files.push(object.name);
+        report.orphaned_bytes += object.size;
+    }
+    if !report.orphaned_files.is_empty() {
+        info!(
+            "{:?} {} orphaned songs with {} bytes",
+            action,
+            report.orphaned_files.len(),
+            report.orphaned_bytes
+        );
+    }
+
+    Ok(report)
+}
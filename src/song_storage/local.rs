@@ -0,0 +1,130 @@
+use std::{io::ErrorKind, ops::Range, path::PathBuf};
+
+use bytes::Bytes;
+use futures_util::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use super::{
+    ARCHIVE_DIR, ByteStream, SongStorage, StorageError, StorageFuture, StoredObject, is_valid_name,
+};
+
+/// Keeps the songs in a directory.
+#[derive(Debug, Clone)]
+pub struct LocalSongStorage {
+    path: PathBuf,
+}
+
+impl LocalSongStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, StorageError> {
+        if !is_valid_name(name) {
+            return Err(StorageError::NotFound(name.to_string()));
+        }
+        Ok(self.path.join(name))
+    }
+}
+
+fn not_found(name: &str) -> impl FnOnce(std::io::Error) -> StorageError + '_ {
+    move |e| match e.kind() {
+        ErrorKind::NotFound => StorageError::NotFound(name.to_string()),
+        _ => StorageError::Io(e),
+    }
+}
+
+impl SongStorage for LocalSongStorage {
+    /// The file is written next to its final name and renamed.
+    fn put<'a>(&'a self, name: &'a str, data: Bytes) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path(name)?;
+            tokio::fs::create_dir_all(&self.path).await?;
+            let partial = self.path.join(format!(".{name}.partial"));
+            tokio::fs::write(&partial, data).await?;
+            tokio::fs::rename(&partial, &path).await?;
+            Ok(())
+        })
+    }
+
+    fn head<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Option<StoredObject>> {
+        Box::pin(async move {
+            let metadata = match tokio::fs::metadata(self.path(name)?).await {
+                Ok(metadata) if metadata.is_file() => metadata,
+                Ok(_) => return Ok(None),
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            Ok(Some(StoredObject {
+                name: name.to_string(),
+                size: metadata.len(),
+                modified: metadata.modified()?,
+            }))
+        })
+    }
+
+    fn read<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Bytes> {
+        Box::pin(async move {
+            let data = tokio::fs::read(self.path(name)?)
+                .await
+                .map_err(not_found(name))?;
+            Ok(Bytes::from(data))
+        })
+    }
+
+    fn stream<'a>(&'a self, name: &'a str, range: Range<u64>) -> StorageFuture<'a, ByteStream> {
+        Box::pin(async move {
+            let mut file = tokio::fs::File::open(self.path(name)?)
+                .await
+                .map_err(not_found(name))?;
+            file.seek(std::io::SeekFrom::Start(range.start)).await?;
+            Ok(ReaderStream::new(file.take(range.end - range.start)).boxed())
+        })
+    }
+
+    /// Hidden files are partial writes and directories hold the archive.
+    fn list(&self) -> StorageFuture<'_, Vec<StoredObject>> {
+        Box::pin(async move {
+            let mut entries = match tokio::fs::read_dir(&self.path).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+                Err(e) => return Err(e.into()),
+            };
+            let mut objects = vec![];
+            while let Some(entry) = entries.next_entry().await? {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let metadata = entry.metadata().await?;
+                if !metadata.is_file() || !is_valid_name(&name) {
+                    continue;
+                }
+                objects.push(StoredObject {
+                    name,
+                    size: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+            Ok(objects)
+        })
+    }
+
+    fn delete<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(name)?).await {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn archive<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let archive = self.path.join(ARCHIVE_DIR);
+            tokio::fs::create_dir_all(&archive).await?;
+            tokio::fs::rename(self.path(name)?, archive.join(name))
+                .await
+                .map_err(not_found(name))?;
+            Ok(())
+        })
+    }
+}
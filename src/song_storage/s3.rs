@@ -0,0 +1,156 @@
+use std::{ops::Range, time::SystemTime};
+
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use object_store::{
+    Error as ObjectStoreError, GetOptions, ObjectStore, ObjectStoreExt, PutPayload,
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+};
+
+use super::{
+    ARCHIVE_DIR, ByteStream, SongStorage, StorageError, StorageFuture, StoredObject, is_valid_name,
+};
+
+/// Connection to an S3 compatible bucket, e.g. of AWS or MinIO.
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Leave empty for AWS, e.g. `http://localhost:9000` for MinIO.
+    pub endpoint: Option<String>,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Keys of the songs start with this folder, so a bucket can be shared.
+    pub prefix: Option<String>,
+}
+
+/// Keeps the songs in an S3 compatible bucket.
+#[derive(Debug)]
+pub struct S3SongStorage {
+    store: AmazonS3,
+    prefix: Path,
+}
+
+impl S3SongStorage {
+    pub fn new(config: S3Config) -> Result<Self, StorageError> {
+        let mut builder = AmazonS3Builder::new()
+            .with_region(config.region)
+            .with_bucket_name(config.bucket)
+            .with_access_key_id(config.access_key_id)
+            .with_secret_access_key(config.secret_access_key);
+        if let Some(endpoint) = config.endpoint {
+            builder = builder
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_endpoint(endpoint);
+        }
+        Ok(Self {
+            store: builder.build()?,
+            prefix: Path::from(config.prefix.unwrap_or_default()),
+        })
+    }
+
+    fn path(&self, name: &str) -> Result<Path, StorageError> {
+        if !is_valid_name(name) {
+            return Err(StorageError::NotFound(name.to_string()));
+        }
+        Ok(self.prefix.clone().join(name))
+    }
+}
+
+fn not_found(name: &str) -> impl FnOnce(ObjectStoreError) -> StorageError + '_ {
+    move |e| match e {
+        ObjectStoreError::NotFound { .. } => StorageError::NotFound(name.to_string()),
+        e => StorageError::ObjectStore(e),
+    }
+}
+
+impl SongStorage for S3SongStorage {
+    fn put<'a>(&'a self, name: &'a str, data: Bytes) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            self.store
+                .put(&self.path(name)?, PutPayload::from(data))
+                .await?;
+            Ok(())
+        })
+    }
+
+    fn head<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Option<StoredObject>> {
+        Box::pin(async move {
+            match self.store.head(&self.path(name)?).await {
+                Ok(meta) => Ok(Some(StoredObject {
+                    name: name.to_string(),
+                    size: meta.size,
+                    modified: SystemTime::from(meta.last_modified),
+                })),
+                Err(ObjectStoreError::NotFound { .. }) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn read<'a>(&'a self, name: &'a str) -> StorageFuture<'a, Bytes> {
+        Box::pin(async move {
+            let result = self
+                .store
+                .get(&self.path(name)?)
+                .await
+                .map_err(not_found(name))?;
+            Ok(result.bytes().await?)
+        })
+    }
+
+    fn stream<'a>(&'a self, name: &'a str, range: Range<u64>) -> StorageFuture<'a, ByteStream> {
+        Box::pin(async move {
+            let options = GetOptions {
+                range: Some(range.into()),
+                ..Default::default()
+            };
+            let result = self
+                .store
+                .get_opts(&self.path(name)?, options)
+                .await
+                .map_err(not_found(name))?;
+            Ok(result.into_stream().map_err(std::io::Error::other).boxed())
+        })
+    }
+
+    fn list(&self) -> StorageFuture<'_, Vec<StoredObject>> {
+        Box::pin(async move {
+            let listing = self.store.list_with_delimiter(Some(&self.prefix)).await?;
+            Ok(listing
+                .objects
+                .into_iter()
+                .filter_map(|meta| {
+                    let name = meta.location.filename()?.to_string();
+                    is_valid_name(&name).then(|| StoredObject {
+                        name,
+                        size: meta.size,
+                        modified: SystemTime::from(meta.last_modified),
+                    })
+                })
+                .collect())
+        })
+    }
+
+    fn delete<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match self.store.delete(&self.path(name)?).await {
+                Err(ObjectStoreError::NotFound { .. }) | Ok(()) => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    /// S3 has no rename, the file is copied and deleted.
+    fn archive<'a>(&'a self, name: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let archived = self.prefix.clone().join(ARCHIVE_DIR).join(name);
+            self.store
+                .rename(&self.path(name)?, &archived)
+                .await
+                .map_err(not_found(name))?;
+            Ok(())
+        })
+    }
+}
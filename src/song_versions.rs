@@ -0,0 +1,130 @@
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::audio::AudioInfo;
+
+/// An uploaded song of an act.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, utoipa::ToSchema)]
+pub struct SongVersion {
+    pub id: i64,
+    /// Counts the uploads of the act, starting with 1.
+    pub version: i64,
+    pub file: String,
+    pub original_file_name: Option<String>,
+    pub sha256: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub codec: Option<String>,
+    pub bitrate: Option<i64>,
+    pub checked: bool,
+    pub uploaded_by: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub uploaded_at: OffsetDateTime,
+    /// The act uses this version.
+    pub current: bool,
+}
+
+/// A song that was just stored in the data path.
+#[derive(Debug)]
+pub struct NewSongVersion<'a> {
+    pub act_id: Uuid,
+    pub file: &'a str,
+    pub original_file_name: &'a str,
+    pub sha256: &'a str,
+    pub song: &'a AudioInfo,
+    pub uploaded_by: Uuid,
+}
+
+/// Record an uploaded song and make it the song of the act.
+///
+/// Returns the id and the version, which counts the uploads of the act. The
+/// version is taken in the insert, so concurrent uploads get different ones.
+pub async fn create(db: &SqlitePool, new: NewSongVersion<'_>) -> sqlx::Result<(i64, i64)> {
+    let mut tx = db.begin().await?;
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO act_song_versions (
+          act_id, version, file, original_file_name, sha256, duration_seconds, codec, bitrate, uploaded_by
+        )
+        SELECT ?, COALESCE(MAX(version), 0) + 1, ?, ?, ?, ?, ?, ?, ?
+        FROM act_song_versions WHERE act_id = ?
+        RETURNING id as "id!: i64", version as "version!: i64"
+        "#,
+        new.act_id,
+        new.file,
+        new.original_file_name,
+        new.sha256,
+        new.song.duration_seconds,
+        new.song.codec,
+        new.song.bitrate,
+        new.uploaded_by,
+        new.act_id,
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    activate(&mut tx, created.id).await?;
+    tx.commit().await?;
+    Ok((created.id, created.version))
+}
+
+/// Make a version the song of its act.
+///
+/// The check comes with the version and the song is analyzed again.
+pub async fn activate(db: &mut sqlx::SqliteConnection, version_id: i64) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE acts
+        SET song_version_id = v.id, song_file = v.file, song_file_name = v.original_file_name,
+            song_duration_seconds = v.duration_seconds, song_codec = v.codec, song_bitrate = v.bitrate,
+            song_checked = v.checked,
+            song_analyzed_at = NULL, song_loudness_lufs = NULL, song_peak_dbfs = NULL, song_normalized_file = NULL
+        FROM act_song_versions v
+        WHERE v.id = ? AND acts.id = v.act_id
+        "#,
+        version_id
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// The id of a version of an act, `None` if the act has no such version.
+pub async fn find(db: &SqlitePool, act_id: Uuid, version: i64) -> sqlx::Result<Option<i64>> {
+    sqlx::query_scalar!(
+        r#"SELECT id as "id!: i64" FROM act_song_versions WHERE act_id = ? AND version = ?"#,
+        act_id,
+        version
+    )
+    .fetch_optional(db)
+    .await
+}
+
+/// All versions of the song of an act, the newest first.
+pub async fn list(db: &SqlitePool, act_id: Uuid) -> sqlx::Result<Vec<SongVersion>> {
+    sqlx::query_as!(
+        SongVersion,
+        r#"
+        SELECT
+          v.id as "id!: i64",
+          v.version as "version!: i64",
+          v.file,
+          v.original_file_name,
+          v.sha256,
+          v.duration_seconds,
+          v.codec,
+          v.bitrate,
+          v.checked as "checked!: bool",
+          users.name as "uploaded_by?: String",
+          v.uploaded_at as "uploaded_at!: OffsetDateTime",
+          COALESCE(acts.song_version_id = v.id, FALSE) as "current!: bool"
+        FROM act_song_versions v
+          JOIN acts ON acts.id = v.act_id
+          LEFT JOIN users ON users.id = v.uploaded_by
+        WHERE v.act_id = ?
+        ORDER BY v.version DESC
+        "#,
+        act_id
+    )
+    .fetch_all(db)
+    .await
+}
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use time::{
+    Date, OffsetDateTime, UtcOffset,
+    macros::{format_description, offset},
+};
+
+use crate::{
+    http_server::routes::http_types::{participant_clubs, participant_names},
+    timeplan::{Timeplan, TimeplanEntry},
+};
+
+/// Start times are exported in the local time of the competition.
+pub(crate) const LOCAL_OFFSET: UtcOffset = offset!(+1);
+
+/// A column of the startlist export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StartlistColumn {
+    StartNumber,
+    Day,
+    Time,
+    Category,
+    ActName,
+    Names,
+    Club,
+    SongFile,
+}
+
+impl StartlistColumn {
+    pub const ALL: [StartlistColumn; 8] = [
+        Self::StartNumber,
+        Self::Day,
+        Self::Time,
+        Self::Category,
+        Self::ActName,
+        Self::Names,
+        Self::Club,
+        Self::SongFile,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StartNumber => "Startnummer",
+            Self::Day => "Tag",
+            Self::Time => "Startzeit",
+            Self::Category => "Wettbewerb",
+            Self::ActName => "Kürtitel",
+            Self::Names => "Name",
+            Self::Club => "Verein",
+            Self::SongFile => "Musikdatei",
+        }
+    }
+}
+
+impl FromStr for StartlistColumn {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(value.trim().to_string()))
+            .map_err(|_| format!("Unbekannte Spalte: {value}"))
+    }
+}
+
+/// Parse a comma separated list of columns, all columns if none are given.
+pub fn parse_columns(columns: Option<&str>) -> Result<Vec<StartlistColumn>, String> {
+    match columns {
+        Some(columns) if !columns.trim().is_empty() => {
+            columns.split(',').map(StartlistColumn::from_str).collect()
+        }
+        _ => Ok(StartlistColumn::ALL.to_vec()),
+    }
+}
+
+/// An act of the startlist with all exportable values.
+struct StartlistRow {
+    start: OffsetDateTime,
+    start_number: usize,
+    category: String,
+    act_name: String,
+    names: String,
+    club: String,
+    song_file: String,
+}
+
+impl StartlistRow {
+    fn day(&self) -> Date {
+        self.start.date()
+    }
+
+    fn value(&self, column: StartlistColumn) -> String {
+        match column {
+            StartlistColumn::StartNumber => self.start_number.to_string(),
+            StartlistColumn::Day => self
+                .start
+                .format(format_description!("[day].[month].[year]"))
+                .unwrap_or_default(),
+            StartlistColumn::Time => self
+                .start
+                .format(format_description!("[hour]:[minute]"))
+                .unwrap_or_default(),
+            StartlistColumn::Category => self.category.clone(),
+            StartlistColumn::ActName => self.act_name.clone(),
+            StartlistColumn::Names => self.names.clone(),
+            StartlistColumn::Club => self.club.clone(),
+            StartlistColumn::SongFile => self.song_file.clone(),
+        }
+    }
+}
+
+/// All acts of the timeplan in starting order, numbered from 1.
+fn rows(timeplan: &Timeplan) -> Vec<StartlistRow> {
+    timeplan
+        .items
+        .iter()
+        .filter_map(|item| match &item.timeplan_entry {
+            TimeplanEntry::Category {
+                description, acts, ..
+            } => Some(acts.iter().map(move |act| (description, act))),
+            TimeplanEntry::Custom { .. } => None,
+        })
+        .flatten()
+        .enumerate()
+        .map(|(index, (description, act))| StartlistRow {
+            start: act.predicted_start.to_offset(LOCAL_OFFSET),
+            start_number: index + 1,
+            category: description.clone(),
+            act_name: act.name.clone(),
+            names: participant_names(&act.participants),
+            club: participant_clubs(&act.participants),
+            song_file: act.song_file.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Render the startlist as CSV following RFC 4180.
+///
+/// The file starts with a byte order mark, otherwise Excel does not detect
+/// the UTF-8 encoding of umlauts.
+pub fn render_csv(timeplan: &Timeplan, columns: &[StartlistColumn]) -> csv::Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer("\u{feff}".as_bytes().to_vec());
+    writer.write_record(columns.iter().map(StartlistColumn::label))?;
+    for row in rows(timeplan) {
+        writer.write_record(columns.iter().map(|column| row.value(*column)))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// Render the startlist as an Excel workbook with one sheet per day.
+pub fn render_xlsx(timeplan: &Timeplan, columns: &[StartlistColumn]) -> Result<Vec<u8>, XlsxError> {
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+    let rows = rows(timeplan);
+    for day in rows.chunk_by(|a, b| a.day() == b.day()) {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(day[0].value(StartlistColumn::Day))?;
+        for (col, column) in columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, column.label(), &bold)?;
+        }
+        for (row, act) in day.iter().enumerate() {
+            for (col, column) in columns.iter().enumerate() {
+                let (row, col) = (row as u32 + 1, col as u16);
+                match column {
+                    StartlistColumn::StartNumber => {
+                        sheet.write_number(row, col, act.start_number as f64)?
+                    }
+                    _ => sheet.write_string(row, col, act.value(*column))?,
+                };
+            }
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+    }
+    if rows.is_empty() {
+        let sheet = workbook.add_worksheet();
+        for (col, column) in columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, column.label(), &bold)?;
+        }
+    }
+    workbook.save_to_buffer()
+}
@@ -1,9 +1,9 @@
-use std::sync::Arc;
-
 use axum::{extract::FromRequestParts, http::request::Parts};
-use time::OffsetDateTime;
 
-use crate::http_server::extractor::auth::Auth;
+use crate::{
+    competition::ActiveCompetition,
+    http_server::{HttpError, extractor::auth::Auth},
+};
 
 #[derive(Debug, Clone, Copy, serde::Serialize, utoipa::ToSchema)]
 pub struct Capabilities {
@@ -14,34 +14,11 @@ pub struct Capabilities {
     pub can_upload_music: bool,
 }
 
-#[derive(Debug, Clone)]
-pub struct StatusOptions {
-    pub start_register_date: OffsetDateTime,
-    pub end_register_date: OffsetDateTime,
-    pub end_music_upload_date: OffsetDateTime,
-}
-
-impl StatusOptions {
-    pub fn get_system_status(&self) -> Capabilities {
-        let now = OffsetDateTime::now_utc();
-
-        let in_register_period = now >= self.start_register_date && now <= self.end_register_date;
-
-        Capabilities {
-            can_register: in_register_period,
-            can_create_club: in_register_period,
-            can_register_starter: in_register_period,
-            can_register_judge: in_register_period,
-            can_upload_music: now <= self.end_music_upload_date,
-        }
-    }
-}
-
 impl<S> FromRequestParts<S> for Capabilities
 where
     S: Send + Sync,
 {
-    type Rejection = ();
+    type Rejection = HttpError;
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         if let Ok(auth) = Auth::from_request_parts(parts, state).await
             && auth.is_admin()
@@ -54,7 +31,7 @@ where
                 can_upload_music: true,
             });
         }
-        let status_options = parts.extensions.get::<Arc<StatusOptions>>().unwrap();
-        Ok(status_options.get_system_status())
+        let competition = ActiveCompetition::from_request_parts(parts, state).await?;
+        Ok(competition.get_system_status())
     }
 }
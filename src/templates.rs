@@ -13,3 +13,20 @@ pub struct PasswordResetMail<'a> {
     pub name: &'a str,
     pub reset_link: &'a str,
 }
+
+#[derive(Template)]
+#[template(path = "emails/club-invitation-mail.j2")]
+pub struct ClubInvitationMail<'a> {
+    pub inviter: &'a str,
+    pub club_name: &'a str,
+    pub role: &'a str,
+    pub invitation_link: &'a str,
+}
+
+#[derive(Template)]
+#[template(path = "emails/account-deletion-notice-mail.j2")]
+pub struct AccountDeletionNoticeMail<'a> {
+    pub name: &'a str,
+    pub verify_link: &'a str,
+    pub days: u64,
+}
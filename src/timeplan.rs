@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use sqlx::SqlitePool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::http_server::{HttpError, routes::http_types::ActParticipant};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TimeplanError {
+    #[error("No first entry")]
+    Empty,
+    #[error("Earliest start time is not set")]
+    MissingStartTime,
+    #[error("Invalid timeplan entry")]
+    InvalidEntry,
+    #[error("Unknown category: {0}")]
+    UnknownCategory(String),
+}
+
+impl From<TimeplanError> for HttpError {
+    fn from(e: TimeplanError) -> Self {
+        HttpError::ErrorMessages(e.to_string())
+    }
+}
+
+/// An entry of the timeplan as it is stored.
+#[derive(Debug, Clone, Default)]
+pub struct PlanEntry {
+    pub id: i64,
+    pub earliest_start_time: Option<OffsetDateTime>,
+    pub duration_seconds: Option<i64>,
+    pub label: Option<String>,
+    pub category: Option<String>,
+    pub started_at: Option<OffsetDateTime>,
+    pub ended_at: Option<OffsetDateTime>,
+}
+
+/// A category with its durations and its acts in starting order.
+#[derive(Debug, Clone, Default)]
+pub struct PlanCategory {
+    pub description: String,
+    pub order: i64,
+    pub einfahrzeit_seconds: i64,
+    pub act_duration_seconds: i64,
+    pub judge_duration_seconds: i64,
+    pub acts: Vec<PlanAct>,
+}
+
+/// An act as it is stored.
+#[derive(Debug, Clone, Default)]
+pub struct PlanAct {
+    pub id: Uuid,
+    pub name: String,
+    pub is_pair: bool,
+    pub participants: Vec<ActParticipant>,
+    pub song_file: Option<String>,
+    pub started_at: Option<OffsetDateTime>,
+    pub ended_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct TimeplanAct {
+    pub status: TimeplanItemStatus,
+    pub id: Uuid,
+    pub name: String,
+    pub is_pair: bool,
+    pub participants: Vec<ActParticipant>,
+    pub song_file: Option<String>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub ended_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601")]
+    pub predicted_start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub predicted_end: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub planned_start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub planned_end: OffsetDateTime,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub enum TimeplanEntry {
+    Category {
+        name: String,
+        description: String,
+        duration_seconds: i64,
+        order: i64,
+        einfahrzeit_seconds: i64,
+        act_duration_seconds: i64,
+        judge_duration_seconds: i64,
+        acts: Vec<TimeplanAct>,
+    },
+    Custom {
+        label: String,
+        duration_seconds: i64,
+    },
+}
+
+impl TimeplanEntry {
+    pub fn duration(&self) -> Duration {
+        match self {
+            TimeplanEntry::Category {
+                duration_seconds, ..
+            } => Duration::seconds(*duration_seconds),
+            TimeplanEntry::Custom {
+                duration_seconds, ..
+            } => Duration::seconds(*duration_seconds),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema, Eq, PartialEq)]
+pub enum TimeplanItemStatus {
+    Planned,
+    Started,
+    Ended,
+}
+
+impl TimeplanItemStatus {
+    fn new(
+        started_at: Option<OffsetDateTime>,
+        ended_at: Option<OffsetDateTime>,
+    ) -> Result<Self, TimeplanError> {
+        match (started_at, ended_at) {
+            (Some(_), Some(_)) => Ok(Self::Ended),
+            (Some(_), None) => Ok(Self::Started),
+            (None, None) => Ok(Self::Planned),
+            (None, Some(_)) => Err(TimeplanError::InvalidEntry),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct TimeplanItem {
+    pub id: i64,
+    pub status: TimeplanItemStatus,
+    #[serde(with = "time::serde::iso8601")]
+    pub predicted_start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub predicted_end: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub planned_start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub planned_end: OffsetDateTime,
+    pub planned_duration: i64,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601::option")]
+    pub ended_at: Option<OffsetDateTime>,
+    pub timeplan_entry: TimeplanEntry,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct Timeplan {
+    /// Seconds the competition is behind the plan.
+    pub offset: i64,
+    pub items: Vec<TimeplanItem>,
+}
+
+/// Compute the planned and the predicted times of all timeplan entries.
+///
+/// The plan starts at the `earliest_start_time` of the first entry and
+/// entries with an `earliest_start_time` never start before it. The
+/// prediction additionally never starts anything before `now` and follows
+/// the recorded start and end times of entries and acts. The offset of the
+/// timeplan is the delay of the last started entry or act.
+pub fn predict(
+    now: OffsetDateTime,
+    entries: &[PlanEntry],
+    categories: &HashMap<String, PlanCategory>,
+) -> Result<Timeplan, TimeplanError> {
+    let first_entry = entries.first().ok_or(TimeplanError::Empty)?;
+    let mut next_planned_start_time = first_entry
+        .earliest_start_time
+        .ok_or(TimeplanError::MissingStartTime)?;
+    let mut next_predicted_start_time = now.max(next_planned_start_time);
+
+    let mut timeplan = Timeplan {
+        offset: (next_predicted_start_time - next_planned_start_time).whole_seconds(),
+        items: vec![],
+    };
+
+    let mut general_offset = None;
+
+    for plan_entry in entries {
+        let mut entry_offset = None;
+        if let Some(earliest_start_time) = plan_entry.earliest_start_time {
+            next_planned_start_time = earliest_start_time.max(next_planned_start_time);
+            next_predicted_start_time = plan_entry
+                .started_at
+                .unwrap_or(earliest_start_time.max(next_predicted_start_time));
+        }
+
+        let item_planned_start_time = next_planned_start_time;
+        let item_predicted_start_time = next_predicted_start_time;
+
+        let entry = match (
+            plan_entry.duration_seconds,
+            &plan_entry.label,
+            &plan_entry.category,
+        ) {
+            (_, _, Some(name)) => {
+                let cat = categories
+                    .get(name)
+                    .ok_or_else(|| TimeplanError::UnknownCategory(name.clone()))?;
+                let act_duration = Duration::seconds(cat.act_duration_seconds);
+                let judge_duration = Duration::seconds(cat.judge_duration_seconds);
+
+                next_predicted_start_time += Duration::seconds(cat.einfahrzeit_seconds);
+                next_planned_start_time += Duration::seconds(cat.einfahrzeit_seconds);
+
+                let mut timeplan_acts = vec![];
+                for act in &cat.acts {
+                    if let (Some(started_at), None) = (act.started_at, act.ended_at) {
+                        entry_offset = Some((started_at - next_planned_start_time).whole_seconds());
+                    }
+
+                    next_predicted_start_time = now.max(next_predicted_start_time);
+
+                    if let Some(started_at) = act.started_at {
+                        next_predicted_start_time = started_at;
+                    }
+
+                    timeplan_acts.push(TimeplanAct {
+                        status: TimeplanItemStatus::new(act.started_at, act.ended_at)?,
+                        id: act.id,
+                        name: act.name.clone(),
+                        is_pair: act.is_pair,
+                        participants: act.participants.clone(),
+                        song_file: act.song_file.clone(),
+                        started_at: act.started_at,
+                        ended_at: act.ended_at,
+                        predicted_start: next_predicted_start_time,
+                        predicted_end: act
+                            .ended_at
+                            .unwrap_or(next_predicted_start_time + act_duration),
+                        planned_start: next_planned_start_time,
+                        planned_end: next_planned_start_time + act_duration,
+                    });
+                    next_predicted_start_time += act_duration + judge_duration;
+                    next_planned_start_time += act_duration + judge_duration;
+                }
+                TimeplanEntry::Category {
+                    name: name.clone(),
+                    description: cat.description.clone(),
+                    duration_seconds: cat.einfahrzeit_seconds
+                        + (cat.act_duration_seconds + cat.judge_duration_seconds)
+                            * timeplan_acts.len() as i64,
+                    order: cat.order,
+                    einfahrzeit_seconds: cat.einfahrzeit_seconds,
+                    act_duration_seconds: cat.act_duration_seconds,
+                    judge_duration_seconds: cat.judge_duration_seconds,
+                    acts: timeplan_acts,
+                }
+            }
+            (Some(duration), Some(label), None) => {
+                next_predicted_start_time += Duration::seconds(duration);
+                next_planned_start_time += Duration::seconds(duration);
+                TimeplanEntry::Custom {
+                    label: label.clone(),
+                    duration_seconds: duration,
+                }
+            }
+            _ => return Err(TimeplanError::InvalidEntry),
+        };
+
+        let item = TimeplanItem {
+            id: plan_entry.id,
+            status: TimeplanItemStatus::new(plan_entry.started_at, plan_entry.ended_at)?,
+            predicted_start: plan_entry.started_at.unwrap_or(item_predicted_start_time),
+            predicted_end: plan_entry
+                .ended_at
+                .unwrap_or(item_predicted_start_time + entry.duration()),
+            planned_start: item_planned_start_time,
+            planned_end: item_planned_start_time + entry.duration(),
+            planned_duration: entry.duration().whole_seconds(),
+            started_at: plan_entry.started_at,
+            ended_at: plan_entry.ended_at,
+            timeplan_entry: entry,
+        };
+
+        if entry_offset.is_none()
+            && let (Some(started_at), None) = (item.started_at, item.ended_at)
+        {
+            entry_offset = Some((started_at - item.planned_start).whole_seconds());
+        }
+
+        if let Some(offset) = entry_offset {
+            general_offset = Some(offset);
+        } else if let (TimeplanItemStatus::Started, Some(started_at)) =
+            (&item.status, item.started_at)
+        {
+            general_offset = Some((started_at - item.planned_start).whole_seconds());
+        }
+
+        timeplan.items.push(item);
+    }
+
+    timeplan.offset = general_offset.unwrap_or(timeplan.offset);
+
+    Ok(timeplan)
+}
+
+/// Predict the start and end of all timeplan entries of a competition.
+pub async fn predict_competition(
+    db: &SqlitePool,
+    competition_id: Uuid,
+) -> Result<Timeplan, HttpError> {
+    let entries: Vec<PlanEntry> = sqlx::query_as!(
+        PlanEntry,
+        r#"
+        SELECT
+            id,
+            earliest_start_time,
+            duration_seconds,
+            label,
+            category,
+            started_at,
+            ended_at
+        FROM
+            timeplan
+        WHERE
+            competition_id = $1
+        ORDER BY
+            id
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut categories: HashMap<String, PlanCategory> = sqlx::query!(
+        r#"
+        SELECT
+            name,
+            description as "description!",
+            "order" as "order!",
+            einfahrzeit_seconds,
+            act_duration_seconds,
+            judge_duration_seconds
+        FROM
+            categories
+        WHERE
+            competition_id = $1
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|cat| {
+        (
+            cat.name,
+            PlanCategory {
+                description: cat.description,
+                order: cat.order,
+                einfahrzeit_seconds: cat.einfahrzeit_seconds,
+                act_duration_seconds: cat.act_duration_seconds,
+                judge_duration_seconds: cat.judge_duration_seconds,
+                acts: vec![],
+            },
+        )
+    })
+    .collect();
+
+    for act in sqlx::query!(
+        r#"
+        SELECT
+            id as "id!: Uuid",
+            name,
+            is_pair,
+            song_file,
+            participants as "participants!: sqlx::types::Json<Vec<ActParticipant>>",
+            category as "category!",
+            started_at,
+            ended_at
+        FROM
+            view_act
+        WHERE
+            competition_id = $1
+            AND category IS NOT NULL
+        ORDER BY
+            "order"
+        "#,
+        competition_id
+    )
+    .fetch_all(db)
+    .await?
+    {
+        if let Some(category) = categories.get_mut(&act.category) {
+            category.acts.push(PlanAct {
+                id: act.id,
+                name: act.name,
+                is_pair: act.is_pair,
+                participants: act.participants.0,
+                song_file: act.song_file,
+                started_at: act.started_at,
+                ended_at: act.ended_at,
+            });
+        }
+    }
+
+    Ok(predict(
+        time::OffsetDateTime::now_utc(),
+        &entries,
+        &categories,
+    )?)
+}
@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+
+use sqlx::{SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::live::LiveChange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, sqlx::Type, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum TimeplanEventKind {
+    EntryStarted,
+    EntryEnded,
+    ActStarted,
+    ActEnded,
+    /// Reverts all changes of the step.
+    Undo,
+    /// Restores the changes of an undone step.
+    Redo,
+}
+
+/// An event of the append-only timeplan history.
+///
+/// Changes carry the step of the forward they belong to, undo and redo
+/// events carry the step they revert or restore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeplanEvent {
+    pub id: i64,
+    pub step: i64,
+    pub kind: TimeplanEventKind,
+    pub timeplan_id: Option<i64>,
+    pub act_id: Option<Uuid>,
+}
+
+impl TimeplanEvent {
+    fn is_change(&self) -> bool {
+        !matches!(self.kind, TimeplanEventKind::Undo | TimeplanEventKind::Redo)
+    }
+}
+
+/// The state of the history after replaying all of its events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    /// The steps in effect, oldest first.
+    pub applied: Vec<i64>,
+    /// The undone steps, the next one to redo last.
+    pub undone: Vec<i64>,
+    /// The changes of the applied steps in the order they were made.
+    pub changes: Vec<TimeplanEvent>,
+}
+
+impl History {
+    pub fn undo_target(&self) -> Option<i64> {
+        self.applied.last().copied()
+    }
+
+    pub fn redo_target(&self) -> Option<i64> {
+        self.undone.last().copied()
+    }
+}
+
+/// Replay the events in order.
+///
+/// A new step after an undo discards the undone steps, like in an editor.
+pub fn replay(events: &[TimeplanEvent]) -> History {
+    let mut history = History::default();
+    for event in events {
+        match event.kind {
+            TimeplanEventKind::Undo => {
+                if let Some(index) = history.applied.iter().position(|&s| s == event.step) {
+                    history.applied.remove(index);
+                    history.undone.push(event.step);
+                }
+            }
+            TimeplanEventKind::Redo => {
+                if let Some(index) = history.undone.iter().position(|&s| s == event.step) {
+                    history.undone.remove(index);
+                    history.applied.push(event.step);
+                }
+            }
+            _ => {
+                if history.applied.last() != Some(&event.step) {
+                    history.applied.push(event.step);
+                    history.undone.clear();
+                }
+            }
+        }
+    }
+
+    let applied: HashSet<_> = history.applied.iter().copied().collect();
+    history.changes = events
+        .iter()
+        .filter(|event| event.is_change() && applied.contains(&event.step))
+        .cloned()
+        .collect();
+    history
+}
+
+async fn load(
+    conn: &mut SqliteConnection,
+    competition_id: Uuid,
+) -> Result<Vec<TimeplanEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        TimeplanEvent,
+        r#"
+        SELECT
+            id as "id!",
+            step,
+            kind as "kind: TimeplanEventKind",
+            timeplan_id,
+            act_id as "act_id: Uuid"
+        FROM timeplan_events
+        WHERE competition_id = ?
+        ORDER BY id
+        "#,
+        competition_id
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Record the changes of a forward as a new step.
+///
+/// Changes that aren't part of the progress are ignored.
+pub async fn record(
+    db: &SqlitePool,
+    competition_id: Uuid,
+    changes: &[LiveChange],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let step = sqlx::query_scalar!(
+        r#"SELECT COALESCE(MAX(step), 0) + 1 as "step!: i64" FROM timeplan_events WHERE competition_id = ?"#,
+        competition_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for change in changes {
+        let (kind, timeplan_id, act_id) = match *change {
+            LiveChange::ActStarted { act_id } => {
+                (TimeplanEventKind::ActStarted, None, Some(act_id))
+            }
+            LiveChange::ActEnded { act_id } => (TimeplanEventKind::ActEnded, None, Some(act_id)),
+            LiveChange::EntryStarted { timeplan_id } => {
+                (TimeplanEventKind::EntryStarted, Some(timeplan_id), None)
+            }
+            LiveChange::EntryEnded { timeplan_id } => {
+                (TimeplanEventKind::EntryEnded, Some(timeplan_id), None)
+            }
+            LiveChange::TimeplanChanged => continue,
+        };
+        sqlx::query!(
+            "INSERT INTO timeplan_events (competition_id, step, kind, timeplan_id, act_id) VALUES (?, ?, ?, ?, ?)",
+            competition_id,
+            step,
+            kind,
+            timeplan_id,
+            act_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    project(&mut tx, competition_id).await?;
+    tx.commit().await
+}
+
+/// Undo the last applied step, returns it if there was one.
+pub async fn undo(db: &SqlitePool, competition_id: Uuid) -> Result<Option<i64>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let history = replay(&load(&mut tx, competition_id).await?);
+    let Some(step) = history.undo_target() else {
+        return Ok(None);
+    };
+    append(&mut tx, competition_id, TimeplanEventKind::Undo, step).await?;
+    tx.commit().await?;
+    Ok(Some(step))
+}
+
+/// Redo the last undone step, returns it if there was one.
+pub async fn redo(db: &SqlitePool, competition_id: Uuid) -> Result<Option<i64>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let history = replay(&load(&mut tx, competition_id).await?);
+    let Some(step) = history.redo_target() else {
+        return Ok(None);
+    };
+    append(&mut tx, competition_id, TimeplanEventKind::Redo, step).await?;
+    tx.commit().await?;
+    Ok(Some(step))
+}
+
+async fn append(
+    conn: &mut SqliteConnection,
+    competition_id: Uuid,
+    kind: TimeplanEventKind,
+    step: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "INSERT INTO timeplan_events (competition_id, step, kind) VALUES (?, ?, ?)",
+        competition_id,
+        step,
+        kind,
+    )
+    .execute(&mut *conn)
+    .await?;
+    project(conn, competition_id).await
+}
+
+/// Derive started_at and ended_at of the timeplan and the acts from the
+/// history.
+async fn project(conn: &mut SqliteConnection, competition_id: Uuid) -> Result<(), sqlx::Error> {
+    let history = replay(&load(conn, competition_id).await?);
+
+    sqlx::query!(
+        "UPDATE timeplan SET started_at = NULL, ended_at = NULL WHERE competition_id = ?",
+        competition_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query!(
+        "UPDATE acts SET started_at = NULL, ended_at = NULL WHERE competition_id = ?",
+        competition_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for event in history.changes {
+        match event.kind {
+            TimeplanEventKind::EntryStarted => sqlx::query!(
+                "UPDATE timeplan SET started_at = (SELECT created_at FROM timeplan_events WHERE id = ?) WHERE id = ?",
+                event.id,
+                event.timeplan_id,
+            ),
+            TimeplanEventKind::EntryEnded => sqlx::query!(
+                "UPDATE timeplan SET ended_at = (SELECT created_at FROM timeplan_events WHERE id = ?) WHERE id = ?",
+                event.id,
+                event.timeplan_id,
+            ),
+            TimeplanEventKind::ActStarted => sqlx::query!(
+                "UPDATE acts SET started_at = (SELECT created_at FROM timeplan_events WHERE id = ?) WHERE id = ?",
+                event.id,
+                event.act_id,
+            ),
+            TimeplanEventKind::ActEnded => sqlx::query!(
+                "UPDATE acts SET ended_at = (SELECT created_at FROM timeplan_events WHERE id = ?) WHERE id = ?",
+                event.id,
+                event.act_id,
+            ),
+            TimeplanEventKind::Undo | TimeplanEventKind::Redo => continue,
+        }
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
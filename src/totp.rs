@@ -0,0 +1,203 @@
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use crate::http_server::HttpError;
+
+const ISSUER: &str = "Freestyle Cup NRW";
+const DIGITS: usize = 6;
+const STEP: u64 = 30;
+/// Codes of the previous and the next step are accepted for clock drift.
+const SKEW: i64 = 1;
+/// Number of recovery codes created with the enrollment.
+pub const RECOVERY_CODES: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TotpError {
+    #[error("Der Code ist ungültig.")]
+    InvalidCode,
+    #[error("Die Zwei-Faktor-Authentifizierung ist nicht eingerichtet.")]
+    NotEnrolled,
+    #[error("Admins müssen die Zwei-Faktor-Authentifizierung verwenden.")]
+    RequiredForAdmins,
+    #[error("Invalid TOTP secret")]
+    InvalidSecret,
+}
+
+impl From<TotpError> for HttpError {
+    fn from(e: TotpError) -> Self {
+        match e {
+            TotpError::InvalidCode => HttpError::InvalidCredentials,
+            TotpError::RequiredForAdmins => HttpError::StatusCode(StatusCode::FORBIDDEN),
+            e => HttpError::ErrorMessages(e.to_string()),
+        }
+    }
+}
+
+/// How far a session got with the second factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SecondFactor {
+    /// The user has no TOTP and isn't an admin.
+    NotRequired,
+    Verified,
+    /// A code or a recovery code has to be entered with `verify_totp`.
+    Required,
+    /// Admins have to enroll before they can do anything else.
+    EnrollmentRequired,
+}
+
+impl SecondFactor {
+    #[must_use]
+    pub fn new(is_admin: bool, totp_enabled: bool, verified: bool) -> Self {
+        match (totp_enabled, verified) {
+            (_, true) => SecondFactor::Verified,
+            (true, false) => SecondFactor::Required,
+            (false, false) if is_admin => SecondFactor::EnrollmentRequired,
+            (false, false) => SecondFactor::NotRequired,
+        }
+    }
+
+    /// Whether the session is fully authenticated.
+    #[must_use]
+    pub fn is_satisfied(self) -> bool {
+        matches!(self, SecondFactor::NotRequired | SecondFactor::Verified)
+    }
+
+    /// Whether the session may set up a new authenticator, which needs the
+    /// current one if there is one.
+    #[must_use]
+    pub fn may_enroll(self) -> bool {
+        self != SecondFactor::Required
+    }
+}
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, TotpError> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| TotpError::InvalidSecret)?;
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP,
+        secret,
+        Some(ISSUER.to_string()),
+        account.replace(':', ""),
+    )
+    .map_err(|_| TotpError::InvalidSecret)
+}
+
+/// A new random base32 secret.
+#[must_use]
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// The `otpauth://` URL for authenticator apps, usually shown as QR code.
+pub fn otpauth_url(secret: &str, account: &str) -> Result<String, TotpError> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// The step of a valid code, codes of `last_step` or older are rejected so
+/// a code can't be used twice.
+pub fn verify_code(secret: &str, code: &str, last_step: Option<i64>) -> Result<i64, TotpError> {
+    let totp = totp(secret, "")?;
+    let current = i64::try_from(now() / STEP).unwrap();
+    ((current - SKEW)..=(current + SKEW))
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| totp.check(code.trim(), step.unsigned_abs() * STEP))
+        .ok_or(TotpError::InvalidCode)
+}
+
+/// New recovery codes, each can be used once instead of a TOTP code.
+#[must_use]
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let random = Uuid::new_v4().simple().to_string();
+            format!("{}-{}-{}", &random[0..4], &random[4..8], &random[8..12])
+        })
+        .collect()
+}
+
+/// Recovery codes are stored hashed, like passwords. They are random, so a
+/// plain hash is enough.
+#[must_use]
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_lowercase();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+/// Check a TOTP code or, if it doesn't match, a recovery code of a user and
+/// mark it as used.
+pub async fn verify(db: &SqlitePool, user_id: Uuid, code: &str) -> Result<(), HttpError> {
+    let user = sqlx::query!(
+        r#"
+        SELECT totp_secret, totp_last_step, totp_recovery_codes FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_one(db)
+    .await?;
+    let secret = user.totp_secret.ok_or(TotpError::NotEnrolled)?;
+
+    match verify_code(&secret, code, user.totp_last_step) {
+        Ok(step) => {
+            // A request with the same code may have won the race since
+            let updated = sqlx::query!(
+                r#"
+                UPDATE users SET totp_last_step = ?1
+                WHERE id = ?2 AND (totp_last_step IS NULL OR totp_last_step < ?1)
+                "#,
+                step,
+                user_id
+            )
+            .execute(db)
+            .await?;
+            if updated.rows_affected() != 1 {
+                return Err(TotpError::InvalidCode.into());
+            }
+            Ok(())
+        }
+        Err(TotpError::InvalidCode) => {
+            let mut recovery_codes: Vec<String> = user
+                .totp_recovery_codes
+                .as_deref()
+                .and_then(|codes| serde_json::from_str(codes).ok())
+                .unwrap_or_default();
+            let hash = hash_recovery_code(code);
+            let Some(index) = recovery_codes.iter().position(|stored| *stored == hash) else {
+                return Err(TotpError::InvalidCode.into());
+            };
+            recovery_codes.remove(index);
+            let recovery_codes = serde_json::to_string(&recovery_codes).unwrap();
+            let updated = sqlx::query!(
+                "UPDATE users SET totp_recovery_codes = ? WHERE id = ? AND totp_recovery_codes = ?",
+                recovery_codes,
+                user_id,
+                user.totp_recovery_codes
+            )
+            .execute(db)
+            .await?;
+            if updated.rows_affected() != 1 {
+                return Err(TotpError::InvalidCode.into());
+            }
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
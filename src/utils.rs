@@ -49,6 +49,23 @@ pub async fn get_act_id_for_starter_id(
     Ok(act_id.map(|act_id| act_id.act_id))
 }
 
+pub async fn get_competition_id_for_club_id(
+    db: &sqlx::SqlitePool,
+    club_id: Uuid,
+) -> Result<Uuid, String> {
+    let competition = sqlx::query!(
+        r#"
+        SELECT competition_id as "competition_id!: Uuid" FROM clubs WHERE id = ?
+        "#,
+        club_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| format!("Fehler beim Abfragen des Wettbewerbs: {}", e))?
+    .ok_or_else(|| "Verein nicht gefunden.".to_string())?;
+    Ok(competition.competition_id)
+}
+
 pub async fn delete_act(db: &sqlx::SqlitePool, act_id: Uuid) -> Result<(), String> {
     let result = sqlx::query!(
         r#"
@@ -69,6 +86,7 @@ pub async fn delete_act(db: &sqlx::SqlitePool, act_id: Uuid) -> Result<(), Strin
 
 pub async fn set_act(
     db: &sqlx::SqlitePool,
+    competition_id: Uuid,
     name: &str,
     starters: &[Uuid],
     description: Option<&str>,
@@ -77,10 +95,11 @@ pub async fn set_act(
     let id = Uuid::new_v4();
     sqlx::query!(
         r#"
-        INSERT INTO acts (id, name, description, is_pair)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO acts (id, competition_id, name, description, is_pair)
+        VALUES (?, ?, ?, ?, ?)
         "#,
         id,
+        competition_id,
         name,
         description,
         is_pair
@@ -110,7 +129,9 @@ pub async fn initialize_acts(db: &sqlx::SqlitePool) -> Result<(), String> {
     // Singles
     let single_starters = sqlx::query!(
         r#"
-        SELECT id as "id!: Uuid" FROM starter WHERE single_male = TRUE or single_female = TRUE
+        SELECT starter.id as "id!: Uuid", clubs.competition_id as "competition_id!: Uuid"
+        FROM starter JOIN clubs ON clubs.id = starter.club_id
+        WHERE single_male = TRUE or single_female = TRUE
         "#
     )
     .fetch_all(db)
@@ -121,14 +142,16 @@ pub async fn initialize_acts(db: &sqlx::SqlitePool) -> Result<(), String> {
             .await?
             .is_none()
         {
-            set_act(db, "", &[starter.id], None, false).await?;
+            set_act(db, starter.competition_id, "", &[starter.id], None, false).await?;
         }
     }
 
     // Pairs
     let pair_starters = sqlx::query!(
         r#"
-        SELECT id as "id!: Uuid", partner_id as "partner_id!: Uuid" FROM starter WHERE pair = TRUE and partner_id IS NOT NULL
+        SELECT starter.id as "id!: Uuid", partner_id as "partner_id!: Uuid", clubs.competition_id as "competition_id!: Uuid"
+        FROM starter JOIN clubs ON clubs.id = starter.club_id
+        WHERE pair = TRUE and partner_id IS NOT NULL
         "#
     ).fetch_all(db).await.map_err(|e| format!("Fehler beim Abfragen der Paare: {}", e))?;
 
@@ -142,6 +165,7 @@ pub async fn initialize_acts(db: &sqlx::SqlitePool) -> Result<(), String> {
             {
                 set_act(
                     db,
+                    pair_starter.competition_id,
                     "",
                     &[pair_starter.id, pair_starter.partner_id],
                     None,
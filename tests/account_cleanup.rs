@@ -0,0 +1,176 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, token_from_link};
+use nrw_freestyle_cup_registration::account_cleanup::{self, AccountLifetimes, Cleanup};
+use serde_json::json;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+/// The token of the last link to `path` mailed to the user.
+fn mailed_token(app: &TestApp, name: &str, path: &str) -> Uuid {
+    let mails = app.mailer.sent();
+    let body = &mails
+        .iter()
+        .rev()
+        .find(|mail| mail.to == format!("{name}@example.com"))
+        .unwrap()
+        .body;
+    token_from_link(body, &format!("{path}?token="))
+}
+
+/// Move the creation of the user's tokens and account into the past.
+async fn age(app: &TestApp, name: &str, age: Duration) {
+    let created_at = OffsetDateTime::now_utc() - age;
+    for query in [
+        "UPDATE mail_verification SET created_at = ? WHERE user_id = (SELECT id FROM users WHERE name = ?)",
+        "UPDATE password_resets SET created_at = ? WHERE user_id = (SELECT id FROM users WHERE name = ?)",
+        "UPDATE users SET created_at = ? WHERE name = ?",
+    ] {
+        sqlx::query(query)
+            .bind(created_at)
+            .bind(name)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+}
+
+async fn cleanup(app: &TestApp, now: OffsetDateTime) -> Cleanup {
+    account_cleanup::run(
+        &app.db,
+        app.mailer.as_ref(),
+        "http://localhost:3000",
+        &AccountLifetimes::default(),
+        now,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn verification_links_expire_and_work_once() {
+    let app = TestApp::new().await;
+    app.register("slow").await;
+    let token = mailed_token(&app, "slow", "/verify_email");
+    age(&app, "slow", Duration::days(8)).await;
+    let (status, _) = app
+        .post(None, "/api/command/verify_email", json!({ "token": token }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    app.register("quick").await;
+    let token = mailed_token(&app, "quick", "/verify_email");
+    let (status, _) = app
+        .post(None, "/api/command/verify_email", json!({ "token": token }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .post(None, "/api/command/verify_email", json!({ "token": token }))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn password_reset_links_expire_and_work_once() {
+    let app = TestApp::new().await;
+    app.register("forgetful").await;
+    let request_reset = || {
+        app.post(
+            None,
+            "/api/command/request_password_reset",
+            json!({ "email": "forgetful@example.com" }),
+        )
+    };
+    let reset = |token: Uuid, password: &'static str| {
+        app.post(
+            None,
+            "/api/command/reset_password",
+            json!({ "token": token, "new_password": password }),
+        )
+    };
+
+    request_reset().await;
+    let token = mailed_token(&app, "forgetful", "/reset_password");
+    age(&app, "forgetful", Duration::hours(2)).await;
+    let (status, _) = reset(token, "An0ther-Secret").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    request_reset().await;
+    let token = mailed_token(&app, "forgetful", "/reset_password");
+    // A rejected password doesn't use up the link
+    let (status, _) = reset(token, "short").await;
+    assert_ne!(status, StatusCode::OK);
+    let (status, _) = reset(token, "An0ther-Secret").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = reset(token, "Th1rd-Secret").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unverified_accounts_are_notified_and_then_deleted() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    for name in ["lazy", "late", "owner", "fresh"] {
+        app.register(name).await;
+    }
+    for name in ["lazy", "late", "owner"] {
+        age(&app, name, Duration::days(31)).await;
+    }
+    let owner_id: Uuid = sqlx::query_scalar("SELECT id FROM users WHERE name = 'owner'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.create_club(owner_id, "Club").await;
+
+    let now = OffsetDateTime::now_utc();
+    let mails = app.mailer.sent().len();
+    assert_eq!(
+        cleanup(&app, now).await,
+        Cleanup {
+            expired_tokens: 1,
+            notified_accounts: 2,
+            deleted_accounts: 0,
+        }
+    );
+    let sent = app.mailer.sent();
+    assert_eq!(sent.len(), mails + 2);
+    assert!(
+        sent[mails..]
+            .iter()
+            .all(|mail| mail.subject.contains("gelöscht"))
+    );
+    // Notified only once
+    assert_eq!(cleanup(&app, now).await, Cleanup::default());
+
+    let token = mailed_token(&app, "late", "/verify_email");
+    let (status, _) = app
+        .post(None, "/api/command/verify_email", json!({ "token": token }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let later = now + Duration::days(8);
+    assert_eq!(
+        cleanup(&app, later).await,
+        Cleanup {
+            expired_tokens: 1,
+            notified_accounts: 0,
+            deleted_accounts: 1,
+        }
+    );
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM users ORDER BY name")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(names, ["admin", "fresh", "late", "owner"]);
+
+    let (_, log) = app
+        .get(
+            Some(admin),
+            "/api/query/list_audit_log?command=delete_unverified_account",
+        )
+        .await;
+    let log = log.as_array().unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["payload"]["email"], "lazy@example.com");
+}
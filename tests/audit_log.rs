@@ -0,0 +1,141 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use serde_json::json;
+
+#[tokio::test]
+async fn commands_are_recorded_with_before_and_after() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+
+    for amount in [25.0, 40.0] {
+        let (status, _) = app
+            .post(
+                Some(admin),
+                "/api/command/set_payment",
+                json!({ "club_id": club, "amount": amount }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, log) = app
+        .get(Some(admin), "/api/query/list_audit_log?command=set_payment")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let log = log.as_array().unwrap();
+    assert_eq!(log.len(), 2);
+    // Newest first
+    assert_eq!(log[0]["before"]["payment"], 25.0);
+    assert_eq!(log[0]["after"]["payment"], 40.0);
+    assert_eq!(log[1]["before"]["payment"], json!(null));
+    assert_eq!(log[0]["user_id"], json!(admin));
+    assert_eq!(log[0]["user_name"], "admin");
+    assert_eq!(log[0]["payload"]["amount"], 40.0);
+    assert!(log[0]["request_id"].is_string());
+
+    let (_, log) = app
+        .get(
+            Some(admin),
+            &format!("/api/query/list_audit_log?user_id={owner}"),
+        )
+        .await;
+    assert_eq!(log, json!([]));
+}
+
+#[tokio::test]
+async fn category_changes_are_recorded_with_before_and_after() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+
+    let (_, categories) = app.get(None, "/api/query/list_categories").await;
+    let mut category = categories
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["name"] == "NEM")
+        .unwrap()
+        .clone();
+    category["new_name"] = json!("NEM2");
+    category["act_duration_seconds"] = json!(123);
+    let (status, _) = app
+        .post(Some(admin), "/api/command/edit_category", category)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/delete_category",
+            json!({ "name": "NEM2" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, log) = app
+        .get(
+            Some(admin),
+            "/api/query/list_audit_log?command=edit_category",
+        )
+        .await;
+    assert_eq!(log[0]["before"]["name"], "NEM");
+    assert_eq!(log[0]["after"]["name"], "NEM2");
+    assert_eq!(log[0]["after"]["act_duration_seconds"], 123);
+    let (_, log) = app
+        .get(
+            Some(admin),
+            "/api/query/list_audit_log?command=delete_category",
+        )
+        .await;
+    assert_eq!(log[0]["before"]["name"], "NEM2");
+    assert_eq!(log[0]["after"], json!(null));
+}
+
+#[tokio::test]
+async fn passwords_are_not_recorded() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/register",
+            json!({
+                "name": "Trainer",
+                "email": "trainer@example.com",
+                "password": "Sup3r-Secret",
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, log) = app
+        .get(Some(admin), "/api/query/list_audit_log?command=register")
+        .await;
+    assert_eq!(log[0]["payload"]["email"], "trainer@example.com");
+    assert_eq!(log[0]["payload"]["password"], "***");
+    assert_eq!(log[0]["user_id"], json!(null));
+}
+
+#[tokio::test]
+async fn failed_commands_are_not_recorded() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let user = app.create_user("user", false).await;
+    let club = app.create_club(user, "RSV Heimstadt").await;
+
+    let (status, _) = app
+        .post(
+            Some(user),
+            "/api/command/set_payment",
+            json!({ "club_id": club, "amount": 0.0 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.get(Some(user), "/api/query/list_audit_log").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (_, log) = app.get(Some(admin), "/api/query/list_audit_log").await;
+    assert_eq!(log, json!([]));
+}
@@ -0,0 +1,307 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, judge_body, starter_body};
+use nrw_freestyle_cup_registration::song_storage;
+use serde_json::json;
+use uuid::Uuid;
+
+struct Fixture {
+    app: TestApp,
+    admin: Uuid,
+    owner: Uuid,
+    stranger: Uuid,
+    club: Uuid,
+    stranger_club: Uuid,
+    starter: Uuid,
+    act: Uuid,
+}
+
+async fn fixture() -> Fixture {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let stranger = app.create_user("stranger", false).await;
+    let club = app.create_club(owner, "Owner Club").await;
+    let stranger_club = app.create_club(stranger, "Stranger Club").await;
+    let (starter, act) = app.create_starter(club, "Anna").await;
+    Fixture {
+        app,
+        admin,
+        owner,
+        stranger,
+        club,
+        stranger_club,
+        starter,
+        act,
+    }
+}
+
+async fn add_judge(f: &Fixture) -> Uuid {
+    let (status, body) = f
+        .app
+        .post(
+            Some(f.owner),
+            "/api/command/add_club_judge",
+            judge_body(f.club, None),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    body["judge_id"].as_str().unwrap().parse().unwrap()
+}
+
+fn edit_starter_body(starter_id: Uuid) -> serde_json::Value {
+    json!({
+        "starter_id": starter_id,
+        "firstname": "Anna",
+        "lastname": "Renamed",
+        "birthdate": "2012-05-01T00:00:00Z",
+        "single_sonderpokal": false,
+        "single_male": false,
+        "single_female": true,
+        "pair_sonderpokal": false,
+        "pair": false,
+        "partner_name": null,
+        "partner_id": null,
+    })
+}
+
+#[tokio::test]
+async fn add_club_starter_requires_club_access() {
+    let f = fixture().await;
+    let path = "/api/command/add_club_starter";
+    let (status, _) = f
+        .app
+        .post(Some(f.stranger), path, starter_body(f.club))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.post(Some(f.owner), path, starter_body(f.club)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn edit_club_starter_requires_club_access() {
+    let f = fixture().await;
+    let path = "/api/command/edit_club_starter";
+    let (status, _) = f
+        .app
+        .post(Some(f.stranger), path, edit_starter_body(f.starter))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f
+        .app
+        .post(Some(f.admin), path, edit_starter_body(f.starter))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn foreign_partners_are_rejected() {
+    let f = fixture().await;
+    let mut body = starter_body(f.stranger_club);
+    body["pair"] = json!(true);
+    body["partner_id"] = json!(f.starter);
+    let (status, _) = f
+        .app
+        .post(Some(f.stranger), "/api/command/add_club_starter", body)
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (own, _) = f.app.create_starter(f.stranger_club, "Berta").await;
+    let mut body = edit_starter_body(own);
+    body["pair"] = json!(true);
+    body["partner_id"] = json!(f.starter);
+    let (status, _) = f
+        .app
+        .post(Some(f.stranger), "/api/command/edit_club_starter", body)
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let partner_id: Option<Uuid> =
+        sqlx::query_scalar("SELECT partner_id FROM starter WHERE id = ?")
+            .bind(f.starter)
+            .fetch_one(&f.app.db)
+            .await
+            .unwrap();
+    assert_eq!(partner_id, None);
+}
+
+#[tokio::test]
+async fn delete_club_starter_requires_club_access() {
+    let f = fixture().await;
+    let path = "/api/command/delete_club_starter";
+    let body = json!({ "starter_id": f.starter });
+    let (status, _) = f.app.post(Some(f.stranger), path, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.post(Some(f.owner), path, body).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn add_club_judge_requires_club_access() {
+    let f = fixture().await;
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.stranger),
+            "/api/command/add_club_judge",
+            judge_body(f.club, None),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    add_judge(&f).await;
+}
+
+#[tokio::test]
+async fn edit_club_judge_requires_club_access() {
+    let f = fixture().await;
+    let judge = add_judge(&f).await;
+    let path = "/api/command/edit_club_judge";
+    let (status, _) = f
+        .app
+        .post(Some(f.stranger), path, judge_body(f.club, Some(judge)))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f
+        .app
+        .post(Some(f.owner), path, judge_body(f.club, Some(judge)))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn delete_club_judge_requires_club_access() {
+    let f = fixture().await;
+    let judge = add_judge(&f).await;
+    let path = "/api/command/delete_club_judge";
+    let body = json!({ "judge_id": judge });
+    let (status, _) = f.app.post(Some(f.stranger), path, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.post(Some(f.owner), path, body).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn edit_club_act_requires_club_access() {
+    let f = fixture().await;
+    let path = "/api/command/edit_club_act";
+    let body = json!({ "id": f.act, "name": "Kür", "description": null });
+    let (status, _) = f.app.post(Some(f.stranger), path, body.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.post(Some(f.owner), path, body).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn save_act_song_requires_club_access() {
+    let f = fixture().await;
+    let path = format!("/api/command/save_act_song?act_id={}", f.act);
+    let (status, _) = f
+        .app
+        .upload(Some(f.stranger), &path, "song.mp3", b"not really music")
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let file = song_storage::file_name(&song_storage::sha256(b"not really music"), "mp3");
+    assert!(!f.app.data_path.join(file).exists());
+    let (status, _) = f
+        .app
+        .upload(Some(f.owner), &path, "song.wav", &common::wav(1))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rename_club_requires_club_access() {
+    let f = fixture().await;
+    let path = "/api/command/rename_club";
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.stranger),
+            path,
+            json!({ "club_id": f.club, "name": "Taken Over" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.admin),
+            path,
+            json!({ "club_id": f.club, "name": "Renamed Club" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn get_club_requires_club_access() {
+    let f = fixture().await;
+    let path = format!("/api/query/get_club?club_id={}", f.club);
+    let (status, _) = f.app.get(Some(f.stranger), &path).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.get(None, &path).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, club) = f.app.get(Some(f.owner), "/api/query/get_club").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(club["name"], "Owner Club");
+}
+
+#[tokio::test]
+async fn list_club_starters_requires_club_access() {
+    let f = fixture().await;
+    let path = format!("/api/query/list_club_starters?club_id={}", f.club);
+    let (status, _) = f.app.get(Some(f.stranger), &path).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, starters) = f.app.get(Some(f.owner), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(starters.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn list_club_judges_requires_club_access() {
+    let f = fixture().await;
+    add_judge(&f).await;
+    let path = format!("/api/query/list_club_judges?club_id={}", f.club);
+    let (status, _) = f.app.get(Some(f.stranger), &path).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, judges) = f.app.get(Some(f.admin), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(judges.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn list_club_acts_requires_club_access() {
+    let f = fixture().await;
+    let path = format!("/api/query/list_club_acts?club_id={}", f.club);
+    let (status, _) = f.app.get(Some(f.stranger), &path).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, acts) = f.app.get(Some(f.owner), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(acts.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn get_act_requires_club_access() {
+    let f = fixture().await;
+    let path = format!("/api/query/get_act?act_id={}", f.act);
+    let (status, _) = f.app.get(Some(f.stranger), &path).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, act) = f.app.get(Some(f.owner), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(act["id"], json!(f.act));
+}
+
+#[tokio::test]
+async fn unknown_resources_are_not_found() {
+    let f = fixture().await;
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.owner),
+            "/api/command/delete_club_starter",
+            json!({ "starter_id": Uuid::now_v7() }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
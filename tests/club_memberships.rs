@@ -0,0 +1,222 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, starter_body, token_from_link};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Invite `name@example.com` to the club and accept as `user_id`.
+async fn invite(app: &TestApp, owner: Uuid, club: Uuid, name: &str, role: &str) -> Uuid {
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/invite_club_member",
+            json!({ "club_id": club, "email": format!("{name}@example.com"), "role": role }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let mail = app.mailer.sent().pop().unwrap();
+    assert_eq!(mail.to, format!("{name}@example.com"));
+    token_from_link(&mail.body, "/accept_club_invitation?token=")
+}
+
+#[tokio::test]
+async fn invited_trainers_manage_starters_but_not_the_club() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let trainer = app.create_user("trainer", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+
+    let (status, _) = app
+        .post(
+            Some(trainer),
+            "/api/command/invite_club_member",
+            json!({ "club_id": club, "email": "trainer@example.com", "role": "owner" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let token = invite(&app, owner, club, "trainer", "trainer").await;
+    let mail = app.mailer.sent().pop().unwrap();
+    assert!(mail.subject.contains("RSV Heimstadt"));
+    assert!(mail.body.contains("owner hat dich"));
+
+    // The link only works for the invited address
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/accept_club_invitation",
+            json!({ "token": token }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let (status, accepted) = app
+        .post(
+            Some(trainer),
+            "/api/command/accept_club_invitation",
+            json!({ "token": token }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(accepted["role"], "trainer");
+    let (status, _) = app
+        .post(
+            Some(trainer),
+            "/api/command/accept_club_invitation",
+            json!({ "token": token }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, user) = app.get(Some(trainer), "/api/query/whoami").await;
+    assert_eq!(user["club_id"], json!(club));
+    assert_eq!(
+        user["memberships"],
+        json!([{ "club_id": club, "role": "trainer" }])
+    );
+
+    let (status, _) = app
+        .post(
+            Some(trainer),
+            "/api/command/add_club_starter",
+            starter_body(club),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .post(
+            Some(trainer),
+            "/api/command/rename_club",
+            json!({ "club_id": club, "name": "Übernommen" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Members are visible to the trainer, invitations only to owners
+    invite(&app, owner, club, "reader", "read_only").await;
+    let path = format!("/api/query/list_club_members?club_id={club}");
+    let (status, members) = app.get(Some(trainer), &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(members["members"].as_array().unwrap().len(), 2);
+    assert_eq!(members["invitations"], json!([]));
+    let (_, members) = app.get(Some(owner), &path).await;
+    assert_eq!(members["invitations"][0]["email"], "reader@example.com");
+    assert_eq!(members["invitations"][0]["invited_by"], "owner");
+}
+
+#[tokio::test]
+async fn read_only_members_only_read() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let reader = app.create_user("reader", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+
+    let token = invite(&app, owner, club, "reader", "read_only").await;
+    let (status, _) = app
+        .post(
+            Some(reader),
+            "/api/command/accept_club_invitation",
+            json!({ "token": token }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, starters) = app
+        .get(
+            Some(reader),
+            &format!("/api/query/list_club_starters?club_id={club}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(starters.as_array().unwrap().len(), 1);
+    let (status, _) = app
+        .get(Some(reader), &format!("/api/query/get_act?act_id={act_id}"))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .post(
+            Some(reader),
+            "/api/command/add_club_starter",
+            starter_body(club),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .upload(
+            Some(reader),
+            &format!("/api/command/save_act_song?act_id={act_id}"),
+            "Kür.wav",
+            &common::wav(1),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn clubs_keep_an_owner() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let trainer = app.create_user("trainer", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let token = invite(&app, owner, club, "trainer", "trainer").await;
+    app.post(
+        Some(trainer),
+        "/api/command/accept_club_invitation",
+        json!({ "token": token }),
+    )
+    .await;
+
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/set_club_member_role",
+            json!({ "club_id": club, "user_id": owner, "role": "trainer" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/remove_club_member",
+            json!({ "club_id": club, "user_id": owner }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let (status, _) = app
+        .post(
+            Some(trainer),
+            "/api/command/remove_club_member",
+            json!({ "club_id": club, "user_id": owner }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // With a second owner the first one can leave
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/set_club_member_role",
+            json!({ "club_id": club, "user_id": trainer, "role": "owner" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/remove_club_member",
+            json!({ "club_id": club, "user_id": owner }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, user) = app.get(Some(owner), "/api/query/whoami").await;
+    assert_eq!(user["club_id"], json!(null));
+    assert_eq!(user["memberships"], json!([]));
+    let (status, _) = app
+        .get(
+            Some(owner),
+            &format!("/api/query/list_club_starters?club_id={club}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,499 @@
+#![allow(dead_code)]
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use axum_extra::extract::cookie::Cookie;
+use http_body_util::BodyExt;
+use nrw_freestyle_cup_registration::{
+    account_cleanup::AccountLifetimes,
+    http_server::{HttpServerOptions, routes::get_router},
+    jwt::JWTConfig,
+    mailer::MemoryMailer,
+    reloadable_sqlite::ReloadableSqlite,
+    session,
+    song_storage::{LocalSongStorage, SongStorage},
+    utils::set_act,
+};
+use sqlx::{SqlitePool, migrate, sqlite::SqlitePoolOptions};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// The full router on top of a migrated in-memory database.
+///
+/// Mails are captured by a [`MemoryMailer`] and songs are stored in a
+/// temporary directory by a [`LocalSongStorage`] that is removed on drop.
+pub struct TestApp {
+    pub router: Router,
+    pub db: SqlitePool,
+    pub jwt: Arc<JWTConfig>,
+    pub mailer: Arc<MemoryMailer>,
+    pub data_path: PathBuf,
+    pub storage: Arc<dyn SongStorage>,
+}
+
+/// Clubs competing in the first category of the startlist, see
+/// [`TestApp::panel_setup`].
+pub struct PanelSetup {
+    pub admin: Uuid,
+    pub home: Uuid,
+    pub guest: Uuid,
+    pub home_club: Uuid,
+    pub guest_club: Uuid,
+    /// The act of the starter Anna of the home club.
+    pub anna: Uuid,
+    pub category: String,
+}
+
+impl TestApp {
+    pub async fn new() -> Self {
+        // Every connection to an in-memory database gets its own database, so
+        // the pool must never open a second connection or drop the first one.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrate!("./migrations").run(&db).await.unwrap();
+
+        // Keep the registration of the active competition open.
+        let now = time::OffsetDateTime::now_utc();
+        sqlx::query(
+            "UPDATE competitions SET start_register_date = ?, end_register_date = ?, end_music_upload_date = ? WHERE is_active",
+        )
+        .bind(now - time::Duration::days(1))
+        .bind(now + time::Duration::days(1))
+        .bind(now + time::Duration::days(1))
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let jwt_algorithm = jsonwebtoken::Algorithm::HS512;
+        let mut validator = jsonwebtoken::Validation::new(jwt_algorithm);
+        validator.validate_aud = false;
+        let jwt = Arc::new(JWTConfig::new(
+            jsonwebtoken::EncodingKey::from_secret(b"test-secret"),
+            jsonwebtoken::DecodingKey::from_secret(b"test-secret"),
+            jwt_algorithm,
+            validator,
+            true,
+        ));
+
+        let data_path = std::env::temp_dir().join(format!("cup-test-{}", Uuid::now_v7()));
+        let mailer = Arc::new(MemoryMailer::new());
+        let storage: Arc<dyn SongStorage> = Arc::new(LocalSongStorage::new(&data_path));
+        let router = get_router(
+            Arc::new(HttpServerOptions {
+                bind_address: "127.0.0.1:0".to_string(),
+                base_url: "http://localhost:3000".to_string(),
+                reload_db_token: "reload_db".to_string(),
+                trust_forwarded_for: true,
+                lifetimes: AccountLifetimes::default(),
+            }),
+            ReloadableSqlite::new(db.clone(), "sqlite::memory:".to_string()),
+            mailer.clone(),
+            jwt.clone(),
+            storage.clone(),
+        );
+
+        Self {
+            router,
+            db,
+            jwt,
+            mailer,
+            data_path,
+            storage,
+        }
+    }
+
+    pub async fn create_user(&self, name: &str, is_admin: bool) -> Uuid {
+        let id = Uuid::now_v7();
+        sqlx::query(
+            "INSERT INTO users (id, email, email_verified, name, password, is_admin) VALUES (?, ?, true, ?, '', ?)",
+        )
+        .bind(id)
+        .bind(format!("{name}@example.com"))
+        .bind(name)
+        .bind(is_admin)
+        .execute(&self.db)
+        .await
+        .unwrap();
+        id
+    }
+
+    /// Register `name@example.com` with the password `Sup3r-Secret` and
+    /// return the cookies of the new session.
+    pub async fn register(&self, name: &str) -> HashMap<String, String> {
+        let (status, _, cookies) = self
+            .post_with_cookies(
+                &[],
+                "/api/command/register",
+                serde_json::json!({
+                    "name": name,
+                    "email": format!("{name}@example.com"),
+                    "password": "Sup3r-Secret",
+                }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        cookies
+    }
+
+    /// Log in as `name@example.com` and return the body and the cookies of
+    /// the new session.
+    pub async fn login(&self, name: &str) -> (serde_json::Value, HashMap<String, String>) {
+        let (status, body, cookies) = self
+            .post_with_cookies(
+                &[],
+                "/api/command/login",
+                serde_json::json!({
+                    "email": format!("{name}@example.com"),
+                    "password": "Sup3r-Secret",
+                }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        (body, cookies)
+    }
+
+    pub async fn active_competition(&self) -> Uuid {
+        sqlx::query_scalar("SELECT id FROM competitions WHERE is_active")
+            .fetch_one(&self.db)
+            .await
+            .unwrap()
+    }
+
+    /// Create a club in the active competition, owned by the given user.
+    pub async fn create_club(&self, owner_id: Uuid, name: &str) -> Uuid {
+        let id = Uuid::now_v7();
+        sqlx::query("INSERT INTO clubs (id, competition_id, name, owner_id) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(self.active_competition().await)
+            .bind(name)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE users SET club_id = ? WHERE id = ?")
+            .bind(id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO club_memberships (club_id, user_id, role) VALUES (?, ?, 'owner')")
+            .bind(id)
+            .bind(owner_id)
+            .execute(&self.db)
+            .await
+            .unwrap();
+        id
+    }
+
+    /// Create a female single starter together with her act.
+    pub async fn create_starter(&self, club_id: Uuid, firstname: &str) -> (Uuid, Uuid) {
+        let id = Uuid::now_v7();
+        sqlx::query(
+            r#"
+            INSERT INTO starter (
+              id, club_id, firstname, lastname, birthdate, single_sonderpokal,
+              single_male, single_female, pair_sonderpokal, pair
+            ) VALUES (?, ?, ?, 'Tester', '2012-05-01T00:00:00Z', false, false, true, false, false)
+            "#,
+        )
+        .bind(id)
+        .bind(club_id)
+        .bind(firstname)
+        .execute(&self.db)
+        .await
+        .unwrap();
+        let competition_id: Uuid =
+            sqlx::query_scalar("SELECT competition_id FROM clubs WHERE id = ?")
+                .bind(club_id)
+                .fetch_one(&self.db)
+                .await
+                .unwrap();
+        let act_id = set_act(&self.db, competition_id, "", &[id], None, false)
+            .await
+            .unwrap();
+        (id, act_id)
+    }
+
+    /// An admin, a home and a guest club with their owners and a starter of
+    /// the home club. The panel of her category needs as many judges per
+    /// role as given in `requirements`.
+    pub async fn panel_setup(&self, mut requirements: serde_json::Value) -> PanelSetup {
+        let admin = self.create_user("admin", true).await;
+        let home = self.create_user("home", false).await;
+        let guest = self.create_user("guest", false).await;
+        let home_club = self.create_club(home, "RSV Heimstadt").await;
+        let guest_club = self.create_club(guest, "RSV Gaststadt").await;
+        let (_, anna) = self.create_starter(home_club, "Anna").await;
+
+        let (_, startlist) = self.get(None, "/api/query/startlist").await;
+        let category = startlist[0]["category"].as_str().unwrap().to_string();
+        requirements["category"] = serde_json::json!(category);
+        let (status, _) = self
+            .post(
+                Some(admin),
+                "/api/command/set_panel_requirements",
+                requirements,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        PanelSetup {
+            admin,
+            home,
+            guest,
+            home_club,
+            guest_club,
+            anna,
+            category,
+        }
+    }
+
+    /// Add a judge with the given qualifications to the club as its owner.
+    pub async fn add_judge(
+        &self,
+        owner_id: Uuid,
+        club_id: Uuid,
+        qualifications: serde_json::Value,
+    ) -> Uuid {
+        let mut body = judge_body(club_id, None);
+        body["qualifications"] = qualifications;
+        let (status, body) = self
+            .post(Some(owner_id), "/api/command/add_club_judge", body)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        body["judge_id"].as_str().unwrap().parse().unwrap()
+    }
+
+    /// An access token of a new session of the user, past the second
+    /// factor.
+    pub async fn token(&self, user_id: Uuid) -> String {
+        let session = session::create(&self.db, user_id).await.unwrap();
+        session::verify_second_factor(&self.db, session.id)
+            .await
+            .unwrap();
+        self.jwt.create_access_token(&session).unwrap()
+    }
+
+    pub async fn request(&self, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let (status, json, _) = self.request_with_cookies(request).await;
+        (status, json)
+    }
+
+    /// Like [`TestApp::request`] but also returns the cookies the response
+    /// sets, removed cookies have an empty value.
+    pub async fn request_with_cookies(
+        &self,
+        request: Request<Body>,
+    ) -> (StatusCode, serde_json::Value, HashMap<String, String>) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let cookies = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|value| {
+                let cookie = Cookie::parse(value.to_str().unwrap().to_string()).unwrap();
+                (cookie.name().to_string(), cookie.value().to_string())
+            })
+            .collect();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+        (status, json, cookies)
+    }
+
+    /// Like [`TestApp::post`] but with the given cookies instead of a new
+    /// session of a user.
+    pub async fn post_with_cookies(
+        &self,
+        cookies: &[(&str, &str)],
+        path: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value, HashMap<String, String>) {
+        let request = with_cookies(Request::post(path), cookies)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.request_with_cookies(request).await
+    }
+
+    /// Like [`TestApp::get`] but with the given cookies instead of a new
+    /// session of a user.
+    pub async fn get_with_cookies(
+        &self,
+        cookies: &[(&str, &str)],
+        path: &str,
+    ) -> (StatusCode, serde_json::Value, HashMap<String, String>) {
+        let request = with_cookies(Request::get(path), cookies)
+            .body(Body::empty())
+            .unwrap();
+        self.request_with_cookies(request).await
+    }
+
+    pub async fn post(
+        &self,
+        user_id: Option<Uuid>,
+        path: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::post(path).header(header::CONTENT_TYPE, "application/json");
+        if let Some(user_id) = user_id {
+            request = request.header(header::COOKIE, format!("jwt={}", self.token(user_id).await));
+        }
+        self.request(request.body(Body::from(body.to_string())).unwrap())
+            .await
+    }
+
+    pub async fn get(&self, user_id: Option<Uuid>, path: &str) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::get(path);
+        if let Some(user_id) = user_id {
+            request = request.header(header::COOKIE, format!("jwt={}", self.token(user_id).await));
+        }
+        self.request(request.body(Body::empty()).unwrap()).await
+    }
+
+    /// Like [`TestApp::get`] but for downloads that are not JSON.
+    pub async fn download(
+        &self,
+        user_id: Option<Uuid>,
+        path: &str,
+    ) -> (StatusCode, Option<String>, Vec<u8>) {
+        let mut request = Request::get(path);
+        if let Some(user_id) = user_id {
+            request = request.header(header::COOKIE, format!("jwt={}", self.token(user_id).await));
+        }
+        let response = self
+            .router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, content_type, body.to_vec())
+    }
+
+    pub async fn upload(
+        &self,
+        user_id: Option<Uuid>,
+        path: &str,
+        file_name: &str,
+        content: &[u8],
+    ) -> (StatusCode, serde_json::Value) {
+        let boundary = "cup-test-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(content);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        let mut request = Request::post(path).header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+        if let Some(user_id) = user_id {
+            request = request.header(header::COOKIE, format!("jwt={}", self.token(user_id).await));
+        }
+        self.request(request.body(Body::from(body)).unwrap()).await
+    }
+}
+
+fn with_cookies(
+    request: axum::http::request::Builder,
+    cookies: &[(&str, &str)],
+) -> axum::http::request::Builder {
+    if cookies.is_empty() {
+        return request;
+    }
+    let cookies: Vec<String> = cookies
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    request.header(header::COOKIE, cookies.join("; "))
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.data_path);
+    }
+}
+
+/// Body for `add_club_judge` without qualifications.
+pub fn judge_body(club_id: Uuid, judge_id: Option<Uuid>) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "club_id": club_id,
+        "firstname": "Judy",
+        "lastname": "Judge",
+        "mail": "judy@example.com",
+        "birthdate": "1990-01-01T00:00:00Z",
+        "qualifications": [],
+    });
+    if let Some(judge_id) = judge_id {
+        body["judge_id"] = serde_json::json!(judge_id);
+    }
+    body
+}
+
+/// Body for `add_club_starter` of a female single starter.
+pub fn starter_body(club_id: Uuid) -> serde_json::Value {
+    serde_json::json!({
+        "club_id": club_id,
+        "firstname": "Berta",
+        "lastname": "Tester",
+        "birthdate": "2011-01-01T00:00:00Z",
+        "single_sonderpokal": false,
+        "single_male": false,
+        "single_female": true,
+        "pair_sonderpokal": false,
+        "pair": false,
+        "partner_id": null,
+        "partner_name": null,
+    })
+}
+
+/// The token of a mailed link, `path` ends right before it, e.g.
+/// `/verify_email?token=`.
+pub fn token_from_link(body: &str, path: &str) -> Uuid {
+    let start = body.find(path).expect("link missing in mail") + path.len();
+    body[start..start + 36].parse().unwrap()
+}
+
+/// A mono 16 bit PCM WAV file with a 440 Hz sine at half amplitude.
+pub fn wav(seconds: u32) -> Vec<u8> {
+    const SAMPLE_RATE: u32 = 8000;
+    let samples = SAMPLE_RATE * seconds;
+    let mut wav = Vec::with_capacity(44 + samples as usize * 2);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples * 2).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples * 2).to_le_bytes());
+    for i in 0..samples {
+        let t = i as f64 / SAMPLE_RATE as f64;
+        let sample =
+            (f64::sin(2.0 * std::f64::consts::PI * 440.0 * t) * i16::MAX as f64 / 2.0) as i16;
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
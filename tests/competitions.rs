@@ -0,0 +1,225 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use serde_json::json;
+use uuid::Uuid;
+
+fn competition_body(name: &str, day: &str, copy_from: Option<Uuid>) -> serde_json::Value {
+    json!({
+        "name": name,
+        "venue": "Sporthalle",
+        "days": [day],
+        "start_register_date": "2020-01-01T00:00:00Z",
+        "end_register_date": "2099-01-01T00:00:00Z",
+        "end_music_upload_date": "2099-01-01T00:00:00Z",
+        "copy_from": copy_from,
+    })
+}
+
+async fn add_competition(app: &TestApp, admin: Uuid, body: serde_json::Value) -> Uuid {
+    let (status, body) = app
+        .post(Some(admin), "/api/command/add_competition", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    body["competition_id"].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn migrated_competition_is_active() {
+    let app = TestApp::new().await;
+    let (status, competition) = app.get(None, "/api/query/get_active_competition").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(competition["name"], "Freestyle Cup NRW 2026");
+    assert_eq!(competition["days"], json!(["2026-04-19"]));
+    assert_eq!(competition["is_active"], true);
+
+    let (_, categories) = app.get(None, "/api/query/list_categories").await;
+    assert!(!categories.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn competitions_are_managed_by_admins() {
+    let app = TestApp::new().await;
+    let user = app.create_user("user", false).await;
+    let (status, _) = app
+        .post(
+            Some(user),
+            "/api/command/add_competition",
+            competition_body("Cup 2027", "2027-04-18", None),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.get(Some(user), "/api/query/list_competitions").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let admin = app.create_user("admin", true).await;
+    add_competition(
+        &app,
+        admin,
+        competition_body("Cup 2027", "2027-04-18", None),
+    )
+    .await;
+    let (status, competitions) = app.get(Some(admin), "/api/query/list_competitions").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(competitions.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn new_competition_copies_categories_and_timeplan() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let current = app.active_competition().await;
+    let (_, old_categories) = app.get(None, "/api/query/list_categories").await;
+    let (_, old_timeplan) = app.get(None, "/api/query/list_timeplan").await;
+
+    let next = add_competition(
+        &app,
+        admin,
+        competition_body("Cup 2027", "2027-04-18", Some(current)),
+    )
+    .await;
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/activate_competition",
+            json!({ "id": next }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(app.active_competition().await, next);
+
+    let (_, categories) = app.get(None, "/api/query/list_categories").await;
+    assert_eq!(
+        categories.as_array().unwrap().len(),
+        old_categories.as_array().unwrap().len()
+    );
+    let old = old_categories
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["name"] == "NEWU11")
+        .unwrap();
+    let new = categories
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["name"] == "NEWU11")
+        .unwrap();
+    // Years are formatted with sign and six digits, e.g. "+002014-03-16T..."
+    let year = |value: &serde_json::Value| value.as_str().unwrap()[..7].parse::<i32>().unwrap();
+    assert_eq!(year(&new["from_birthday"]), year(&old["from_birthday"]) + 1);
+
+    let (_, timeplan) = app.get(None, "/api/query/list_timeplan").await;
+    assert_eq!(
+        timeplan.as_array().unwrap().len(),
+        old_timeplan.as_array().unwrap().len()
+    );
+    // 2026-04-19 to 2027-04-18 are 364 days
+    assert_eq!(
+        old_timeplan[0]["earliest_start_time"].as_str().unwrap()[..14],
+        *"+002025-03-15T"
+    );
+    assert_eq!(
+        timeplan[0]["earliest_start_time"].as_str().unwrap()[..14],
+        *"+002026-03-14T"
+    );
+}
+
+#[tokio::test]
+async fn clubs_and_acts_belong_to_their_competition() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Testhausen").await;
+    app.create_starter(club, "Anna").await;
+    let current = app.active_competition().await;
+
+    let (_, startlist) = app.get(None, "/api/query/startlist").await;
+    assert_eq!(startlist.as_array().unwrap().len(), 1);
+
+    let next = add_competition(
+        &app,
+        admin,
+        competition_body("Cup 2027", "2027-04-18", Some(current)),
+    )
+    .await;
+    app.post(
+        Some(admin),
+        "/api/command/activate_competition",
+        json!({ "id": next }),
+    )
+    .await;
+
+    let (_, startlist) = app.get(None, "/api/query/startlist").await;
+    assert!(startlist.as_array().unwrap().is_empty());
+    let (_, starters) = app.get(Some(admin), "/api/query/list_starters").await;
+    assert!(starters.as_array().unwrap().is_empty());
+
+    // Club names only have to be unique within a competition
+    let (status, club) = app
+        .post(
+            Some(owner),
+            "/api/command/create_club",
+            json!({ "name": "RSV Testhausen" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let new_club: Uuid = club["club_id"].as_str().unwrap().parse().unwrap();
+    app.create_starter(new_club, "Berta").await;
+    let (_, startlist) = app.get(None, "/api/query/startlist").await;
+    assert_eq!(startlist.as_array().unwrap().len(), 1);
+    assert_eq!(startlist[0]["participants"][0]["firstname"], "Berta");
+}
+
+#[tokio::test]
+async fn age_is_computed_against_the_first_competition_day() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Testhausen").await;
+    // Born on 2012-05-01
+    app.create_starter(club, "Anna").await;
+
+    let (_, starters) = app.get(Some(admin), "/api/query/list_starters").await;
+    assert!(starters[0]["age_on_competition"].as_f64().unwrap() < 14.0);
+
+    let current = app.active_competition().await;
+    let mut body = competition_body("Freestyle Cup NRW 2026", "2026-05-02", None);
+    body["id"] = json!(current);
+    body["days"] = json!(["2026-05-03", "2026-05-02"]);
+    let (status, _) = app
+        .post(Some(admin), "/api/command/edit_competition", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, competition) = app.get(None, "/api/query/get_active_competition").await;
+    assert_eq!(competition["days"], json!(["2026-05-02", "2026-05-03"]));
+    let (_, starters) = app.get(Some(admin), "/api/query/list_starters").await;
+    assert!(starters[0]["age_on_competition"].as_f64().unwrap() > 14.0);
+}
+
+#[tokio::test]
+async fn capabilities_follow_the_active_competition_deadlines() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let user = app.create_user("user", false).await;
+
+    let (_, status) = app.get(Some(user), "/api/query/get_system_status").await;
+    assert_eq!(status["can_register"], true);
+
+    let mut body = competition_body("Freestyle Cup NRW 2026", "2026-04-19", None);
+    body["id"] = json!(app.active_competition().await);
+    body["end_register_date"] = json!("2021-01-01T00:00:00Z");
+    body["end_music_upload_date"] = json!("2021-01-01T00:00:00Z");
+    let (status, _) = app
+        .post(Some(admin), "/api/command/edit_competition", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, status) = app.get(Some(user), "/api/query/get_system_status").await;
+    assert_eq!(status["can_register"], false);
+    assert_eq!(status["can_upload_music"], false);
+    let (_, status) = app.get(Some(admin), "/api/query/get_system_status").await;
+    assert_eq!(status["can_register"], true);
+}
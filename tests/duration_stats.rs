@@ -0,0 +1,176 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::duration_stats::{
+    CategoryRun, DurationStats, category_durations,
+};
+use serde_json::json;
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+
+#[test]
+fn stats_of_samples() {
+    let stats = DurationStats::from_samples(&[30, 10, 20, 40, 100]).unwrap();
+    assert_eq!(stats.samples, 5);
+    assert_eq!(stats.mean_seconds, 40.0);
+    assert_eq!(stats.median_seconds, 30.0);
+    assert_eq!(stats.p90_seconds, 100.0);
+
+    let stats = DurationStats::from_samples(&[10, 21, -5]).unwrap();
+    assert_eq!(stats.samples, 2);
+    assert_eq!(stats.median_seconds, 15.5);
+    assert_eq!(stats.suggested_seconds(), 16);
+
+    assert_eq!(DurationStats::from_samples(&[]), None);
+}
+
+#[test]
+fn durations_of_a_category() {
+    let start = datetime!(2026-04-19 10:00 UTC);
+    let act = |offset: i64, length: Option<i64>| {
+        let started_at = start + Duration::seconds(offset);
+        (
+            started_at,
+            length.map(|l| started_at + Duration::seconds(l)),
+        )
+    };
+    let runs = [
+        CategoryRun {
+            category: "NEM".to_string(),
+            entry_started_at: Some(start),
+            // Unordered, the last act is still running
+            acts: vec![act(300, Some(160)), act(120, Some(150)), act(490, None)],
+        },
+        CategoryRun {
+            category: "NEM".to_string(),
+            entry_started_at: None,
+            acts: vec![act(0, Some(170))],
+        },
+    ];
+
+    let durations = category_durations(&runs);
+    assert_eq!(durations.len(), 1);
+    let nem = &durations[0];
+    assert_eq!(nem.category, "NEM");
+    assert_eq!(nem.einfahrzeit.unwrap().median_seconds, 120.0);
+    let act = nem.act.unwrap();
+    assert_eq!(act.samples, 3);
+    assert_eq!(act.mean_seconds, 160.0);
+    assert_eq!(act.p90_seconds, 170.0);
+    // 270 -> 300 and 460 -> 490
+    let judge = nem.judge.unwrap();
+    assert_eq!(judge.samples, 2);
+    assert_eq!(judge.median_seconds, 30.0);
+}
+
+#[tokio::test]
+async fn suggested_durations_are_applied_to_the_next_competition() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let competition_id = app.active_competition().await;
+
+    let mut acts = vec![];
+    for name in ["Anna", "Berta", "Clara"] {
+        acts.push(app.create_starter(club, name).await.1);
+    }
+    let category: String = sqlx::query_scalar("SELECT category FROM view_act WHERE id = ?")
+        .bind(acts[0])
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    // Einfahrzeit of 90 seconds, acts of 150 seconds and 40 seconds for the judges
+    sqlx::query(
+        "UPDATE timeplan SET started_at = '2026-04-19 10:00:00' WHERE competition_id = ? AND category = ?",
+    )
+    .bind(competition_id)
+    .bind(&category)
+    .execute(&app.db)
+    .await
+    .unwrap();
+    for (i, act_id) in acts.iter().enumerate() {
+        let started_at = 90 + i as i64 * 190;
+        sqlx::query(
+            "UPDATE acts SET started_at = datetime('2026-04-19 10:00:00', ? || ' seconds'), ended_at = datetime('2026-04-19 10:00:00', ? || ' seconds') WHERE id = ?",
+        )
+        .bind(started_at)
+        .bind(started_at + 150)
+        .bind(act_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    }
+
+    let (status, stats) = app
+        .get(
+            Some(admin),
+            &format!("/api/query/get_duration_statistics?competition_id={competition_id}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let stats = stats.as_array().unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0]["category"], json!(category));
+    assert_eq!(stats[0]["act"]["samples"], 3);
+    assert_eq!(stats[0]["act"]["median_seconds"], 150.0);
+
+    let (status, body) = app
+        .post(
+            Some(admin),
+            "/api/command/add_competition",
+            json!({
+                "name": "Cup 2027",
+                "days": ["2027-04-18"],
+                "start_register_date": "2027-01-01T00:00:00Z",
+                "end_register_date": "2027-03-01T00:00:00Z",
+                "end_music_upload_date": "2027-04-01T00:00:00Z",
+                "copy_from": competition_id,
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let next: Uuid = body["competition_id"].as_str().unwrap().parse().unwrap();
+
+    let (status, body) = app
+        .post(
+            Some(admin),
+            "/api/command/apply_suggested_durations",
+            json!({ "competition_id": next }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["updated"], json!([category]));
+
+    let durations: (i64, i64, i64) = sqlx::query_as(
+        "SELECT einfahrzeit_seconds, act_duration_seconds, judge_duration_seconds FROM categories WHERE competition_id = ? AND name = ?",
+    )
+    .bind(next)
+    .bind(&category)
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(durations, (90, 150, 40));
+
+    // Nothing changes the second time
+    let (status, body) = app
+        .post(
+            Some(admin),
+            "/api/command/apply_suggested_durations",
+            json!({ "competition_id": next }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["updated"], json!([]));
+
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/apply_suggested_durations",
+            json!({ "competition_id": next }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
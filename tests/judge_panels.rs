@@ -0,0 +1,280 @@
+mod common;
+
+use std::collections::HashSet;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::{
+    judge::{JudgeQualification, JudgeRole},
+    judge_panel::{PanelAssignment, PanelJudge, PanelRequirements, PanelSlot, assign},
+};
+use serde_json::json;
+use time::macros::datetime;
+use uuid::Uuid;
+
+struct Fixture {
+    app: TestApp,
+    admin: Uuid,
+    category: String,
+    home_judge: Uuid,
+    guest_judge: Uuid,
+}
+
+/// A starter of the home club and one performance judge from each club.
+async fn fixture() -> Fixture {
+    let app = TestApp::new().await;
+    let setup = app
+        .panel_setup(json!({
+            "performance": 1,
+            "technique": 0,
+            "dismount": 0,
+            "hospitation": 0,
+        }))
+        .await;
+
+    let qualifications = json!([{ "category": setup.category, "role": "performance" }]);
+    let home_judge = app
+        .add_judge(setup.home, setup.home_club, qualifications.clone())
+        .await;
+    let guest_judge = app
+        .add_judge(setup.guest, setup.guest_club, qualifications)
+        .await;
+
+    Fixture {
+        app,
+        admin: setup.admin,
+        category: setup.category,
+        home_judge,
+        guest_judge,
+    }
+}
+
+async fn get_panel(f: &Fixture) -> serde_json::Value {
+    let (status, panels) = f
+        .app
+        .get(Some(f.admin), "/api/query/list_judge_panels")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    panels
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|panel| panel["category"] == f.category.as_str())
+        .unwrap()
+        .clone()
+}
+
+#[tokio::test]
+async fn panels_avoid_judges_of_starting_clubs() {
+    let f = fixture().await;
+    let (status, _) = f
+        .app
+        .post(Some(f.admin), "/api/command/assign_judge_panels", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let panel = get_panel(&f).await;
+    assert_eq!(panel["judges"].as_array().unwrap().len(), 1);
+    assert_eq!(panel["judges"][0]["judge_id"], json!(f.guest_judge));
+    assert_eq!(panel["judges"][0]["own_club"], false);
+    assert_eq!(panel["missing"], json!([]));
+}
+
+#[tokio::test]
+async fn categories_split_over_several_entries_avoid_starting_clubs_in_each() {
+    let f = fixture().await;
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.admin),
+            "/api/command/add_timeplan_entry",
+            json!({ "category": f.category }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = f
+        .app
+        .post(Some(f.admin), "/api/command/assign_judge_panels", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, panels) = f
+        .app
+        .get(Some(f.admin), "/api/query/list_judge_panels")
+        .await;
+    let panels: Vec<_> = panels
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|panel| panel["category"] == f.category.as_str())
+        .collect();
+    assert_eq!(panels.len(), 2);
+    for panel in panels {
+        let judges = panel["judges"].as_array().unwrap();
+        assert!(
+            judges
+                .iter()
+                .all(|judge| judge["judge_id"] != json!(f.home_judge))
+        );
+    }
+}
+
+#[tokio::test]
+async fn manual_assignments_are_kept() {
+    let f = fixture().await;
+    let timeplan_id = get_panel(&f).await["timeplan_id"].clone();
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.admin),
+            "/api/command/set_judge_panel_assignment",
+            json!({
+                "timeplan_id": timeplan_id,
+                "judge_id": f.home_judge,
+                "role": "performance",
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    f.app
+        .post(Some(f.admin), "/api/command/assign_judge_panels", json!({}))
+        .await;
+    let panel = get_panel(&f).await;
+    assert_eq!(panel["judges"].as_array().unwrap().len(), 1);
+    assert_eq!(panel["judges"][0]["judge_id"], json!(f.home_judge));
+    assert_eq!(panel["judges"][0]["is_manual"], true);
+    assert_eq!(panel["judges"][0]["own_club"], true);
+
+    let (status, _) = f
+        .app
+        .post(
+            Some(f.admin),
+            "/api/command/delete_judge_panel_assignment",
+            json!({ "timeplan_id": timeplan_id, "judge_id": f.home_judge }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let panel = get_panel(&f).await;
+    assert_eq!(
+        panel["missing"],
+        json!([{ "role": "performance", "count": 1 }])
+    );
+}
+
+#[tokio::test]
+async fn panels_are_managed_by_admins() {
+    let f = fixture().await;
+    let user = f.app.create_user("user", false).await;
+    let (status, _) = f
+        .app
+        .post(Some(user), "/api/command/assign_judge_panels", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = f.app.get(Some(user), "/api/query/list_judge_panels").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+fn slot(timeplan_id: i64, start: time::OffsetDateTime, minutes: i64) -> PanelSlot {
+    PanelSlot {
+        timeplan_id,
+        category: "NEM".to_string(),
+        start,
+        end: start + time::Duration::minutes(minutes),
+        requirements: PanelRequirements {
+            performance: 1,
+            ..Default::default()
+        },
+        starter_clubs: HashSet::new(),
+    }
+}
+
+fn judge() -> PanelJudge {
+    PanelJudge {
+        id: Uuid::now_v7(),
+        club_id: Uuid::now_v7(),
+        qualifications: vec![JudgeQualification {
+            category: "NEM".to_string(),
+            role: JudgeRole::Performance,
+            hospitation: false,
+        }],
+    }
+}
+
+fn judges_of(assignments: &[PanelAssignment], timeplan_id: i64) -> Vec<Uuid> {
+    assignments
+        .iter()
+        .filter(|a| a.timeplan_id == timeplan_id)
+        .map(|a| a.judge_id)
+        .collect()
+}
+
+#[test]
+fn judges_are_not_assigned_to_overlapping_slots() {
+    let judges = [judge()];
+    let slots = [
+        slot(1, datetime!(2026-04-19 10:00 UTC), 60),
+        slot(2, datetime!(2026-04-19 10:30 UTC), 60),
+        slot(3, datetime!(2026-04-19 11:30 UTC), 60),
+    ];
+    let assignments = assign(&slots, &judges, &[]);
+    assert_eq!(judges_of(&assignments, 1), [judges[0].id]);
+    assert!(judges_of(&assignments, 2).is_empty());
+    assert_eq!(judges_of(&assignments, 3), [judges[0].id]);
+}
+
+#[test]
+fn judges_fill_one_role_in_slots_without_length() {
+    let mut all_round = judge();
+    all_round.qualifications.push(JudgeQualification {
+        category: "NEM".to_string(),
+        role: JudgeRole::Technique,
+        hospitation: false,
+    });
+    let mut empty = slot(1, datetime!(2026-04-19 10:00 UTC), 0);
+    empty.requirements.technique = 1;
+    let assignments = assign(&[empty], &[all_round], &[]);
+    assert_eq!(assignments.len(), 1);
+}
+
+#[test]
+fn load_is_spread_over_the_day() {
+    let judges = [judge(), judge()];
+    let slots = [
+        slot(1, datetime!(2026-04-19 10:00 UTC), 60),
+        slot(2, datetime!(2026-04-19 11:00 UTC), 60),
+    ];
+    let assignments = assign(&slots, &judges, &[]);
+    assert_ne!(judges_of(&assignments, 1), judges_of(&assignments, 2));
+}
+
+#[test]
+fn hospitants_are_offered_for_every_role_they_learn() {
+    let hospitation = |role| JudgeQualification {
+        category: "NEM".to_string(),
+        role,
+        hospitation: true,
+    };
+    let mut learning = judge();
+    learning.qualifications = vec![
+        hospitation(JudgeRole::Performance),
+        hospitation(JudgeRole::Technique),
+    ];
+    let mut slot = slot(1, datetime!(2026-04-19 10:00 UTC), 60);
+    slot.requirements = PanelRequirements {
+        hospitation: 2,
+        ..Default::default()
+    };
+    let manual = PanelAssignment {
+        timeplan_id: 1,
+        judge_id: Uuid::now_v7(),
+        role: JudgeRole::Performance,
+        hospitation: true,
+        is_manual: true,
+    };
+
+    let assignments = assign(&[slot], &[learning.clone()], &[manual]);
+    assert_eq!(assignments.len(), 2);
+    assert_eq!(assignments[1].judge_id, learning.id);
+    assert_eq!(assignments[1].role, JudgeRole::Technique);
+}
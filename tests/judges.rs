@@ -0,0 +1,126 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, judge_body};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn add_judge(app: &TestApp, owner: Uuid, body: serde_json::Value) -> Uuid {
+    let (status, body) = app
+        .post(Some(owner), "/api/command/add_club_judge", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    body["judge_id"].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn qualifications_round_trip() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let admin = app.create_user("admin", true).await;
+    let club = app.create_club(owner, "RSV Testhausen").await;
+
+    let mut body = judge_body(club, None);
+    body["qualifications"] = json!([
+        { "category": "NEWU11", "role": "performance" },
+        { "category": "NEWU11", "role": "dismount", "hospitation": true },
+        { "category": "SE15+", "role": "technique" },
+    ]);
+    let judge = add_judge(&app, owner, body).await;
+
+    let path = format!("/api/query/list_club_judges?club_id={club}");
+    let (_, judges) = app.get(Some(owner), &path).await;
+    let qualifications = judges[0]["qualifications"].as_array().unwrap();
+    assert_eq!(qualifications.len(), 3);
+    assert!(qualifications.contains(&json!({
+        "category": "NEWU11",
+        "role": "dismount",
+        "hospitation": true,
+    })));
+
+    let mut body = judge_body(club, Some(judge));
+    body["qualifications"] = json!([{ "category": "SE15+", "role": "performance" }]);
+    let (status, _) = app
+        .post(Some(owner), "/api/command/edit_club_judge", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, judges) = app.get(Some(admin), "/api/query/list_judges").await;
+    assert_eq!(
+        judges[0]["qualifications"],
+        json!([{ "category": "SE15+", "role": "performance", "hospitation": false }])
+    );
+}
+
+#[tokio::test]
+async fn qualifications_need_existing_categories() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Testhausen").await;
+
+    let mut body = judge_body(club, None);
+    body["qualifications"] = json!([{ "category": "Unbekannt", "role": "performance" }]);
+    let (status, _) = app
+        .post(Some(owner), "/api/command/add_club_judge", body)
+        .await;
+    assert!(!status.is_success());
+
+    // The judge is not added without its qualifications
+    let path = format!("/api/query/list_club_judges?club_id={club}");
+    let (_, judges) = app.get(Some(owner), &path).await;
+    assert!(judges.as_array().unwrap().is_empty());
+
+    let mut body = judge_body(club, None);
+    body["qualifications"] = json!([
+        { "category": "NEM", "role": "technique" },
+        { "category": "NEM", "role": "technique", "hospitation": true },
+    ]);
+    let (status, _) = app
+        .post(Some(owner), "/api/command/add_club_judge", body)
+        .await;
+    assert!(!status.is_success());
+}
+
+#[tokio::test]
+async fn qualifications_follow_category_changes() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let admin = app.create_user("admin", true).await;
+    let club = app.create_club(owner, "RSV Testhausen").await;
+
+    let mut body = judge_body(club, None);
+    body["qualifications"] = json!([
+        { "category": "NEM", "role": "technique" },
+        { "category": "SE15+", "role": "technique" },
+    ]);
+    add_judge(&app, owner, body).await;
+
+    let (_, categories) = app.get(None, "/api/query/list_categories").await;
+    let mut category = categories
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|category| category["name"] == "NEM")
+        .unwrap()
+        .clone();
+    category["new_name"] = json!("NEM2");
+    let (status, _) = app
+        .post(Some(admin), "/api/command/edit_category", category)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/delete_category",
+            json!({ "name": "SE15+" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, judges) = app.get(Some(admin), "/api/query/list_judges").await;
+    assert_eq!(
+        judges[0]["qualifications"],
+        json!([{ "category": "NEM2", "role": "technique", "hospitation": false }])
+    );
+}
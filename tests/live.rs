@@ -0,0 +1,101 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use common::TestApp;
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::ServiceExt;
+
+/// Read server-sent events until one contains `needle`.
+async fn wait_for(body: &mut Body, needle: &str) -> String {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let frame = body.frame().await.unwrap().unwrap();
+            if let Ok(data) = frame.into_data() {
+                let event = String::from_utf8(data.to_vec()).unwrap();
+                if event.contains(needle) {
+                    return event;
+                }
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn timeplan_progress_is_pushed() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+
+    let response = app
+        .router
+        .clone()
+        .oneshot(Request::get("/api/live").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let mut body = response.into_body();
+
+    let (status, _) = app
+        .post(Some(admin), "/api/command/timeplan_forward", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let event = wait_for(&mut body, "entry_started").await;
+    assert!(event.starts_with("event: timeplan"));
+
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/set_act_order",
+            json!({ "act_id": act_id, "order": 5 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    wait_for(&mut body, "timeplan_changed").await;
+}
+
+#[tokio::test]
+async fn category_changes_reload_the_timeplan() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let response = app
+        .router
+        .clone()
+        .oneshot(Request::get("/api/live").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let mut body = response.into_body();
+
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/move_category_down",
+            json!({ "name": "NEM" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    wait_for(&mut body, "timeplan_changed").await;
+
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/delete_category",
+            json!({ "name": "NEM" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    wait_for(&mut body, "timeplan_changed").await;
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use common::TestApp;
+use serde_json::json;
+use tower::ServiceExt;
+
+/// Log in from an address and return the status and the `Retry-After`
+/// header.
+async fn login(app: &TestApp, ip: &str, email: &str, password: &str) -> (StatusCode, Option<u64>) {
+    let request = Request::post("/api/command/login")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-forwarded-for", format!("203.0.113.99, {ip}"))
+        .body(Body::from(
+            json!({ "email": email, "password": password }).to_string(),
+        ))
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+    let retry_after = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .map(|value| value.to_str().unwrap().parse().unwrap());
+    (response.status(), retry_after)
+}
+
+#[tokio::test]
+async fn failed_logins_back_off_and_lock_out_the_account() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    app.register("trainer").await;
+    app.register("other").await;
+
+    for _ in 0..6 {
+        let (status, retry_after) =
+            login(&app, "198.51.100.1", "trainer@example.com", "wrong").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(retry_after, None);
+    }
+    // Even the right password is rejected while blocked, from any address
+    let (status, retry_after) =
+        login(&app, "198.51.100.2", "Trainer@Example.com", "Sup3r-Secret").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(retry_after.unwrap() >= 1);
+
+    // Hammering while blocked locks the account
+    let (_, log) = app
+        .get(
+            Some(admin),
+            "/api/query/list_audit_log?command=login_lockout",
+        )
+        .await;
+    assert_eq!(log, json!([]));
+    for _ in 0..3 {
+        let (status, _) = login(&app, "198.51.100.1", "trainer@example.com", "wrong").await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+    let (_, log) = app
+        .get(
+            Some(admin),
+            "/api/query/list_audit_log?command=login_lockout",
+        )
+        .await;
+    let log = log.as_array().unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["user_name"], "trainer");
+    assert_eq!(log[0]["payload"]["email"], "trainer@example.com");
+    assert_eq!(log[0]["payload"]["attempts"], 10);
+
+    let (status, retry_after) =
+        login(&app, "198.51.100.1", "trainer@example.com", "Sup3r-Secret").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(retry_after.unwrap() > 14 * 60);
+
+    // Other accounts from the same address are not affected
+    let (status, _) = login(&app, "198.51.100.1", "other@example.com", "Sup3r-Secret").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn unknown_emails_fail_like_wrong_passwords() {
+    let app = TestApp::new().await;
+    app.register("trainer").await;
+
+    let (unknown, _) = login(&app, "198.51.100.1", "nobody@example.com", "Sup3r-Secret").await;
+    let (wrong, _) = login(&app, "198.51.100.1", "trainer@example.com", "wrong").await;
+    assert_eq!(unknown, StatusCode::UNAUTHORIZED);
+    assert_eq!(wrong, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn password_reset_requests_are_limited() {
+    let app = TestApp::new().await;
+    app.register("trainer").await;
+    let mails = app.mailer.sent().len();
+
+    for _ in 0..4 {
+        let (status, _) = app
+            .post(
+                None,
+                "/api/command/request_password_reset",
+                json!({ "email": "trainer@example.com" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/request_password_reset",
+            json!({ "email": "trainer@example.com" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(app.mailer.sent().len(), mails + 4);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::{
+    audio,
+    loudness::{Loudness, LoudnessMeter, TARGET_LUFS},
+    song_analysis,
+};
+
+fn sine(sample_rate: u32, channels: usize, seconds: f64, amplitude: f32) -> Vec<f32> {
+    let frames = (sample_rate as f64 * seconds) as usize;
+    (0..frames)
+        .flat_map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            let sample = amplitude * f32::sin(2.0 * std::f32::consts::PI * 1000.0 * t);
+            std::iter::repeat_n(sample, channels)
+        })
+        .collect()
+}
+
+#[test]
+fn sine_has_the_reference_loudness() {
+    // A full scale 1 kHz sine in one channel measures -3.01 LUFS
+    let mut meter = LoudnessMeter::new(48000, 1);
+    meter.add(&sine(48000, 1, 5.0, 1.0));
+    let lufs = meter.integrated_lufs().unwrap();
+    assert!((lufs + 3.01).abs() < 0.05, "{lufs}");
+    assert!(meter.peak_dbfs().abs() < 0.01);
+
+    // Both channels add up, 20 dB lower at another sample rate
+    let mut meter = LoudnessMeter::new(44100, 2);
+    meter.add(&sine(44100, 2, 5.0, 0.1));
+    let lufs = meter.integrated_lufs().unwrap();
+    assert!((lufs + 20.0).abs() < 0.05, "{lufs}");
+}
+
+#[test]
+fn silence_is_gated() {
+    let mut meter = LoudnessMeter::new(48000, 1);
+    meter.add(&sine(48000, 1, 3.0, 0.1));
+    meter.add(&vec![0.0; 48000 * 10]);
+    // Silence is gated, only the blocks at the end of the sine lower it a bit
+    let lufs = meter.integrated_lufs().unwrap();
+    assert!((lufs + 23.01).abs() < 0.3, "{lufs}");
+
+    let mut meter = LoudnessMeter::new(48000, 1);
+    meter.add(&vec![0.0; 48000]);
+    assert_eq!(meter.integrated_lufs(), None);
+}
+
+#[test]
+fn gain_keeps_the_peak_below_the_limit() {
+    let quiet = Loudness {
+        integrated_lufs: -30.0,
+        peak_dbfs: -20.0,
+    };
+    assert_eq!(quiet.normalization_gain_db(), TARGET_LUFS + 30.0);
+    let spiky = Loudness {
+        integrated_lufs: -30.0,
+        peak_dbfs: -3.0,
+    };
+    assert_eq!(spiky.normalization_gain_db(), 2.0);
+}
+
+#[tokio::test]
+async fn songs_are_analyzed_and_normalized() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+    let (status, _) = app
+        .upload(
+            Some(owner),
+            &format!("/api/command/save_act_song?act_id={act_id}"),
+            "Kür.wav",
+            &common::wav(3),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let analyzed = song_analysis::analyze_pending(&app.db, app.storage.as_ref(), false)
+        .await
+        .unwrap();
+    assert_eq!(analyzed, 1);
+    let (_, act) = app
+        .get(Some(admin), &format!("/api/query/get_act?act_id={act_id}"))
+        .await;
+    // Half amplitude and a bit less weight at 440 Hz
+    let lufs = act["song_loudness_lufs"].as_f64().unwrap();
+    assert!((-10.0..-9.0).contains(&lufs), "{lufs}");
+    assert_eq!(act["song_normalized_file"], serde_json::Value::Null);
+
+    // Only the normalized copy is missing now
+    let analyzed = song_analysis::analyze_pending(&app.db, app.storage.as_ref(), true)
+        .await
+        .unwrap();
+    assert_eq!(analyzed, 1);
+    let (_, act) = app
+        .get(Some(admin), &format!("/api/query/get_act?act_id={act_id}"))
+        .await;
+    let normalized_file = act["song_normalized_file"].as_str().unwrap();
+    assert_eq!(normalized_file, format!("{act_id}.normalized.wav"));
+
+    let (status, _, normalized) = app
+        .download(Some(owner), &format!("/songs/{normalized_file}"))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let loudness = audio::measure_loudness(&normalized, Some("wav"))
+        .unwrap()
+        .unwrap();
+    assert!(
+        (loudness.integrated_lufs - TARGET_LUFS).abs() < 0.5,
+        "{loudness:?}"
+    );
+
+    let analyzed = song_analysis::analyze_pending(&app.db, app.storage.as_ref(), true)
+        .await
+        .unwrap();
+    assert_eq!(analyzed, 0);
+}
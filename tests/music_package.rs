@@ -0,0 +1,92 @@
+mod common;
+
+use std::io::{Cursor, Read};
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::music_package::file_name_part;
+use serde_json::json;
+
+#[test]
+fn file_names_are_safe() {
+    assert_eq!(file_name_part("Anna Tester & Berta"), "Anna-Tester-Berta");
+    assert_eq!(file_name_part("  Kür (U15)  "), "Kür-U15");
+    assert_eq!(file_name_part("../etc"), "etc");
+}
+
+#[tokio::test]
+async fn checked_songs_are_packed_in_timeplan_order() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, anna) = app.create_starter(club, "Anna").await;
+    let (_, berta) = app.create_starter(club, "Berta").await;
+    let (_, clara) = app.create_starter(club, "Clara").await;
+
+    for act_id in [anna, berta, clara] {
+        let (status, _) = app
+            .upload(
+                Some(owner),
+                &format!("/api/command/save_act_song?act_id={act_id}"),
+                "song.wav",
+                &common::wav(1),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    // Berta's song isn't checked, so it isn't packed
+    for act_id in [anna, clara] {
+        let (status, _) = app
+            .post(
+                Some(admin),
+                "/api/command/set_song_checked",
+                json!({ "act_id": act_id, "checked": true }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, _, _) = app
+        .download(Some(owner), "/api/query/export_music_package")
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, content_type, zip) = app
+        .download(Some(admin), "/api/query/export_music_package")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/zip"));
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let songs: Vec<&String> = names.iter().filter(|name| name.ends_with(".wav")).collect();
+    assert_eq!(songs.len(), 2);
+    assert!(songs[0].starts_with("001_") && songs[0].ends_with("_Anna-Tester.wav"));
+    assert!(songs[1].starts_with("003_") && songs[1].ends_with("_Clara-Tester.wav"));
+
+    let playlist = names
+        .iter()
+        .find(|name| name.starts_with("playlist_") && name.ends_with(".m3u8"))
+        .unwrap()
+        .clone();
+    let mut content = String::new();
+    archive
+        .by_name(&playlist)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert!(content.starts_with("#EXTM3U\n#EXTINF:1,1 Anna Tester"));
+    assert!(content.contains(songs[1].as_str()));
+
+    let mut manifest = String::new();
+    archive
+        .by_name("manifest.json")
+        .unwrap()
+        .read_to_string(&mut manifest)
+        .unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest["songs"].as_array().unwrap().len(), 2);
+    assert_eq!(manifest["songs"][1]["start_number"], 3);
+    assert_eq!(manifest["songs"][1]["original_file_name"], "song.wav");
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, token_from_link};
+use nrw_freestyle_cup_registration::song_storage;
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn register_to_startlist() {
+    let app = TestApp::new().await;
+
+    let (status, registered) = app
+        .post(
+            None,
+            "/api/command/register",
+            json!({
+                "name": "Trainer",
+                "email": "trainer@example.com",
+                "password": "Sup3r-Secret",
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let user_id: Uuid = registered["user_id"].as_str().unwrap().parse().unwrap();
+
+    let mails = app.mailer.sent();
+    assert_eq!(mails.len(), 1);
+    assert_eq!(mails[0].to, "trainer@example.com");
+    let token = token_from_link(&mails[0].body, "/verify_email?token=");
+
+    let (status, _) = app
+        .post(None, "/api/command/verify_email", json!({ "token": token }))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, user) = app.get(Some(user_id), "/api/query/whoami").await;
+    assert_eq!(user["email_verified"], true);
+
+    let (status, club) = app
+        .post(
+            Some(user_id),
+            "/api/command/create_club",
+            json!({ "name": "RSV Testhausen" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let club_id = club["club_id"].as_str().unwrap().to_string();
+
+    let (status, _) = app
+        .post(
+            Some(user_id),
+            "/api/command/add_club_starter",
+            json!({
+                "club_id": club_id,
+                "firstname": "Erika",
+                "lastname": "Musterfrau",
+                "birthdate": "2013-06-01T00:00:00Z",
+                "single_sonderpokal": false,
+                "single_male": false,
+                "single_female": true,
+                "pair_sonderpokal": false,
+                "pair": false,
+                "partner_id": null,
+                "partner_name": null,
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, acts) = app
+        .get(
+            Some(user_id),
+            &format!("/api/query/list_club_acts?club_id={club_id}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let act_id = acts[0]["id"].as_str().unwrap().to_string();
+
+    let (status, _) = app
+        .upload(
+            Some(user_id),
+            &format!("/api/command/save_act_song?act_id={act_id}"),
+            "Kür.wav",
+            &common::wav(1),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let file = song_storage::file_name(&song_storage::sha256(&common::wav(1)), "wav");
+    let stored = std::fs::read(app.data_path.join(file)).unwrap();
+    assert_eq!(stored, common::wav(1));
+
+    let (status, startlist) = app.get(None, "/api/query/startlist").await;
+    assert_eq!(status, StatusCode::OK);
+    let startlist = startlist.as_array().unwrap();
+    assert_eq!(startlist.len(), 1);
+    assert_eq!(startlist[0]["id"], act_id);
+    assert_eq!(startlist[0]["participants"][0]["firstname"], "Erika");
+    assert_eq!(
+        startlist[0]["participants"][0]["club_name"],
+        "RSV Testhausen"
+    );
+}
+
+#[tokio::test]
+async fn password_reset_mail_allows_new_login() {
+    let app = TestApp::new().await;
+    app.post(
+        None,
+        "/api/command/register",
+        json!({
+            "name": "Forgetful",
+            "email": "forgetful@example.com",
+            "password": "Sup3r-Secret",
+        }),
+    )
+    .await;
+
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/request_password_reset",
+            json!({ "email": "forgetful@example.com" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let mails = app.mailer.sent();
+    let token = token_from_link(&mails.last().unwrap().body, "/reset_password?token=");
+
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/reset_password",
+            json!({ "token": token, "new_password": "An0ther-Secret" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/login",
+            json!({ "email": "forgetful@example.com", "password": "An0ther-Secret" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
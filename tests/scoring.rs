@@ -0,0 +1,318 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::{
+    judge::JudgeRole,
+    scoring::{ScoreCriterion, ScoreEntry, format_points, rank, score_act},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+struct Fixture {
+    app: TestApp,
+    admin: Uuid,
+    anna: Uuid,
+    technique: Uuid,
+    performance: Uuid,
+    dismount: Uuid,
+}
+
+/// Two acts of the home club and one guest judge per role in their panel.
+async fn fixture() -> Fixture {
+    let app = TestApp::new().await;
+    let setup = app
+        .panel_setup(json!({
+            "performance": 1,
+            "technique": 1,
+            "dismount": 1,
+            "hospitation": 0,
+        }))
+        .await;
+    app.create_starter(setup.home_club, "Berta").await;
+
+    let mut judges = vec![];
+    for role in ["technique", "performance", "dismount"] {
+        let qualifications = json!([{ "category": setup.category, "role": role }]);
+        judges.push(
+            app.add_judge(setup.guest, setup.guest_club, qualifications)
+                .await,
+        );
+    }
+    app.post(
+        Some(setup.admin),
+        "/api/command/assign_judge_panels",
+        json!({}),
+    )
+    .await;
+
+    sqlx::query("UPDATE acts SET started_at = datetime('now') WHERE id = ?")
+        .bind(setup.anna)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    Fixture {
+        app,
+        admin: setup.admin,
+        anna: setup.anna,
+        technique: judges[0],
+        performance: judges[1],
+        dismount: judges[2],
+    }
+}
+
+async fn submit(
+    f: &Fixture,
+    judge_id: Uuid,
+    role: &str,
+    criteria: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    f.app
+        .post(
+            Some(f.admin),
+            "/api/command/submit_score",
+            json!({ "judge_id": judge_id, "role": role, "criteria": criteria }),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn scores_of_the_running_act_are_ranked() {
+    let f = fixture().await;
+    let (status, body) = submit(
+        &f,
+        f.technique,
+        "technique",
+        json!([
+            { "criterion": "quantity", "value": 7 },
+            { "criterion": "mastery", "value": 8 },
+            { "criterion": "difficulty", "value": 9 },
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["act_id"], json!(f.anna));
+    submit(
+        &f,
+        f.performance,
+        "performance",
+        json!([
+            { "criterion": "presence", "value": 6 },
+            { "criterion": "composition", "value": 6 },
+            { "criterion": "interpretation", "value": 6 },
+        ]),
+    )
+    .await;
+    submit(
+        &f,
+        f.dismount,
+        "dismount",
+        json!([
+            { "criterion": "minor_dismounts", "value": 2 },
+            { "criterion": "major_dismounts", "value": 1 },
+        ]),
+    )
+    .await;
+
+    let (status, results) = f.app.get(Some(f.admin), "/api/query/results").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results.as_array().unwrap().len(), 2);
+    assert_eq!(results[0]["id"], json!(f.anna));
+    assert_eq!(results[0]["technique"], 40.0);
+    assert_eq!(results[0]["performance"], 30.0);
+    assert_eq!(results[0]["dismount_deduction"], 2.0);
+    assert_eq!(results[0]["total"], 68.0);
+    assert_eq!(results[0]["rank"], 1);
+    assert_eq!(results[1]["total"], json!(null));
+    assert_eq!(results[1]["rank"], json!(null));
+}
+
+#[tokio::test]
+async fn judges_submit_their_own_scores() {
+    let f = fixture().await;
+    let criteria = json!([
+        { "criterion": "quantity", "value": 7 },
+        { "criterion": "mastery", "value": 8 },
+        { "criterion": "difficulty", "value": 9 },
+    ]);
+    let body = json!({ "judge_id": f.technique, "role": "technique", "criteria": criteria });
+
+    let user = f.app.create_user("user", false).await;
+    let (status, _) = f
+        .app
+        .post(Some(user), "/api/command/submit_score", body.clone())
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The judges of the fixture are all registered with this mail
+    let judy = f.app.create_user("judy", false).await;
+    let (status, body) = f
+        .app
+        .post(Some(judy), "/api/command/submit_score", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["act_id"], json!(f.anna));
+}
+
+#[tokio::test]
+async fn categories_without_an_order_have_results() {
+    let f = fixture().await;
+    // Like a category added by an admin
+    sqlx::query(r#"UPDATE categories SET "order" = NULL"#)
+        .execute(&f.app.db)
+        .await
+        .unwrap();
+
+    let (status, results) = f.app.get(Some(f.admin), "/api/query/results").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(results[0]["category_order"], json!(null));
+    let (status, _, _) = f
+        .app
+        .download(Some(f.admin), "/api/query/get_results_csv")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn scores_are_validated() {
+    let f = fixture().await;
+    // Missing criterion
+    let (status, _) = submit(
+        &f,
+        f.technique,
+        "technique",
+        json!([
+            { "criterion": "quantity", "value": 7 },
+            { "criterion": "mastery", "value": 8 },
+        ]),
+    )
+    .await;
+    assert!(!status.is_success());
+    // Out of range
+    let (status, _) = submit(
+        &f,
+        f.technique,
+        "technique",
+        json!([
+            { "criterion": "quantity", "value": 11 },
+            { "criterion": "mastery", "value": 8 },
+            { "criterion": "difficulty", "value": 9 },
+        ]),
+    )
+    .await;
+    assert!(!status.is_success());
+    // Not in the panel with this role
+    let (status, _) = submit(
+        &f,
+        f.performance,
+        "technique",
+        json!([
+            { "criterion": "quantity", "value": 7 },
+            { "criterion": "mastery", "value": 8 },
+            { "criterion": "difficulty", "value": 9 },
+        ]),
+    )
+    .await;
+    assert!(!status.is_success());
+
+    let user = f.app.create_user("user", false).await;
+    let (status, _) = f.app.get(Some(user), "/api/query/results").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn results_are_exported() {
+    let f = fixture().await;
+    submit(
+        &f,
+        f.technique,
+        "technique",
+        json!([
+            { "criterion": "quantity", "value": 7 },
+            { "criterion": "mastery", "value": 8 },
+            { "criterion": "difficulty", "value": 9 },
+        ]),
+    )
+    .await;
+    submit(
+        &f,
+        f.performance,
+        "performance",
+        json!([
+            { "criterion": "presence", "value": 6 },
+            { "criterion": "composition", "value": 6 },
+            { "criterion": "interpretation", "value": 6 },
+        ]),
+    )
+    .await;
+
+    let (status, content_type, csv) = f
+        .app
+        .download(Some(f.admin), "/api/query/get_results_csv")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("text/csv; charset=utf-8"));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(&csv[3..]);
+    assert_eq!(reader.headers().unwrap().len(), 9);
+    assert!(reader.records().all(|record| record.unwrap().len() == 9));
+    let csv = String::from_utf8(csv).unwrap();
+    let anna = csv.lines().find(|line| line.contains("Anna")).unwrap();
+    assert!(anna.contains(";1;"));
+    assert!(anna.ends_with(";40,00;30,00;0,00;70,00"));
+
+    let (status, content_type, pdf) = f
+        .app
+        .download(Some(f.admin), "/api/query/get_results_pdf")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/pdf"));
+    assert!(pdf.starts_with(b"%PDF"));
+
+    let user = f.app.create_user("user", false).await;
+    let (status, _, _) = f
+        .app
+        .download(Some(user), "/api/query/get_results_pdf")
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn points_use_a_decimal_comma() {
+    assert_eq!(format_points(Some(68.5)), "68,50");
+    assert_eq!(format_points(None), "-");
+}
+
+fn technique(judge_id: Uuid, value: f64) -> Vec<ScoreEntry> {
+    ScoreCriterion::for_role(JudgeRole::Technique)
+        .iter()
+        .map(|criterion| ScoreEntry {
+            judge_id,
+            role: JudgeRole::Technique,
+            criterion: *criterion,
+            value,
+        })
+        .collect()
+}
+
+#[test]
+fn highest_and_lowest_judges_are_dropped() {
+    let entries: Vec<ScoreEntry> = [0.0, 5.0, 6.0, 7.0, 10.0]
+        .into_iter()
+        .flat_map(|value| technique(Uuid::now_v7(), value))
+        .collect();
+    let score = score_act(&entries);
+    assert_eq!(score.technique, Some(30.0));
+    // No performance yet
+    assert_eq!(score.total, None);
+}
+
+#[test]
+fn equal_totals_share_a_rank() {
+    assert_eq!(
+        rank(&[Some(50.0), Some(70.0), None, Some(50.0), Some(40.0)]),
+        [Some(2), Some(1), None, Some(2), Some(4)]
+    );
+}
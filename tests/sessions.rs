@@ -0,0 +1,151 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{TestApp, token_from_link};
+use serde_json::json;
+
+#[tokio::test]
+async fn refresh_tokens_are_rotated_and_reuse_revokes_the_session() {
+    let app = TestApp::new().await;
+    let first = app.register("trainer").await;
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &first["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A refresh token is no access token
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &first["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Without an access token the refresh token is rotated
+    let (status, _, second) = app
+        .get_with_cookies(&[("refresh", &first["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(second.contains_key("jwt"));
+    assert_ne!(second["refresh"], first["refresh"]);
+
+    // Requests sent in parallel to the refresh still work
+    let (status, _, _) = app
+        .get_with_cookies(
+            &[("jwt", "expired"), ("refresh", &first["refresh"])],
+            "/api/query/whoami",
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, third) = app
+        .get_with_cookies(&[("refresh", &second["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The first refresh token is outdated now, somebody must have stolen it
+    let (status, _, stolen) = app
+        .get_with_cookies(&[("refresh", &first["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(stolen.is_empty());
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &third["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = app
+        .get_with_cookies(&[("refresh", &third["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn logout_revokes_the_session() {
+    let app = TestApp::new().await;
+    app.register("trainer").await;
+    let (_, laptop) = app.login("trainer").await;
+    let (_, phone) = app.login("trainer").await;
+
+    let (status, _, removed) = app
+        .post_with_cookies(&[("jwt", &laptop["jwt"])], "/api/command/logout", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(removed["jwt"], "");
+    assert_eq!(removed["refresh"], "");
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &laptop["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = app
+        .get_with_cookies(&[("refresh", &laptop["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &phone["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn logout_everywhere_revokes_all_sessions_of_the_user() {
+    let app = TestApp::new().await;
+    let registered = app.register("trainer").await;
+    let (_, laptop) = app.login("trainer").await;
+    let other = app.register("other").await;
+
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &laptop["jwt"])],
+            "/api/command/logout_everywhere",
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    for session in [&registered, &laptop] {
+        let (status, _, _) = app
+            .get_with_cookies(&[("jwt", &session["jwt"])], "/api/query/whoami")
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _, _) = app
+            .get_with_cookies(&[("refresh", &session["refresh"])], "/api/query/whoami")
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &other["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn password_reset_revokes_all_sessions() {
+    let app = TestApp::new().await;
+    let session = app.register("forgetful").await;
+
+    app.post(
+        None,
+        "/api/command/request_password_reset",
+        json!({ "email": "forgetful@example.com" }),
+    )
+    .await;
+    let mails = app.mailer.sent();
+    let body = &mails.last().unwrap().body;
+    let token = token_from_link(body, "/reset_password?token=");
+    let (status, _) = app
+        .post(
+            None,
+            "/api/command/reset_password",
+            json!({ "token": token, "new_password": "An0ther-Secret" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, _) = app
+        .get_with_cookies(&[("jwt", &session["jwt"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = app
+        .get_with_cookies(&[("refresh", &session["refresh"])], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::song_storage::{file_name, sha256};
+use serde_json::json;
+
+#[tokio::test]
+async fn songs_are_only_sent_to_their_clubs_admins_and_music_operators() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let stranger = app.create_user("stranger", false).await;
+    let operator = app.create_user("operator", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    app.create_club(stranger, "RV Nachbarort").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+
+    let (status, _) = app
+        .upload(
+            Some(owner),
+            &format!("/api/command/save_act_song?act_id={act_id}"),
+            "Kür.wav",
+            &common::wav(1),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let song = format!("/songs/{}", file_name(&sha256(&common::wav(1)), "wav"));
+
+    let (status, _, _) = app.download(None, &song).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = app.download(Some(stranger), &song).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _, body) = app.download(Some(owner), &song).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, common::wav(1));
+    let (status, _, _) = app.download(Some(admin), &song).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, _) = app.download(Some(operator), &song).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let grant = json!({ "user_id": operator, "is_music_operator": true });
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/set_music_operator",
+            grant.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .post(Some(admin), "/api/command/set_music_operator", grant)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = app.download(Some(operator), &song).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .get(
+            Some(operator),
+            &format!("/api/query/get_act?act_id={act_id}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Unknown files of other clubs look the same as forbidden ones
+    let (status, _, _) = app.download(Some(stranger), "/songs/missing.wav").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _, _) = app.download(Some(admin), "/songs/missing.wav").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn signed_song_urls_work_without_login_until_they_expire() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let stranger = app.create_user("stranger", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+    let get_song_url = format!("/api/query/get_song_url?act_id={act_id}&normalized=true");
+
+    let (status, _) = app.get(Some(owner), &get_song_url).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    app.upload(
+        Some(owner),
+        &format!("/api/command/save_act_song?act_id={act_id}"),
+        "Kür.wav",
+        &common::wav(1),
+    )
+    .await;
+
+    let (status, _) = app.get(Some(stranger), &get_song_url).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, signed) = app.get(Some(owner), &get_song_url).await;
+    assert_eq!(status, StatusCode::OK);
+    let url = signed["url"].as_str().unwrap();
+    let file = file_name(&sha256(&common::wav(1)), "wav");
+    // Without the analysis there is no normalized copy yet
+    assert!(url.starts_with(&format!("/songs/{file}?token=")));
+    assert_eq!(signed["normalized"], false);
+
+    let (status, _, body) = app.download(None, url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, common::wav(1));
+
+    // The token only grants access to the signed song
+    let token = url.split_once("?token=").unwrap().1;
+    let (status, _, _) = app
+        .download(None, &format!("/songs/other.wav?token={token}"))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _, _) = app
+        .download(None, &format!("/songs/{file}?token=invalid"))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let expired = jsonwebtoken::encode(
+        &app.jwt.get_header(),
+        &json!({ "song": file, "exp": 1_000_000_000 }),
+        app.jwt.encoding_key(),
+    )
+    .unwrap();
+    let (status, _, _) = app
+        .download(None, &format!("/songs/{file}?token={expired}"))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // A login token is no song token
+    let (status, _, _) = app
+        .download(
+            None,
+            &format!("/songs/{file}?token={}", app.token(owner).await),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,326 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use common::TestApp;
+use futures_util::TryStreamExt;
+use http_body_util::BodyExt;
+use nrw_freestyle_cup_registration::{
+    song_storage::{
+        self, ARCHIVE_DIR, LocalSongStorage, OrphanAction, S3Config, S3SongStorage, SongStorage,
+        StorageError, file_name, sha256,
+    },
+    utils::delete_act,
+};
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn sorted<const N: usize>(files: [&String; N]) -> Vec<String> {
+    let mut files: Vec<String> = files.into_iter().cloned().collect();
+    files.sort();
+    files
+}
+
+#[tokio::test]
+async fn identical_songs_are_stored_once() {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, anna) = app.create_starter(club, "Anna").await;
+    let (_, berta) = app.create_starter(club, "Berta").await;
+
+    for act_id in [anna, anna, berta] {
+        let (status, _) = app
+            .upload(
+                Some(owner),
+                &format!("/api/command/save_act_song?act_id={act_id}"),
+                "song.wav",
+                &common::wav(1),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let files: Vec<_> = std::fs::read_dir(&app.data_path)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(files, [file_name(&sha256(&common::wav(1)), "wav")]);
+}
+
+#[tokio::test]
+async fn orphaned_songs_are_collected() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, anna) = app.create_starter(club, "Anna").await;
+    let (_, berta) = app.create_starter(club, "Berta").await;
+    for (act_id, seconds) in [(anna, 1), (berta, 2)] {
+        app.upload(
+            Some(owner),
+            &format!("/api/command/save_act_song?act_id={act_id}"),
+            "song.wav",
+            &common::wav(seconds),
+        )
+        .await;
+    }
+    let anna_file = file_name(&sha256(&common::wav(1)), "wav");
+    let berta_file = file_name(&sha256(&common::wav(2)), "wav");
+
+    // A song from before the content addressed storage
+    let legacy = format!("{anna}.wav");
+    std::fs::write(app.data_path.join(&legacy), common::wav(3)).unwrap();
+    sqlx::query("UPDATE act_song_versions SET file = ?, sha256 = NULL WHERE act_id = ?")
+        .bind(&legacy)
+        .bind(anna)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE acts SET song_file = ? WHERE id = ?")
+        .bind(&legacy)
+        .bind(anna)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    delete_act(&app.db, berta).await.unwrap();
+
+    let report = song_storage::collect_garbage(
+        &app.db,
+        app.storage.as_ref(),
+        OrphanAction::Report,
+        Duration::ZERO,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.files, 3);
+    assert_eq!(report.referenced_files, 1);
+    assert_eq!(
+        report.orphaned_files,
+        [anna_file.clone(), berta_file.clone()]
+    );
+    assert!(app.data_path.join(&berta_file).exists());
+
+    let report = song_storage::collect_garbage(
+        &app.db,
+        app.storage.as_ref(),
+        OrphanAction::Archive,
+        Duration::ZERO,
+    )
+    .await
+    .unwrap();
+    let legacy_file = file_name(&sha256(&common::wav(3)), "wav");
+    assert_eq!(report.adopted_files, 1);
+    assert_eq!(report.referenced_files, 1);
+    assert_eq!(
+        report.orphaned_files,
+        sorted([&anna_file, &legacy, &berta_file])
+    );
+    assert!(app.data_path.join(&legacy_file).exists());
+    assert!(!app.data_path.join(&berta_file).exists());
+    assert!(app.data_path.join(ARCHIVE_DIR).join(&berta_file).exists());
+
+    let (_, act) = app
+        .get(Some(admin), &format!("/api/query/get_act?act_id={anna}"))
+        .await;
+    assert_eq!(act["song_file"], legacy_file);
+
+    // Only admins collect, and files of running uploads are kept
+    let body = json!({ "action": "remove" });
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/collect_song_garbage",
+            body.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    std::fs::write(app.data_path.join("stray.mp3"), b"stray").unwrap();
+    let (status, report) = app
+        .post(Some(admin), "/api/command/collect_song_garbage", body)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["files"], 2);
+    assert_eq!(report["orphaned_files"], json!([]));
+}
+
+#[tokio::test]
+async fn storing_onto_an_aged_orphan_keeps_it() {
+    let app = TestApp::new().await;
+    let data = common::wav(1);
+    let sha256 = sha256(&data);
+    let file = file_name(&sha256, "wav");
+    app.storage.put(&file, data.clone().into()).await.unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(app.data_path.join(&file))
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() - 2 * song_storage::GARBAGE_MIN_AGE)
+        .unwrap();
+
+    // The upload stored the song but isn't recorded yet
+    song_storage::store(app.storage.as_ref(), &sha256, data.into(), "wav")
+        .await
+        .unwrap();
+    let report = song_storage::collect_garbage(
+        &app.db,
+        app.storage.as_ref(),
+        OrphanAction::Remove,
+        song_storage::GARBAGE_MIN_AGE,
+    )
+    .await
+    .unwrap();
+    assert!(report.orphaned_files.is_empty());
+    assert!(app.data_path.join(&file).exists());
+}
+
+/// The contract every storage backend has to keep.
+async fn exercise(storage: &dyn SongStorage) {
+    assert!(storage.list().await.unwrap().is_empty());
+    assert!(storage.head("song.mp3").await.unwrap().is_none());
+    assert!(matches!(
+        storage.read("song.mp3").await,
+        Err(StorageError::NotFound(_))
+    ));
+
+    storage.put("song.mp3", "0123456789".into()).await.unwrap();
+    assert_eq!(storage.head("song.mp3").await.unwrap().unwrap().size, 10);
+    assert_eq!(storage.read("song.mp3").await.unwrap(), "0123456789");
+    let part: Vec<_> = storage
+        .stream("song.mp3", 2..6)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(part.concat(), b"2345");
+
+    storage.put("other.wav", "other".into()).await.unwrap();
+    let mut names: Vec<_> = storage
+        .list()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|object| object.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, ["other.wav", "song.mp3"]);
+
+    storage.archive("other.wav").await.unwrap();
+    storage.delete("song.mp3").await.unwrap();
+    storage.delete("song.mp3").await.unwrap();
+    assert!(storage.list().await.unwrap().is_empty());
+    assert!(matches!(
+        storage.read("../secret").await,
+        Err(StorageError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn local_storage_keeps_the_contract() {
+    let path = std::env::temp_dir().join(format!("cup-storage-{}", Uuid::now_v7()));
+    exercise(&LocalSongStorage::new(&path)).await;
+    assert!(path.join(ARCHIVE_DIR).join("other.wav").exists());
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+/// Runs against a local MinIO if `MINIO_ENDPOINT` is set, e.g.
+/// `docker run -p 9000:9000 minio/minio server /data` with a bucket `songs`.
+#[tokio::test]
+async fn s3_storage_keeps_the_contract() {
+    let Ok(endpoint) = std::env::var("MINIO_ENDPOINT") else {
+        eprintln!("MINIO_ENDPOINT is not set, skipping");
+        return;
+    };
+    let env = |name: &str, default: &str| std::env::var(name).unwrap_or(default.to_string());
+    let storage = S3SongStorage::new(S3Config {
+        endpoint: Some(endpoint),
+        region: env("MINIO_REGION", "us-east-1"),
+        bucket: env("MINIO_BUCKET", "songs"),
+        access_key_id: env("MINIO_ACCESS_KEY", "minioadmin"),
+        secret_access_key: env("MINIO_SECRET_KEY", "minioadmin"),
+        prefix: Some(format!("test-{}", Uuid::now_v7())),
+    })
+    .unwrap();
+    exercise(&storage).await;
+}
+
+async fn get_song(
+    app: &TestApp,
+    user_id: Uuid,
+    path: &str,
+    range: Option<&str>,
+) -> (StatusCode, Vec<(String, String)>, Vec<u8>) {
+    let mut request =
+        Request::get(path).header(header::COOKIE, format!("jwt={}", app.token(user_id).await));
+    if let Some(range) = range {
+        request = request.header(header::RANGE, range);
+    }
+    let response = app
+        .router
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = [
+        header::CONTENT_TYPE,
+        header::CONTENT_RANGE,
+        header::ACCEPT_RANGES,
+    ]
+    .into_iter()
+    .filter_map(|name| {
+        let value = response.headers().get(&name)?.to_str().ok()?.to_string();
+        Some((name.to_string(), value))
+    })
+    .collect();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, headers, body.to_vec())
+}
+
+#[tokio::test]
+async fn songs_are_served_with_ranges() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    app.storage
+        .put("song.mp3", "0123456789".into())
+        .await
+        .unwrap();
+
+    let (status, headers, body) = get_song(&app, admin, "/songs/song.mp3", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, b"0123456789");
+    assert!(headers.contains(&("content-type".to_string(), "audio/mpeg".to_string())));
+    assert!(headers.contains(&("accept-ranges".to_string(), "bytes".to_string())));
+
+    let (status, headers, body) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=2-5")).await;
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body, b"2345");
+    assert!(headers.contains(&("content-range".to_string(), "bytes 2-5/10".to_string())));
+
+    let (status, _, body) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=7-")).await;
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(body, b"789");
+    let (_, _, body) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=-2")).await;
+    assert_eq!(body, b"89");
+    let (_, _, body) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=8-100")).await;
+    assert_eq!(body, b"89");
+
+    let (status, headers, _) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=10-")).await;
+    assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert!(headers.contains(&("content-range".to_string(), "bytes */10".to_string())));
+
+    // Multiple ranges aren't supported, the whole song is sent
+    let (status, _, body) = get_song(&app, admin, "/songs/song.mp3", Some("bytes=0-1,4-5")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.len(), 10);
+
+    let (status, _, _) = get_song(&app, admin, "/songs/missing.mp3", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = get_song(&app, admin, "/songs/..%2Fdb.sqlite", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
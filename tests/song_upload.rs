@@ -0,0 +1,76 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::{
+    audio::{AudioError, probe},
+    song_storage::{file_name, sha256},
+};
+
+#[test]
+fn probe_reads_duration_and_codec() {
+    let info = probe(&common::wav(3), Some("wav")).unwrap();
+    assert_eq!(info.duration_seconds, 3.0);
+    assert_eq!(info.codec, "pcm_s16le");
+    // 8 kHz 16 bit mono and the header
+    assert_eq!(info.bitrate, 128_117);
+
+    // The extension is only a hint
+    assert!(probe(&common::wav(1), Some("mp3")).is_ok());
+    assert!(matches!(
+        probe(b"ID3 not really music", Some("mp3")),
+        Err(AudioError::NotAudio)
+    ));
+}
+
+#[tokio::test]
+async fn uploads_are_validated_and_described() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+    let path = format!("/api/command/save_act_song?act_id={act_id}");
+
+    let not_music = b"<html>no music</html>";
+    let (status, _) = app.upload(Some(owner), &path, "song.mp3", not_music).await;
+    assert!(!status.is_success());
+    assert!(
+        !app.data_path
+            .join(file_name(&sha256(not_music), "mp3"))
+            .exists()
+    );
+
+    sqlx::query(
+        "UPDATE categories SET act_duration_seconds = 2 WHERE name = (SELECT category FROM view_act WHERE id = ?)",
+    )
+    .bind(act_id)
+    .execute(&app.db)
+    .await
+    .unwrap();
+    let (status, body) = app
+        .upload(Some(owner), &path, "Kür.WAV", &common::wav(3))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["song"]["duration_seconds"], 3.0);
+    assert_eq!(body["too_long"], true);
+    assert!(
+        app.data_path
+            .join(file_name(&sha256(&common::wav(3)), "wav"))
+            .exists()
+    );
+
+    let (status, act) = app
+        .get(Some(admin), &format!("/api/query/get_act?act_id={act_id}"))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(act["song_duration_seconds"], 3.0);
+    assert_eq!(act["song_codec"], "pcm_s16le");
+    assert_eq!(act["song_too_long"], true);
+
+    let (status, body) = app
+        .upload(Some(owner), &path, "Kür.wav", &common::wav(1))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["too_long"], false);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::song_storage::{file_name, sha256};
+use serde_json::json;
+
+#[tokio::test]
+async fn uploads_are_versioned_and_can_be_rolled_back() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    let (_, act_id) = app.create_starter(club, "Anna").await;
+    let upload = format!("/api/command/save_act_song?act_id={act_id}");
+    let get_act = format!("/api/query/get_act?act_id={act_id}");
+
+    let (status, body) = app
+        .upload(Some(owner), &upload, "Kür.wav", &common::wav(1))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["version"], 1);
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/set_song_checked",
+            json!({ "act_id": act_id, "checked": true }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A new upload is unchecked and keeps the first file
+    let (status, body) = app
+        .upload(Some(owner), &upload, "Kür neu.mp3", &common::wav(2))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["version"], 2);
+    let (_, act) = app.get(Some(admin), &get_act).await;
+    assert_eq!(act["song_checked"], false);
+    let first_file = file_name(&sha256(&common::wav(1)), "wav");
+    assert_eq!(act["song_file"], file_name(&sha256(&common::wav(2)), "mp3"));
+    assert!(app.data_path.join(&first_file).exists());
+
+    let (status, versions) = app
+        .get(
+            Some(owner),
+            &format!("/api/query/list_act_song_versions?act_id={act_id}"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let versions = versions.as_array().unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0]["version"], 2);
+    assert_eq!(versions[0]["current"], true);
+    assert_eq!(versions[0]["uploaded_by"], "owner");
+    assert_eq!(versions[1]["original_file_name"], "Kür.wav");
+    assert_eq!(versions[1]["sha256"], sha256(&common::wav(1)));
+    assert_eq!(versions[1]["checked"], true);
+    assert_eq!(versions[1]["current"], false);
+
+    let rollback = json!({ "act_id": act_id, "version": 1 });
+    let (status, _) = app
+        .post(
+            Some(owner),
+            "/api/command/rollback_act_song",
+            rollback.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .post(
+            Some(admin),
+            "/api/command/rollback_act_song",
+            json!({ "act_id": act_id, "version": 7 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // The check of the first version comes back with it
+    let (status, _) = app
+        .post(Some(admin), "/api/command/rollback_act_song", rollback)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, act) = app.get(Some(admin), &get_act).await;
+    assert_eq!(act["song_file"], first_file);
+    assert_eq!(act["song_file_name"], "Kür.wav");
+    assert_eq!(act["song_duration_seconds"], 1.0);
+    assert_eq!(act["song_checked"], true);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::startlist_export::{StartlistColumn, parse_columns};
+
+async fn fixture() -> TestApp {
+    let app = TestApp::new().await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV \"Einrad\", Heimstadt").await;
+    app.create_starter(club, "Anna").await;
+    app.create_starter(club, "Berta").await;
+    app
+}
+
+#[tokio::test]
+async fn startlist_csv_quotes_fields() {
+    let app = fixture().await;
+    let (status, content_type, csv) = app
+        .download(
+            None,
+            "/api/query/get_startlist_csv?columns=start_number,names,club",
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("text/csv; charset=utf-8"));
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+    assert_eq!(lines[0], "Startnummer,Name,Verein");
+    assert_eq!(lines[1], "1,Anna Tester,\"RSV \"\"Einrad\"\", Heimstadt\"");
+    assert_eq!(lines[2], "2,Berta Tester,\"RSV \"\"Einrad\"\", Heimstadt\"");
+}
+
+#[tokio::test]
+async fn startlist_xlsx_is_a_workbook() {
+    let app = fixture().await;
+    let (status, content_type, xlsx) = app.download(None, "/api/query/get_startlist_xlsx").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        content_type.as_deref(),
+        Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+    );
+    // XLSX files are ZIP archives
+    assert!(xlsx.starts_with(b"PK"));
+
+    let (status, _, _) = app
+        .download(
+            None,
+            "/api/query/get_startlist_xlsx?columns=names,shoe_size",
+        )
+        .await;
+    assert!(!status.is_success());
+}
+
+#[test]
+fn all_columns_are_exported_by_default() {
+    assert_eq!(parse_columns(None).unwrap(), StartlistColumn::ALL);
+    assert_eq!(parse_columns(Some("")).unwrap(), StartlistColumn::ALL);
+    assert_eq!(
+        parse_columns(Some("time, song_file")).unwrap(),
+        [StartlistColumn::Time, StartlistColumn::SongFile]
+    );
+    assert!(parse_columns(Some("time,unknown")).is_err());
+}
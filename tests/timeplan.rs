@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use nrw_freestyle_cup_registration::timeplan::{
+    PlanAct, PlanCategory, PlanEntry, Timeplan, TimeplanEntry, TimeplanItemStatus, predict,
+};
+use time::{Duration, OffsetDateTime, macros::datetime};
+use uuid::Uuid;
+
+const START: OffsetDateTime = datetime!(2026-04-19 10:00 UTC);
+
+fn act() -> PlanAct {
+    PlanAct {
+        id: Uuid::now_v7(),
+        ..Default::default()
+    }
+}
+
+/// Two minutes per act and one minute for the judges.
+fn category(einfahrzeit_seconds: i64, acts: Vec<PlanAct>) -> HashMap<String, PlanCategory> {
+    HashMap::from([(
+        "NEM".to_string(),
+        PlanCategory {
+            description: "Nachwuchs Einzel".to_string(),
+            einfahrzeit_seconds,
+            act_duration_seconds: 120,
+            judge_duration_seconds: 60,
+            acts,
+            ..Default::default()
+        },
+    )])
+}
+
+fn category_entry(id: i64, earliest_start_time: Option<OffsetDateTime>) -> PlanEntry {
+    PlanEntry {
+        id,
+        earliest_start_time,
+        category: Some("NEM".to_string()),
+        ..Default::default()
+    }
+}
+
+fn break_entry(id: i64, earliest_start_time: Option<OffsetDateTime>) -> PlanEntry {
+    PlanEntry {
+        id,
+        earliest_start_time,
+        duration_seconds: Some(1800),
+        label: Some("Pause".to_string()),
+        ..Default::default()
+    }
+}
+
+fn act_starts(timeplan: &Timeplan, item: usize) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    match &timeplan.items[item].timeplan_entry {
+        TimeplanEntry::Category { acts, .. } => acts
+            .iter()
+            .map(|act| (act.planned_start, act.predicted_start))
+            .collect(),
+        TimeplanEntry::Custom { .. } => vec![],
+    }
+}
+
+#[test]
+fn plan_before_the_competition_is_not_delayed() {
+    let entries = [category_entry(1, Some(START)), break_entry(2, None)];
+    let timeplan = predict(
+        START - Duration::days(1),
+        &entries,
+        &category(300, vec![act(), act()]),
+    )
+    .unwrap();
+
+    assert_eq!(timeplan.offset, 0);
+    assert_eq!(
+        act_starts(&timeplan, 0),
+        [
+            (
+                datetime!(2026-04-19 10:05 UTC),
+                datetime!(2026-04-19 10:05 UTC)
+            ),
+            (
+                datetime!(2026-04-19 10:08 UTC),
+                datetime!(2026-04-19 10:08 UTC)
+            ),
+        ]
+    );
+    assert_eq!(timeplan.items[0].planned_duration, 300 + 2 * 180);
+    assert_eq!(
+        timeplan.items[1].planned_start,
+        datetime!(2026-04-19 10:11 UTC)
+    );
+    assert_eq!(
+        timeplan.items[1].planned_end,
+        datetime!(2026-04-19 10:41 UTC)
+    );
+}
+
+#[test]
+fn nothing_is_predicted_before_now() {
+    let now = START + Duration::minutes(10);
+    let entries = [category_entry(1, Some(START)), break_entry(2, None)];
+    let timeplan = predict(now, &entries, &category(300, vec![act(), act()])).unwrap();
+
+    assert_eq!(timeplan.offset, 600);
+    assert_eq!(
+        act_starts(&timeplan, 0),
+        [
+            (
+                datetime!(2026-04-19 10:05 UTC),
+                datetime!(2026-04-19 10:15 UTC)
+            ),
+            (
+                datetime!(2026-04-19 10:08 UTC),
+                datetime!(2026-04-19 10:18 UTC)
+            ),
+        ]
+    );
+    assert_eq!(
+        timeplan.items[1].predicted_start,
+        datetime!(2026-04-19 10:21 UTC)
+    );
+}
+
+#[test]
+fn earliest_start_time_absorbs_delays() {
+    let now = START + Duration::minutes(10);
+    let entries = [
+        break_entry(1, Some(START)),
+        category_entry(2, Some(datetime!(2026-04-19 11:00 UTC))),
+    ];
+    let timeplan = predict(now, &entries, &category(300, vec![act()])).unwrap();
+
+    assert_eq!(
+        timeplan.items[0].predicted_end,
+        datetime!(2026-04-19 10:40 UTC)
+    );
+    assert_eq!(
+        timeplan.items[1].planned_start,
+        datetime!(2026-04-19 11:00 UTC)
+    );
+    assert_eq!(
+        timeplan.items[1].predicted_start,
+        datetime!(2026-04-19 11:00 UTC)
+    );
+    assert_eq!(
+        act_starts(&timeplan, 1),
+        [(
+            datetime!(2026-04-19 11:05 UTC),
+            datetime!(2026-04-19 11:05 UTC)
+        )]
+    );
+}
+
+#[test]
+fn acts_start_immediately_without_einfahrzeit() {
+    let entries = [category_entry(1, Some(START))];
+    let timeplan = predict(START, &entries, &category(0, vec![act()])).unwrap();
+
+    assert_eq!(act_starts(&timeplan, 0), [(START, START)]);
+    assert_eq!(timeplan.items[0].planned_end, START + Duration::minutes(3));
+}
+
+#[test]
+fn running_and_ended_acts_move_the_prediction() {
+    let mut ended = act();
+    ended.started_at = Some(datetime!(2026-04-19 10:07 UTC));
+    ended.ended_at = Some(datetime!(2026-04-19 10:10 UTC));
+    let mut running = act();
+    running.started_at = Some(datetime!(2026-04-19 10:12 UTC));
+    let mut entry = category_entry(1, Some(START));
+    entry.started_at = Some(START);
+
+    let now = datetime!(2026-04-19 10:13 UTC);
+    let timeplan = predict(now, &[entry], &category(300, vec![ended, running, act()])).unwrap();
+
+    let TimeplanEntry::Category { acts, .. } = &timeplan.items[0].timeplan_entry else {
+        panic!("category expected");
+    };
+    assert_eq!(acts[0].status, TimeplanItemStatus::Ended);
+    assert_eq!(acts[0].predicted_end, datetime!(2026-04-19 10:10 UTC));
+    assert_eq!(acts[1].status, TimeplanItemStatus::Started);
+    assert_eq!(acts[1].predicted_start, datetime!(2026-04-19 10:12 UTC));
+    assert_eq!(acts[2].status, TimeplanItemStatus::Planned);
+    assert_eq!(acts[2].planned_start, datetime!(2026-04-19 10:11 UTC));
+    assert_eq!(acts[2].predicted_start, datetime!(2026-04-19 10:15 UTC));
+    // The running act was planned for 10:08
+    assert_eq!(timeplan.offset, 240);
+    assert_eq!(timeplan.items[0].status, TimeplanItemStatus::Started);
+}
+
+#[test]
+fn invalid_timeplans_are_rejected() {
+    assert!(predict(START, &[], &HashMap::new()).is_err());
+    assert!(predict(START, &[category_entry(1, None)], &category(0, vec![])).is_err());
+    assert!(predict(START, &[category_entry(1, Some(START))], &HashMap::new()).is_err());
+}
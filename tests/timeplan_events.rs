@@ -0,0 +1,158 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use nrw_freestyle_cup_registration::timeplan_events::{TimeplanEvent, TimeplanEventKind, replay};
+use serde_json::json;
+use uuid::Uuid;
+
+fn event(id: i64, step: i64, kind: TimeplanEventKind) -> TimeplanEvent {
+    TimeplanEvent {
+        id,
+        step,
+        kind,
+        timeplan_id: Some(1),
+        act_id: None,
+    }
+}
+
+#[test]
+fn undo_and_redo_replay_whole_steps() {
+    use TimeplanEventKind::*;
+
+    let mut events = vec![
+        event(1, 1, EntryStarted),
+        event(2, 1, ActStarted),
+        event(3, 2, ActEnded),
+        event(4, 2, EntryEnded),
+        event(5, 2, Undo),
+    ];
+    let history = replay(&events);
+    assert_eq!(history.applied, [1]);
+    assert_eq!(history.undone, [2]);
+    assert_eq!(
+        history.changes.iter().map(|e| e.id).collect::<Vec<_>>(),
+        [1, 2]
+    );
+    assert_eq!(history.undo_target(), Some(1));
+    assert_eq!(history.redo_target(), Some(2));
+
+    events.push(event(6, 1, Undo));
+    events.push(event(7, 1, Redo));
+    let history = replay(&events);
+    assert_eq!(history.applied, [1]);
+    assert_eq!(history.redo_target(), Some(2));
+
+    events.push(event(8, 2, Redo));
+    let history = replay(&events);
+    assert_eq!(history.applied, [1, 2]);
+    assert_eq!(history.changes.len(), 4);
+    assert_eq!(history.redo_target(), None);
+}
+
+#[test]
+fn a_new_step_discards_the_undone_steps() {
+    use TimeplanEventKind::*;
+
+    let history = replay(&[
+        event(1, 1, EntryStarted),
+        event(2, 2, EntryEnded),
+        event(3, 2, Undo),
+        event(4, 3, EntryEnded),
+        // Stale redo of a discarded step
+        event(5, 2, Redo),
+    ]);
+    assert_eq!(history.applied, [1, 3]);
+    assert!(history.undone.is_empty());
+    assert_eq!(
+        history.changes.iter().map(|e| e.id).collect::<Vec<_>>(),
+        [1, 4]
+    );
+}
+
+type Progress = Vec<(Option<String>, Option<String>)>;
+
+async fn progress(app: &TestApp) -> (Progress, Progress) {
+    let entries = sqlx::query_as("SELECT started_at, ended_at FROM timeplan ORDER BY id")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    let acts = sqlx::query_as("SELECT started_at, ended_at FROM acts ORDER BY id")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    (entries, acts)
+}
+
+async fn command(app: &TestApp, admin: Uuid, command: &str) {
+    let (status, _) = app
+        .post(Some(admin), &format!("/api/command/{command}"), json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn backward_and_redo_restore_the_progress() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+    let owner = app.create_user("owner", false).await;
+    let club = app.create_club(owner, "RSV Heimstadt").await;
+    app.create_starter(club, "Anna").await;
+
+    let initial = progress(&app).await;
+    command(&app, admin, "timeplan_forward").await;
+    let first = progress(&app).await;
+    assert_ne!(first, initial);
+    command(&app, admin, "timeplan_forward").await;
+    let second = progress(&app).await;
+    assert_ne!(second, first);
+
+    command(&app, admin, "timeplan_backward").await;
+    assert_eq!(progress(&app).await, first);
+    command(&app, admin, "timeplan_backward").await;
+    assert_eq!(progress(&app).await, initial);
+    // Nothing left to undo
+    command(&app, admin, "timeplan_backward").await;
+    assert_eq!(progress(&app).await, initial);
+
+    command(&app, admin, "timeplan_redo").await;
+    assert_eq!(progress(&app).await, first);
+    command(&app, admin, "timeplan_redo").await;
+    assert_eq!(progress(&app).await, second);
+
+    // The history is kept
+    let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM timeplan_events")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert!(events >= 6);
+}
+
+#[tokio::test]
+async fn forward_after_backward_discards_redo() {
+    let app = TestApp::new().await;
+    let admin = app.create_user("admin", true).await;
+
+    command(&app, admin, "timeplan_forward").await;
+    command(&app, admin, "timeplan_forward").await;
+    let second = progress(&app).await;
+    command(&app, admin, "timeplan_backward").await;
+    command(&app, admin, "timeplan_forward").await;
+    let forwarded = progress(&app).await;
+
+    command(&app, admin, "timeplan_redo").await;
+    assert_eq!(progress(&app).await, forwarded);
+    // Same entries are done, only the times differ
+    assert_eq!(
+        forwarded
+            .0
+            .iter()
+            .map(|(s, e)| (s.is_some(), e.is_some()))
+            .collect::<Vec<_>>(),
+        second
+            .0
+            .iter()
+            .map(|(s, e)| (s.is_some(), e.is_some()))
+            .collect::<Vec<_>>(),
+    );
+}
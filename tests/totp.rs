@@ -0,0 +1,230 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use serde_json::json;
+use totp_rs::TOTP;
+
+/// The second factor state reported by `whoami`.
+async fn reported_second_factor(app: &TestApp, jwt: &str) -> String {
+    let (status, body, _) = app
+        .get_with_cookies(&[("jwt", jwt)], "/api/query/whoami")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    body["second_factor"].as_str().unwrap().to_string()
+}
+
+/// Enroll with the session and return the authenticator and the recovery
+/// codes.
+async fn enroll(app: &TestApp, jwt: &str) -> (TOTP, Vec<String>) {
+    let (status, body, _) = app
+        .post_with_cookies(
+            &[("jwt", jwt)],
+            "/api/command/start_totp_enrollment",
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let authenticator = TOTP::from_url(body["otpauth_url"].as_str().unwrap()).unwrap();
+    assert_eq!(authenticator.get_secret_base32(), body["secret"]);
+
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", jwt)],
+            "/api/command/confirm_totp_enrollment",
+            json!({ "code": "000000" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, body, _) = app
+        .post_with_cookies(
+            &[("jwt", jwt)],
+            "/api/command/confirm_totp_enrollment",
+            json!({ "code": authenticator.generate_current().unwrap() }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let recovery_codes = serde_json::from_value(body["recovery_codes"].clone()).unwrap();
+    (authenticator, recovery_codes)
+}
+
+#[tokio::test]
+async fn enrolled_users_need_a_code_after_the_password() {
+    let app = TestApp::new().await;
+    app.register("trainer").await;
+    let (login, cookies) = app.login("trainer").await;
+    let jwt = cookies["jwt"].clone();
+    assert_eq!(login["second_factor"], "not_required");
+    let (authenticator, recovery_codes) = enroll(&app, &jwt).await;
+    assert_eq!(recovery_codes.len(), 10);
+    let stored: String = sqlx::query_scalar(
+        "SELECT totp_recovery_codes FROM users WHERE email = 'trainer@example.com'",
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert!(!stored.contains(&recovery_codes[0]));
+
+    let (login, cookies) = app.login("trainer").await;
+    let jwt = cookies["jwt"].clone();
+    assert_eq!(login["second_factor"], "required");
+    assert_eq!(reported_second_factor(&app, &jwt).await, "required");
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/create_club",
+            json!({ "name": "Club" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // The code of the enrollment can't be used again
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/verify_totp",
+            json!({ "code": authenticator.generate_current().unwrap() }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(reported_second_factor(&app, &jwt).await, "required");
+
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/verify_totp",
+            json!({ "code": recovery_codes[0].to_uppercase() }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reported_second_factor(&app, &jwt).await, "verified");
+
+    // Recovery codes work once
+    let (_, cookies) = app.login("trainer").await;
+    let jwt = cookies["jwt"].clone();
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/verify_totp",
+            json!({ "code": recovery_codes[0] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/verify_totp",
+            json!({ "code": recovery_codes[1] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/disable_totp",
+            json!({ "code": recovery_codes[2] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (login, _) = app.login("trainer").await;
+    assert_eq!(login["second_factor"], "not_required");
+}
+
+#[tokio::test]
+async fn admins_have_to_enroll_before_anything_else() {
+    let app = TestApp::new().await;
+    app.register("admin").await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE email = 'admin@example.com'")
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let (login, cookies) = app.login("admin").await;
+    let jwt = cookies["jwt"].clone();
+    assert_eq!(login["second_factor"], "enrollment_required");
+    assert_eq!(
+        reported_second_factor(&app, &jwt).await,
+        "enrollment_required"
+    );
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/set_music_operator",
+            json!({ "user_id": uuid::Uuid::now_v7(), "is_music_operator": true }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (authenticator, recovery_codes) = enroll(&app, &jwt).await;
+    assert_eq!(reported_second_factor(&app, &jwt).await, "verified");
+
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/disable_totp",
+            json!({ "code": recovery_codes[0] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // A new login needs the code, and can't enroll again without it
+    let (login, cookies) = app.login("admin").await;
+    let jwt = cookies["jwt"].clone();
+    assert_eq!(login["second_factor"], "required");
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/start_totp_enrollment",
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    for _ in 0..6 {
+        let (status, _, _) = app
+            .post_with_cookies(
+                &[("jwt", &jwt)],
+                "/api/command/verify_totp",
+                json!({ "code": "000000" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    // Guessing codes is limited like passwords
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/verify_totp",
+            json!({ "code": authenticator.generate_current().unwrap() }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn disabling_is_limited_like_the_login() {
+    let app = TestApp::new().await;
+    app.register("trainer").await;
+    let (_, cookies) = app.login("trainer").await;
+    let jwt = cookies["jwt"].clone();
+    let (_, recovery_codes) = enroll(&app, &jwt).await;
+
+    for _ in 0..6 {
+        let (status, _, _) = app
+            .post_with_cookies(
+                &[("jwt", &jwt)],
+                "/api/command/disable_totp",
+                json!({ "code": "000000" }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    let (status, _, _) = app
+        .post_with_cookies(
+            &[("jwt", &jwt)],
+            "/api/command/disable_totp",
+            json!({ "code": recovery_codes[0] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}